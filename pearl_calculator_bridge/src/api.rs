@@ -1,17 +1,31 @@
-use crate::inputs::{CalculationInput, PearlTraceInput, RawTraceInput};
-use crate::outputs::{PearlTraceOutput, TNTResultOutput};
+use crate::inputs::{
+    CalculationInput, GroupCalculationInput, LookupBuildInput, PearlTraceInput, RawTraceInput,
+    RobustnessInput,
+};
+use crate::outputs::{
+    ChunkReportOutput, PearlTraceOutput, RobustResultOutput, SolveProgressOutput, TNTResultOutput,
+    TNTSearchOutput,
+};
 
 use pearl_calculator_core::calculation::calculation::{
+    calculate_chunk_report as core_calculate_chunk_report,
     calculate_pearl_trace as core_calculate_pearl_trace,
     calculate_raw_trace as core_calculate_raw_trace,
-    calculate_tnt_amount as core_calculate_tnt_amount,
+    calculate_tnt_amount as core_calculate_tnt_amount, search_group_tnt_amount,
+    search_group_tnt_amount_with_progress, search_tnt_amount_with_progress,
 };
+use pearl_calculator_core::calculation::lookup::LookupTable;
+use pearl_calculator_core::calculation::progress::CancellationToken;
+use pearl_calculator_core::calculation::ranking::{Ranking, rank_results};
+use pearl_calculator_core::calculation::results::TNTSearch;
+use pearl_calculator_core::calculation::robustness::rank_by_robustness;
 use pearl_calculator_core::physics::world::space::Space3D;
 pub fn calculate_tnt_amount(input: CalculationInput) -> Result<Vec<TNTResultOutput>, String> {
-    let version = input.get_version()?;
+    let profile = input.get_profile()?;
     let cannon = input.get_cannon()?;
     let destination = input.get_destination();
     let origin = input.get_origin();
+    let world = input.get_world()?;
     let results = core_calculate_tnt_amount(
         &cannon,
         destination,
@@ -19,9 +33,11 @@ pub fn calculate_tnt_amount(input: CalculationInput) -> Result<Vec<TNTResultOutp
         input.max_vertical_tnt,
         input.max_ticks,
         input.max_distance,
-        version,
+        &world,
+        &profile,
         input.uses_plane_intercept_y(),
     );
+    let results = rank_results(results, &input.ranking.unwrap_or_default());
 
     Ok(results
         .into_iter()
@@ -29,20 +45,146 @@ pub fn calculate_tnt_amount(input: CalculationInput) -> Result<Vec<TNTResultOutp
         .collect())
 }
 
+/// Solves a cannon with any number of named charge groups. Each result's `counts` has one
+/// entry per group, in the order the groups were given.
+pub fn calculate_group_tnt_amount(input: GroupCalculationInput) -> Result<TNTSearchOutput, String> {
+    let profile = input.get_profile()?;
+    let cannon = input.get_cannon()?;
+    let origin = input.get_origin();
+    let world = input.get_world()?;
+    let search = search_group_tnt_amount(
+        &cannon,
+        input.get_destination(),
+        &input.get_options(&world, &profile),
+        input.aims_3d(),
+    );
+
+    Ok(search_output(
+        search,
+        &input.ranking.unwrap_or_default(),
+        origin,
+    ))
+}
+
+/// Solves like [`calculate_group_tnt_amount`], passing the closest results so far to
+/// `on_progress` as candidates are simulated. Once `cancel` is set, returns the results found so
+/// far.
+pub fn calculate_group_tnt_amount_with_progress(
+    input: GroupCalculationInput,
+    cancel: &CancellationToken,
+    on_progress: &mut dyn FnMut(&SolveProgressOutput),
+) -> Result<TNTSearchOutput, String> {
+    let profile = input.get_profile()?;
+    let cannon = input.get_cannon()?;
+    let origin = input.get_origin();
+    let world = input.get_world()?;
+    let search = search_group_tnt_amount_with_progress(
+        &cannon,
+        input.get_destination(),
+        &input.get_options(&world, &profile),
+        input.aims_3d(),
+        cancel,
+        &mut |progress| on_progress(&SolveProgressOutput::from_core(progress, origin)),
+    );
+
+    Ok(search_output(
+        search,
+        &input.ranking.unwrap_or_default(),
+        origin,
+    ))
+}
+
+/// Solves like [`calculate_tnt_amount`], passing the closest results so far to `on_progress` as
+/// candidates are simulated. Once `cancel` is set, returns the results found so far.
+pub fn calculate_tnt_amount_with_progress(
+    input: CalculationInput,
+    cancel: &CancellationToken,
+    on_progress: &mut dyn FnMut(&SolveProgressOutput),
+) -> Result<TNTSearchOutput, String> {
+    let profile = input.get_profile()?;
+    let cannon = input.get_cannon()?;
+    let origin = input.get_origin();
+    let world = input.get_world()?;
+    let search = search_tnt_amount_with_progress(
+        &cannon,
+        input.get_destination(),
+        &input.get_options(&world, &profile),
+        cancel,
+        &mut |progress| on_progress(&SolveProgressOutput::from_core(progress, origin)),
+    );
+
+    Ok(search_output(
+        search,
+        &input.ranking.unwrap_or_default(),
+        origin,
+    ))
+}
+
+fn search_output(search: TNTSearch, ranking: &Ranking, origin: Space3D) -> TNTSearchOutput {
+    TNTSearchOutput {
+        results: rank_results(search.results, ranking)
+            .into_iter()
+            .map(|result| TNTResultOutput::from_core(result, origin))
+            .collect(),
+        cancelled: search.cancelled,
+        truncated: search.truncated,
+    }
+}
+
+/// Solves for the destination, then reorders the closest results by how often they still land
+/// within tolerance once dispenser jitter is sampled.
+pub fn analyse_robustness(input: RobustnessInput) -> Result<Vec<RobustResultOutput>, String> {
+    let calculation = &input.calculation;
+    let profile = calculation.get_profile()?;
+    let cannon = calculation.get_cannon()?;
+    let destination = calculation.get_destination();
+    let origin = calculation.get_origin();
+    let world = calculation.get_world()?;
+    let results = core_calculate_tnt_amount(
+        &cannon,
+        destination,
+        calculation.max_tnt,
+        calculation.max_vertical_tnt,
+        calculation.max_ticks,
+        calculation.max_distance,
+        &world,
+        &profile,
+        calculation.uses_plane_intercept_y(),
+    );
+    let candidates = rank_results(results, &calculation.ranking.clone().unwrap_or_default())
+        .into_iter()
+        .take(input.candidates.unwrap_or(10))
+        .collect();
+
+    Ok(rank_by_robustness(
+        &cannon,
+        candidates,
+        destination,
+        &input.get_jitter(),
+        &world,
+        &profile,
+    )
+    .into_iter()
+    .map(|(result, report)| RobustResultOutput::from_core(result, report, origin))
+    .collect())
+}
+
 pub fn calculate_pearl_trace(input: PearlTraceInput) -> Result<PearlTraceOutput, String> {
-    let version = input.get_version()?;
+    let profile = input.get_profile()?;
     let cannon = input.get_cannon()?;
     let flight_direction = input.get_flight_direction()?;
+    let world = input.get_world()?;
 
     let result = core_calculate_pearl_trace(
         &cannon,
         input.red_tnt,
         input.blue_tnt,
         input.vertical_tnt_amount.unwrap_or(0),
+        input.stage_tnt.as_deref().unwrap_or_default(),
         flight_direction,
         10000,
-        &[],
-        version,
+        &world,
+        &profile,
     )
     .ok_or_else(|| "Pearl trace calculation failed".to_string())?;
 
@@ -53,8 +195,32 @@ pub fn calculate_pearl_trace(input: PearlTraceInput) -> Result<PearlTraceOutput,
     ))
 }
 
+pub fn calculate_chunk_report(input: PearlTraceInput) -> Result<ChunkReportOutput, String> {
+    let profile = input.get_profile()?;
+    let cannon = input.get_cannon()?;
+    let flight_direction = input.get_flight_direction()?;
+    let world = input.get_world()?;
+
+    let result = core_calculate_pearl_trace(
+        &cannon,
+        input.red_tnt,
+        input.blue_tnt,
+        input.vertical_tnt_amount.unwrap_or(0),
+        input.stage_tnt.as_deref().unwrap_or_default(),
+        flight_direction,
+        10000,
+        &world,
+        &profile,
+    )
+    .ok_or_else(|| "Chunk report calculation failed".to_string())?;
+    let report = core_calculate_chunk_report(&result, input.get_origin());
+
+    Ok(ChunkReportOutput::from_core(report))
+}
+
 pub fn calculate_raw_trace(input: RawTraceInput) -> Result<PearlTraceOutput, String> {
-    let version = input.get_version()?;
+    let profile = input.get_profile()?;
+    let world = input.get_world()?;
 
     let pearl_pos = Space3D::new(input.pearl_x, input.pearl_y, input.pearl_z);
     let pearl_motion = Space3D::new(
@@ -63,15 +229,21 @@ pub fn calculate_raw_trace(input: RawTraceInput) -> Result<PearlTraceOutput, Str
         input.pearl_motion_z,
     );
 
-    let tnt_charges: Vec<(Space3D, u32)> = input
+    let tnt_charges = input
         .tnt_groups
         .iter()
-        .map(|g| (Space3D::new(g.x, g.y, g.z), g.amount))
+        .map(|group| group.to_charge())
         .collect();
 
-    let result =
-        core_calculate_raw_trace(pearl_pos, pearl_motion, tnt_charges, 10000, &[], version)
-            .ok_or_else(|| "Raw trace calculation failed".to_string())?;
+    let result = core_calculate_raw_trace(
+        pearl_pos,
+        pearl_motion,
+        tnt_charges,
+        10000,
+        &world,
+        &profile,
+    )
+    .ok_or_else(|| "Raw trace calculation failed".to_string())?;
 
     Ok(PearlTraceOutput::from_core(
         result,
@@ -79,3 +251,49 @@ pub fn calculate_raw_trace(input: RawTraceInput) -> Result<PearlTraceOutput, Str
         Space3D::default(),
     ))
 }
+
+pub fn build_lookup_table(input: LookupBuildInput) -> Result<Vec<u8>, String> {
+    let calculation = &input.calculation;
+    let profile = calculation.get_profile()?;
+    let cannon = calculation.get_cannon()?;
+    let world = calculation.get_world()?;
+
+    let table = LookupTable::build(
+        &cannon,
+        input.radius,
+        input.step,
+        &calculation.get_options(&world, &profile),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(table.to_bytes())
+}
+
+pub fn load_lookup_table(bytes: &[u8]) -> Result<LookupTable, String> {
+    LookupTable::from_bytes(bytes).map_err(|e| e.to_string())
+}
+
+/// Answers from `table` when it was built for this cannon and samples the destination, and
+/// solves live otherwise. The table only holds the closest solution, so a hit returns that one
+/// result, and requests ranked any other way are always solved live.
+pub fn calculate_tnt_amount_with_table(
+    input: CalculationInput,
+    table: Option<&LookupTable>,
+) -> Result<Vec<TNTResultOutput>, String> {
+    let default_ranking = input
+        .ranking
+        .as_ref()
+        .is_none_or(|ranking| *ranking == Ranking::default());
+    if let Some(table) = table
+        && default_ranking
+    {
+        let profile = input.get_profile()?;
+        let cannon = input.get_cannon()?;
+        let world = input.get_world()?;
+        if table.matches(&cannon, &input.get_options(&world, &profile))
+            && let Some(result) = table.resolve(&cannon, input.get_destination(), &world, &profile)
+        {
+            return Ok(vec![TNTResultOutput::from_core(result, input.get_origin())]);
+        }
+    }
+    calculate_tnt_amount(input)
+}
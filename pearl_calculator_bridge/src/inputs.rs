@@ -1,6 +1,17 @@
-use pearl_calculator_core::calculation::inputs::{Cannon, Pearl};
+use pearl_calculator_core::calculation::calculation::SolveOptions;
+use pearl_calculator_core::calculation::inputs::{
+    Cannon, CannonStage, GeneralData, GroupCannon, Pearl, TNT, TNTGroup,
+};
+use pearl_calculator_core::calculation::ranking::Ranking;
+use pearl_calculator_core::calculation::robustness::JitterModel;
+use pearl_calculator_core::physics::entities::explosion::ExplosionKind;
 
 use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::entities::profile::PhysicsProfile;
+use pearl_calculator_core::physics::entities::projectile::ProjectileKind;
+use pearl_calculator_core::physics::world::chunks::{ChunkLoading, LoadTicket};
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::dimension::Dimension;
 use pearl_calculator_core::physics::world::direction::Direction;
 use pearl_calculator_core::physics::world::layout_direction::LayoutDirection;
 use pearl_calculator_core::physics::world::space::Space3D;
@@ -34,15 +45,25 @@ pub struct CalculationInput {
     pub max_ticks: u32,
     pub max_distance: f64,
     pub version: String,
+    /// A custom physics profile that replaces the built-in one named by `version`.
+    pub profile: Option<PhysicsProfile>,
 
     pub vertical_tnt: Option<Space3DInput>,
     pub max_vertical_tnt: Option<u32>,
     pub mode: Option<String>,
+    pub dimension: Option<String>,
+    pub projectile: Option<String>,
+    pub stages: Option<Vec<StageInput>>,
+    pub aim_fuse: Option<u32>,
+    /// How results are ordered or filtered; closest landing first when absent.
+    pub ranking: Option<Ranking>,
+    /// Chunk tickets loading the flight path; every chunk ticks when absent.
+    pub chunk_tickets: Option<Vec<LoadTicketInput>>,
 }
 
 impl CalculationInput {
-    pub fn get_version(&self) -> Result<PearlVersion, String> {
-        parse_version(&self.version)
+    pub fn get_profile(&self) -> Result<PhysicsProfile, String> {
+        resolve_profile(&self.version, self.profile.as_ref())
     }
 
     pub fn get_cannon(&self) -> Result<Cannon, String> {
@@ -60,6 +81,9 @@ impl CalculationInput {
             &self.default_blue_direction,
             self.vertical_tnt,
             self.mode.clone(),
+            self.projectile.as_deref(),
+            self.stages.as_deref().unwrap_or_default(),
+            self.aim_fuse,
         )
     }
 
@@ -71,10 +95,34 @@ impl CalculationInput {
         )
     }
 
+    pub fn get_options<'a>(
+        &self,
+        world_collisions: &'a CollisionWorld,
+        profile: &'a PhysicsProfile,
+    ) -> SolveOptions<'a> {
+        SolveOptions {
+            max_tnt: self.max_tnt,
+            max_vertical_tnt: self.max_vertical_tnt,
+            max_ticks: self.max_ticks,
+            max_distance: self.max_distance,
+            world_collisions,
+            profile,
+            plane_intercept_y: self.uses_plane_intercept_y(),
+        }
+    }
+
     pub fn get_origin(&self) -> Space3D {
         Space3D::new(self.pearl_x, 0.0, self.pearl_z)
     }
 
+    pub fn get_world(&self) -> Result<CollisionWorld, String> {
+        build_world(
+            self.dimension.as_deref(),
+            self.chunk_tickets.as_deref(),
+            self.get_origin(),
+        )
+    }
+
     pub fn uses_plane_intercept_y(&self) -> bool {
         uses_plane_intercept_y(self.mode.as_deref(), self.destination_y)
     }
@@ -86,6 +134,7 @@ pub struct PearlTraceInput {
     pub red_tnt: u32,
     pub blue_tnt: u32,
     pub vertical_tnt_amount: Option<u32>,
+    pub stage_tnt: Option<Vec<u32>>,
     pub pearl_x: f64,
     pub pearl_y: f64,
     pub pearl_z: f64,
@@ -103,13 +152,19 @@ pub struct PearlTraceInput {
     pub destination_z: f64,
     pub direction: Option<String>,
     pub version: String,
+    pub profile: Option<PhysicsProfile>,
     pub vertical_tnt: Option<Space3DInput>,
     pub mode: Option<String>,
+    pub dimension: Option<String>,
+    pub projectile: Option<String>,
+    pub stages: Option<Vec<StageInput>>,
+    pub aim_fuse: Option<u32>,
+    pub chunk_tickets: Option<Vec<LoadTicketInput>>,
 }
 
 impl PearlTraceInput {
-    pub fn get_version(&self) -> Result<PearlVersion, String> {
-        parse_version(&self.version)
+    pub fn get_profile(&self) -> Result<PhysicsProfile, String> {
+        resolve_profile(&self.version, self.profile.as_ref())
     }
 
     pub fn get_cannon(&self) -> Result<Cannon, String> {
@@ -127,6 +182,9 @@ impl PearlTraceInput {
             &self.default_blue_direction,
             self.vertical_tnt,
             self.mode.clone(),
+            self.projectile.as_deref(),
+            self.stages.as_deref().unwrap_or_default(),
+            self.aim_fuse,
         )
     }
 
@@ -150,6 +208,14 @@ impl PearlTraceInput {
     pub fn get_origin(&self) -> Space3D {
         Space3D::new(self.pearl_x, 0.0, self.pearl_z)
     }
+
+    pub fn get_world(&self) -> Result<CollisionWorld, String> {
+        build_world(
+            self.dimension.as_deref(),
+            self.chunk_tickets.as_deref(),
+            self.get_origin(),
+        )
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, Copy)]
@@ -165,6 +231,33 @@ impl From<Space3DInput> for Space3D {
     }
 }
 
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct StageInput {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub fuse: u32,
+    pub max_tnt: u32,
+}
+
+/// A chunk ticket at an absolute block position.
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct LoadTicketInput {
+    pub x: f64,
+    pub z: f64,
+    pub level: u32,
+}
+
+impl LoadTicketInput {
+    pub fn to_ticket(&self) -> LoadTicket {
+        LoadTicket {
+            position: Space3D::new(self.x, 0.0, self.z),
+            level: self.level,
+        }
+    }
+}
+
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TntGroupInput {
@@ -172,6 +265,21 @@ pub struct TntGroupInput {
     pub y: f64,
     pub z: f64,
     pub amount: u32,
+    /// Tick on which the group explodes, counted from the pearl's first tick.
+    pub fuse: Option<u32>,
+}
+
+impl TntGroupInput {
+    pub fn to_charge(&self) -> TNT {
+        TNT {
+            position: Space3D::new(self.x, self.y, self.z),
+            fuse: self.fuse.unwrap_or(0),
+            motion: Space3D::default(),
+            source: ExplosionKind::Tnt,
+            amount: self.amount,
+            anchored: true,
+        }
+    }
 }
 
 #[derive(serde::Deserialize)]
@@ -185,14 +293,242 @@ pub struct RawTraceInput {
     pub pearl_motion_z: f64,
     pub tnt_groups: Vec<TntGroupInput>,
     pub version: String,
+    pub profile: Option<PhysicsProfile>,
+    pub dimension: Option<String>,
+    pub chunk_tickets: Option<Vec<LoadTicketInput>>,
 }
 
 impl RawTraceInput {
-    pub fn get_version(&self) -> Result<PearlVersion, String> {
-        parse_version(&self.version)
+    pub fn get_data(&self) -> GeneralData {
+        GeneralData {
+            pearl_position: Space3D::new(self.pearl_x, self.pearl_y, self.pearl_z),
+            pearl_motion: Space3D::new(
+                self.pearl_motion_x,
+                self.pearl_motion_y,
+                self.pearl_motion_z,
+            ),
+            tnt_charges: self
+                .tnt_groups
+                .iter()
+                .map(|group| group.to_charge())
+                .collect(),
+            projectile: ProjectileKind::EnderPearl,
+        }
+    }
+
+    pub fn get_profile(&self) -> Result<PhysicsProfile, String> {
+        resolve_profile(&self.version, self.profile.as_ref())
+    }
+
+    pub fn get_world(&self) -> Result<CollisionWorld, String> {
+        build_world(
+            self.dimension.as_deref(),
+            self.chunk_tickets.as_deref(),
+            Space3D::default(),
+        )
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupInput {
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub min_tnt: Option<u32>,
+    pub max_tnt: u32,
+    /// Flight directions the group fires for; every direction when absent.
+    pub directions: Option<Vec<Direction>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupCalculationInput {
+    pub pearl_x: f64,
+    pub pearl_y: f64,
+    pub pearl_z: f64,
+    pub pearl_motion_x: f64,
+    pub pearl_motion_y: f64,
+    pub pearl_motion_z: f64,
+    pub cannon_y: f64,
+    pub groups: Vec<GroupInput>,
+
+    pub destination_x: f64,
+    pub destination_y: Option<f64>,
+    pub destination_z: f64,
+
+    pub max_ticks: u32,
+    pub max_distance: f64,
+    pub version: String,
+    pub profile: Option<PhysicsProfile>,
+    pub mode: Option<String>,
+    pub dimension: Option<String>,
+    pub projectile: Option<String>,
+    pub ranking: Option<Ranking>,
+    pub chunk_tickets: Option<Vec<LoadTicketInput>>,
+}
+
+impl GroupCalculationInput {
+    pub fn get_profile(&self) -> Result<PhysicsProfile, String> {
+        resolve_profile(&self.version, self.profile.as_ref())
+    }
+
+    pub fn get_cannon(&self) -> Result<GroupCannon, String> {
+        if self.groups.is_empty() {
+            return Err("A cannon needs at least one TNT group".to_string());
+        }
+        if let Some(group) = self
+            .groups
+            .iter()
+            .find(|group| group.min_tnt.unwrap_or(0) > group.max_tnt)
+        {
+            return Err(format!(
+                "Group {} has more minimum than maximum TNT",
+                group.name
+            ));
+        }
+
+        let y_offset = self.cannon_y - self.pearl_y.floor();
+        Ok(GroupCannon {
+            pearl: Pearl {
+                position: Space3D::new(0.0, self.pearl_y + y_offset, 0.0),
+                motion: Space3D::new(
+                    self.pearl_motion_x,
+                    self.pearl_motion_y,
+                    self.pearl_motion_z,
+                ),
+            },
+            groups: self
+                .groups
+                .iter()
+                .map(|group| TNTGroup {
+                    name: group.name.clone(),
+                    position: Space3D::new(group.x, group.y + y_offset, group.z),
+                    min_tnt: group.min_tnt.unwrap_or(0),
+                    max_tnt: group.max_tnt,
+                    directions: group.directions.clone().unwrap_or_default(),
+                })
+                .collect(),
+            projectile: parse_projectile(self.projectile.as_deref())?,
+        })
+    }
+
+    pub fn get_destination(&self) -> Space3D {
+        Space3D::new(
+            self.destination_x - self.pearl_x,
+            self.destination_y.unwrap_or(0.0),
+            self.destination_z - self.pearl_z,
+        )
+    }
+
+    pub fn get_origin(&self) -> Space3D {
+        Space3D::new(self.pearl_x, 0.0, self.pearl_z)
+    }
+
+    pub fn get_world(&self) -> Result<CollisionWorld, String> {
+        build_world(
+            self.dimension.as_deref(),
+            self.chunk_tickets.as_deref(),
+            self.get_origin(),
+        )
+    }
+
+    pub fn uses_plane_intercept_y(&self) -> bool {
+        uses_plane_intercept_y(self.mode.as_deref(), self.destination_y)
+    }
+
+    /// Group bounds stand in for the TNT limits of a four-corner solve.
+    pub fn get_options<'a>(
+        &self,
+        world_collisions: &'a CollisionWorld,
+        profile: &'a PhysicsProfile,
+    ) -> SolveOptions<'a> {
+        SolveOptions {
+            max_tnt: 0,
+            max_vertical_tnt: None,
+            max_ticks: self.max_ticks,
+            max_distance: self.max_distance,
+            world_collisions,
+            profile,
+            plane_intercept_y: self.uses_plane_intercept_y(),
+        }
+    }
+
+    /// Whether the solver should aim at the destination height as well as its column.
+    pub fn aims_3d(&self) -> bool {
+        self.destination_y.is_some() && matches!(self.mode.as_deref(), Some("Vector3D"))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LookupBuildInput {
+    #[serde(flatten)]
+    pub calculation: CalculationInput,
+    /// Blocks from the pearl that the table covers.
+    pub radius: u32,
+    /// Spacing between the sampled blocks.
+    pub step: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RobustnessInput {
+    #[serde(flatten)]
+    pub calculation: CalculationInput,
+    /// How many of the closest results to analyse.
+    pub candidates: Option<usize>,
+    pub trials: Option<u32>,
+    pub tolerance: Option<f64>,
+    pub position_error: Option<f64>,
+    pub horizontal_speed: Option<f64>,
+    pub jump_speed: Option<f64>,
+    pub fuse_ticks: Option<u32>,
+    pub seed: Option<u64>,
+}
+
+impl RobustnessInput {
+    pub fn get_jitter(&self) -> JitterModel {
+        let default = JitterModel::default();
+        JitterModel {
+            horizontal_speed: self.horizontal_speed.unwrap_or(default.horizontal_speed),
+            jump_speed: self.jump_speed.unwrap_or(default.jump_speed),
+            fuse_ticks: self.fuse_ticks.unwrap_or(default.fuse_ticks),
+            position_error: self.position_error.unwrap_or(default.position_error),
+            trials: self.trials.unwrap_or(default.trials),
+            tolerance: self.tolerance.unwrap_or(default.tolerance),
+            seed: self.seed.unwrap_or(default.seed),
+        }
     }
 }
 
+/// Changes to one entity in a running simulator. Unset fields keep their current value.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityEditInput {
+    /// Index of the charge to edit, or the pearl when unset.
+    pub tnt_index: Option<usize>,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub z: Option<f64>,
+    pub motion_x: Option<f64>,
+    pub motion_y: Option<f64>,
+    pub motion_z: Option<f64>,
+    /// Tick on which the charge explodes. Ignored for the pearl.
+    pub fuse: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplosionInput {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub amount: Option<u32>,
+    pub source: Option<ExplosionKind>,
+}
+
 fn parse_version(s: &str) -> Result<PearlVersion, String> {
     match s {
         "Legacy" => Ok(PearlVersion::Legacy),
@@ -202,6 +538,49 @@ fn parse_version(s: &str) -> Result<PearlVersion, String> {
     }
 }
 
+fn resolve_profile(
+    version: &str,
+    profile: Option<&PhysicsProfile>,
+) -> Result<PhysicsProfile, String> {
+    match profile {
+        Some(profile) => Ok(profile.clone()),
+        None => parse_version(version).map(PhysicsProfile::from),
+    }
+}
+
+fn build_world(
+    dimension: Option<&str>,
+    chunk_tickets: Option<&[LoadTicketInput]>,
+    origin: Space3D,
+) -> Result<CollisionWorld, String> {
+    let world = match dimension {
+        None => CollisionWorld::default(),
+        Some("Overworld") => CollisionWorld::default().with_dimension(Dimension::Overworld, origin),
+        Some("Nether") => CollisionWorld::default().with_dimension(Dimension::Nether, origin),
+        Some("End") => CollisionWorld::default().with_dimension(Dimension::End, origin),
+        Some(_) => return Err("Invalid dimension".to_string()),
+    };
+    Ok(match chunk_tickets {
+        None => world,
+        Some(tickets) => world.with_chunk_loading(
+            ChunkLoading::new(tickets.iter().map(|ticket| ticket.to_ticket()).collect()),
+            origin,
+        ),
+    })
+}
+
+fn parse_projectile(s: Option<&str>) -> Result<ProjectileKind, String> {
+    match s {
+        None | Some("EnderPearl") => Ok(ProjectileKind::EnderPearl),
+        Some("Snowball") => Ok(ProjectileKind::Snowball),
+        Some("Egg") => Ok(ProjectileKind::Egg),
+        Some("Arrow") => Ok(ProjectileKind::Arrow),
+        Some("Trident") => Ok(ProjectileKind::Trident),
+        Some("WindCharge") => Ok(ProjectileKind::WindCharge),
+        Some(_) => Err("Invalid projectile".to_string()),
+    }
+}
+
 fn uses_plane_intercept_y(mode: Option<&str>, destination_y: Option<f64>) -> bool {
     matches!(destination_y, Some(_)) && !matches!(mode, Some("Vector3D"))
 }
@@ -240,6 +619,9 @@ fn build_cannon(
     blue_dir: &str,
     vert: Option<Space3DInput>,
     mode_str: Option<String>,
+    projectile: Option<&str>,
+    stages: &[StageInput],
+    aim_fuse: Option<u32>,
 ) -> Result<Cannon, String> {
     let y_offset = cy - py.floor();
     let default_red_direction = parse_layout_direction(red_dir);
@@ -258,6 +640,19 @@ fn build_cannon(
 
     let (red_override, blue_override) = (None, None);
 
+    let aim_fuse = aim_fuse.unwrap_or(0);
+    if stages.iter().any(|stage| stage.fuse >= aim_fuse) {
+        return Err("Every stage must explode before the aimers".to_string());
+    }
+    let stages = stages
+        .iter()
+        .map(|stage| CannonStage {
+            position: Space3D::new(stage.x, stage.y + y_offset, stage.z),
+            fuse: stage.fuse,
+            max_tnt: stage.max_tnt,
+        })
+        .collect();
+
     Ok(Cannon {
         pearl: Pearl {
             position: Space3D::new(0.0, py + y_offset, 0.0),
@@ -273,5 +668,8 @@ fn build_cannon(
         south_east_tnt: with_y_offset(se),
         default_red_duper: default_red_direction,
         default_blue_duper: default_blue_direction,
+        projectile: parse_projectile(projectile)?,
+        stages,
+        aim_fuse,
     })
 }
@@ -1,3 +1,4 @@
 pub mod api;
 pub mod inputs;
 pub mod outputs;
+pub mod session;
@@ -1,4 +1,9 @@
-use pearl_calculator_core::calculation::results::{CalculationResult, TNTResult};
+use pearl_calculator_core::calculation::progress::SolveProgress;
+use pearl_calculator_core::calculation::results::{
+    CalculationResult, ChunkReport, PearlOutcome, TNTResult, TickRecord,
+};
+use pearl_calculator_core::calculation::robustness::RobustnessReport;
+use pearl_calculator_core::calculation::simulator::Simulator;
 use pearl_calculator_core::physics::world::space::Space3D;
 use serde::{Deserialize, Serialize};
 
@@ -9,12 +14,17 @@ pub struct TNTResultOutput {
     pub blue: u32,
     pub red: u32,
     pub vertical: u32,
+    pub stages: Vec<u32>,
+    /// One entry per group of a group cannon, in the order they were given.
+    pub counts: Vec<u32>,
     pub yaw: f64,
     pub pitch: f64,
     pub total: u32,
     pub pearl_end_pos: Space3DOutput,
     pub pearl_end_motion: Space3DOutput,
     pub direction: String,
+    pub peak_y: f64,
+    pub sensitivity: f64,
 }
 
 impl From<TNTResult> for TNTResultOutput {
@@ -33,6 +43,8 @@ impl TNTResultOutput {
             blue: r.blue,
             red: r.red,
             vertical: r.vertical,
+            stages: r.stages,
+            counts: r.counts,
             yaw: r.yaw,
             pitch: r.pitch,
             total: r.total,
@@ -47,6 +59,70 @@ impl TNTResultOutput {
                 z: r.pearl_end_motion.z,
             },
             direction: format!("{:?}", r.direction),
+            peak_y: r.peak_y + origin.y,
+            sensitivity: r.sensitivity,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TNTSearchOutput {
+    pub results: Vec<TNTResultOutput>,
+    /// Set when the solve was cancelled and `results` only covers the part searched so far.
+    pub cancelled: bool,
+    /// Set when the search over a group cannon's redundant charges was cut short, so closer
+    /// results may exist.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RobustResultOutput {
+    #[serde(flatten)]
+    pub result: TNTResultOutput,
+    pub trials: u32,
+    pub success_probability: f64,
+    pub mean_error: f64,
+    pub median_error: f64,
+    pub p95_error: f64,
+    pub max_error: f64,
+    pub errors: Vec<f64>,
+}
+
+impl RobustResultOutput {
+    pub fn from_core(result: TNTResult, report: RobustnessReport, origin: Space3D) -> Self {
+        RobustResultOutput {
+            result: TNTResultOutput::from_core(result, origin),
+            trials: report.trials,
+            success_probability: report.success_probability,
+            mean_error: report.mean_error,
+            median_error: report.median_error,
+            p95_error: report.p95_error,
+            max_error: report.max_error,
+            errors: report.errors,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolveProgressOutput {
+    pub direction: String,
+    pub validated: usize,
+    pub total: usize,
+    pub best: Vec<TNTResultOutput>,
+}
+
+impl SolveProgressOutput {
+    pub fn from_core(progress: &SolveProgress, origin: Space3D) -> Self {
+        SolveProgressOutput {
+            direction: format!("{:?}", progress.direction),
+            validated: progress.validated,
+            total: progress.total,
+            best: progress
+                .best
+                .iter()
+                .cloned()
+                .map(|result| TNTResultOutput::from_core(result, origin))
+                .collect(),
         }
     }
 }
@@ -61,6 +137,9 @@ pub struct PearlTraceOutput {
     pub final_motion: Space3DOutput,
     pub distance: f64,
     pub closest_approach: Option<ClosestApproachOutput>,
+    /// Where the pearl froze in a chunk that does not tick entities.
+    pub stalled_at: Option<Space3DOutput>,
+    pub records: Vec<TickRecordOutput>,
 }
 
 impl PearlTraceOutput {
@@ -78,11 +157,10 @@ impl PearlTraceOutput {
         };
 
         let pearl_trace_output: Vec<Space3DOutput> = result
-            .pearl_trace
+            .records
             .iter()
-            .enumerate()
-            .map(|(index, pos)| {
-                let pos = *pos + origin;
+            .map(|record| {
+                let pos = record.position + origin;
                 if let Some((dest_x, dest_z)) = destination {
                     let dx = pos.x - dest_x;
                     let dz = pos.z - dest_z;
@@ -90,7 +168,7 @@ impl PearlTraceOutput {
 
                     if distance < min_distance {
                         min_distance = distance;
-                        closest_tick = index as u32;
+                        closest_tick = record.tick;
                         closest_point = Space3DOutput {
                             x: pos.x,
                             y: pos.y,
@@ -108,15 +186,21 @@ impl PearlTraceOutput {
             .collect();
 
         let pearl_motion_trace_output: Vec<Space3DOutput> = result
-            .pearl_motion_trace
+            .records
             .iter()
-            .map(|pos| Space3DOutput {
-                x: pos.x,
-                y: pos.y,
-                z: pos.z,
+            .map(|record| Space3DOutput {
+                x: record.motion.x,
+                y: record.motion.y,
+                z: record.motion.z,
             })
             .collect();
 
+        let records = result
+            .records
+            .into_iter()
+            .map(|record| TickRecordOutput::from_core(record, origin))
+            .collect();
+
         let (distance, closest_approach) = if destination.is_some() {
             (
                 result.distance,
@@ -131,6 +215,11 @@ impl PearlTraceOutput {
         };
 
         let landing_position = result.landing_position + origin;
+        let stalled_at = (result.outcome == PearlOutcome::Stalled).then_some(Space3DOutput {
+            x: landing_position.x,
+            y: landing_position.y,
+            z: landing_position.z,
+        });
 
         PearlTraceOutput {
             landing_position: Space3DOutput {
@@ -149,6 +238,169 @@ impl PearlTraceOutput {
             },
             distance,
             closest_approach,
+            stalled_at,
+            records,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TickRecordOutput {
+    pub tick: u32,
+    pub position: Space3DOutput,
+    pub motion: Space3DOutput,
+    pub on_ground: bool,
+    pub is_collided_horizontally: bool,
+    pub is_collided_vertically: bool,
+    /// Indices of the charges that exploded at the start of this tick.
+    pub exploded: Vec<usize>,
+}
+
+impl TickRecordOutput {
+    pub fn from_core(record: TickRecord, origin: Space3D) -> Self {
+        let position = record.position + origin;
+        TickRecordOutput {
+            tick: record.tick,
+            position: Space3DOutput {
+                x: position.x,
+                y: position.y,
+                z: position.z,
+            },
+            motion: Space3DOutput {
+                x: record.motion.x,
+                y: record.motion.y,
+                z: record.motion.z,
+            },
+            on_ground: record.on_ground,
+            is_collided_horizontally: record.is_collided_horizontally,
+            is_collided_vertically: record.is_collided_vertically,
+            exploded: record.exploded,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TntStateOutput {
+    pub position: Space3DOutput,
+    pub motion: Space3DOutput,
+    pub fuse: u32,
+    pub amount: u32,
+    pub source: String,
+    pub exploded: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatorOutput {
+    pub tick: u32,
+    /// The pearl's current state, including any edits since the last tick.
+    pub pearl: TickRecordOutput,
+    pub tnt: Vec<TntStateOutput>,
+    pub records: Vec<TickRecordOutput>,
+    /// How the flight ended, if it has.
+    pub outcome: Option<String>,
+}
+
+impl SimulatorOutput {
+    pub fn from_core(simulator: &Simulator) -> Self {
+        let tick = simulator.tick();
+        let pearl = &simulator.pearl().data;
+        SimulatorOutput {
+            tick,
+            pearl: TickRecordOutput::from_core(
+                TickRecord {
+                    tick,
+                    position: pearl.position,
+                    motion: pearl.motion,
+                    on_ground: pearl.on_ground,
+                    is_collided_horizontally: pearl.is_collided_horizontally,
+                    is_collided_vertically: pearl.is_collided_vertically,
+                    exploded: Vec::new(),
+                },
+                Space3D::default(),
+            ),
+            tnt: simulator
+                .tnt_entities()
+                .iter()
+                .map(|tnt| TntStateOutput {
+                    position: Space3DOutput {
+                        x: tnt.data.position.x,
+                        y: tnt.data.position.y,
+                        z: tnt.data.position.z,
+                    },
+                    motion: Space3DOutput {
+                        x: tnt.data.motion.x,
+                        y: tnt.data.motion.y,
+                        z: tnt.data.motion.z,
+                    },
+                    fuse: tnt.fuse,
+                    amount: tnt.amount,
+                    source: format!("{:?}", tnt.source),
+                    exploded: tnt.fuse < tick,
+                })
+                .collect(),
+            records: simulator
+                .records()
+                .iter()
+                .cloned()
+                .map(|record| TickRecordOutput::from_core(record, Space3D::default()))
+                .collect(),
+            outcome: simulator.outcome().map(|outcome| match outcome {
+                PearlOutcome::Landed(_) => "Landed".to_string(),
+                other => format!("{other:?}"),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ChunkVisitOutput {
+    pub tick: u32,
+    pub chunk_x: i64,
+    pub chunk_z: i64,
+    pub region_x: i64,
+    pub region_z: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ChunkCrossingOutput {
+    pub tick: u32,
+    pub from_x: i64,
+    pub from_z: i64,
+    pub to_x: i64,
+    pub to_z: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkReportOutput {
+    pub ticks: Vec<ChunkVisitOutput>,
+    pub crossings: Vec<ChunkCrossingOutput>,
+}
+
+impl ChunkReportOutput {
+    pub fn from_core(report: ChunkReport) -> Self {
+        ChunkReportOutput {
+            ticks: report
+                .ticks
+                .iter()
+                .map(|visit| ChunkVisitOutput {
+                    tick: visit.tick,
+                    chunk_x: visit.chunk.0,
+                    chunk_z: visit.chunk.1,
+                    region_x: visit.region.0,
+                    region_z: visit.region.1,
+                })
+                .collect(),
+            crossings: report
+                .crossings
+                .iter()
+                .map(|crossing| ChunkCrossingOutput {
+                    tick: crossing.tick,
+                    from_x: crossing.from.0,
+                    from_z: crossing.from.1,
+                    to_x: crossing.to.0,
+                    to_z: crossing.to.1,
+                })
+                .collect(),
         }
     }
 }
@@ -0,0 +1,92 @@
+use crate::inputs::{EntityEditInput, ExplosionInput, RawTraceInput, TntGroupInput};
+use crate::outputs::SimulatorOutput;
+
+use pearl_calculator_core::calculation::simulator::{Simulator, SimulatorState};
+use pearl_calculator_core::physics::world::space::Space3D;
+
+/// A simulator kept alive between calls, along with the snapshots taken of it.
+pub struct SimulatorSession {
+    simulator: Simulator,
+    snapshots: Vec<SimulatorState>,
+}
+
+impl SimulatorSession {
+    pub fn new(input: RawTraceInput) -> Result<Self, String> {
+        Ok(Self {
+            simulator: Simulator::new(&input.get_data(), input.get_world()?, input.get_profile()?),
+            snapshots: Vec::new(),
+        })
+    }
+
+    pub fn state(&self) -> SimulatorOutput {
+        SimulatorOutput::from_core(&self.simulator)
+    }
+
+    pub fn step(&mut self, ticks: u32) -> SimulatorOutput {
+        self.simulator.step(ticks);
+        self.state()
+    }
+
+    /// Edits the pearl or one charge and lets a flight that had ended carry on.
+    pub fn edit(&mut self, input: EntityEditInput) -> Result<SimulatorOutput, String> {
+        let data = match input.tnt_index {
+            None => &mut self.simulator.pearl_mut().data,
+            Some(index) => {
+                let tnt = self
+                    .simulator
+                    .tnt_entities_mut()
+                    .get_mut(index)
+                    .ok_or_else(|| format!("No TNT with index {index}"))?;
+                if let Some(fuse) = input.fuse {
+                    tnt.fuse = fuse;
+                }
+                &mut tnt.data
+            }
+        };
+
+        let position = data.position;
+        data.set_position(Space3D::new(
+            input.x.unwrap_or(position.x),
+            input.y.unwrap_or(position.y),
+            input.z.unwrap_or(position.z),
+        ));
+        data.motion = Space3D::new(
+            input.motion_x.unwrap_or(data.motion.x),
+            input.motion_y.unwrap_or(data.motion.y),
+            input.motion_z.unwrap_or(data.motion.z),
+        );
+
+        self.simulator.clear_outcome();
+        Ok(self.state())
+    }
+
+    pub fn inject_explosion(&mut self, input: ExplosionInput) -> SimulatorOutput {
+        self.simulator.inject_explosion(
+            Space3D::new(input.x, input.y, input.z),
+            input.source.unwrap_or_default(),
+            input.amount.unwrap_or(1),
+        );
+        self.state()
+    }
+
+    pub fn add_tnt(&mut self, group: TntGroupInput) -> SimulatorOutput {
+        self.simulator.add_tnt(&group.to_charge());
+        self.state()
+    }
+
+    /// Saves the current state and returns the id to restore it with.
+    pub fn snapshot(&mut self) -> usize {
+        self.snapshots.push(self.simulator.snapshot());
+        self.snapshots.len() - 1
+    }
+
+    pub fn restore(&mut self, id: usize) -> Result<SimulatorOutput, String> {
+        let state = self
+            .snapshots
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("No snapshot with id {id}"))?;
+        self.simulator.restore(state);
+        Ok(self.state())
+    }
+}
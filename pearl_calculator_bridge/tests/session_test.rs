@@ -0,0 +1,52 @@
+use pearl_calculator_bridge::inputs::{EntityEditInput, RawTraceInput, TntGroupInput};
+use pearl_calculator_bridge::session::SimulatorSession;
+
+fn group(x: f64, amount: u32) -> TntGroupInput {
+    TntGroupInput {
+        x,
+        y: 100.0,
+        z: 0.0,
+        amount,
+        fuse: Some(3),
+    }
+}
+
+#[test]
+fn charges_after_an_empty_group_keep_their_index() {
+    let mut session = SimulatorSession::new(RawTraceInput {
+        pearl_x: 0.0,
+        pearl_y: 100.0,
+        pearl_z: 0.0,
+        pearl_motion_x: 0.0,
+        pearl_motion_y: 0.0,
+        pearl_motion_z: 0.0,
+        tnt_groups: vec![group(-3.0, 0), group(-6.0, 2)],
+        version: "Post1212".to_string(),
+        profile: None,
+        dimension: None,
+        chunk_tickets: None,
+    })
+    .expect("session");
+    assert_eq!(session.state().tnt.len(), 2);
+
+    let state = session
+        .edit(EntityEditInput {
+            tnt_index: Some(1),
+            x: Some(6.0),
+            y: None,
+            z: None,
+            motion_x: None,
+            motion_y: None,
+            motion_z: None,
+            fuse: None,
+        })
+        .expect("edit");
+    assert_eq!(state.tnt[0].position.x, -3.0);
+    assert_eq!(state.tnt[1].position.x, 6.0);
+    assert_eq!(state.tnt[1].amount, 2);
+
+    // With the charge moved to the other side, the explosion pushes the pearl the other way.
+    let state = session.step(5);
+    assert!(state.pearl.motion.x < 0.0);
+    assert_eq!(session.add_tnt(group(1.0, 1)).tnt.len(), 3);
+}
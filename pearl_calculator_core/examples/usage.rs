@@ -15,6 +15,7 @@ use pearl_calculator_core::calculation::calculation::{
 };
 use pearl_calculator_core::calculation::inputs::Cannon;
 use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
 use pearl_calculator_core::physics::world::space::Space3D;
 // The library provides built-in serialization types:
 use pearl_calculator_core::settings::AppSettings;
@@ -100,7 +101,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         None, // max_vertical_tnt: None = no limit (only used in 3D mode)
         MAX_SIMULATION_TICKS,
         SEARCH_TOLERANCE_BLOCKS,
-        PearlVersion::Post1212, // Minecraft 1.21.2+ physics
+        &CollisionWorld::default(), // world_collisions: empty = no obstacles
+        &PearlVersion::Post1212.profile(), // Minecraft 1.21.2+ physics
         false,
     );
 
@@ -153,20 +155,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // calculate_pearl_trace returns Option<CalculationResult>
     // CalculationResult contains:
-    //   - pearl_trace: Vec<Space3D> (position at each tick)
-    //   - pearl_motion_trace: Vec<Space3D> (motion at each tick)
+    //   - records: Vec<TickRecord> (position, motion, collision flags and exploded charges
+    //     at each tick)
     //   - landing_position: Space3D
     //   - final_motion: Space3D
     //   - tick: u32
+    //   - outcome: PearlOutcome (Landed on a block, TimedOut or fell into the Void)
     let trace = calculate_pearl_trace(
         &cannon,
         best.red,
         best.blue,
         best.vertical,
+        &best.stages,
         best.direction,
         sim_ticks,
-        &[], // world_collisions: empty = no obstacles
-        PearlVersion::Post1212,
+        &CollisionWorld::default(), // world_collisions: empty = no obstacles
+        &PearlVersion::Post1212.profile(),
     );
 
     println!("   Time: {:.2?}", start_time.elapsed());
@@ -176,28 +180,34 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     match trace {
         Some(result) => {
             println!("   CalculationResult:");
-            println!("     trace_points: {}", result.pearl_trace.len());
+            println!("     trace_points: {}", result.records.len());
             println!(
                 "     landing_pos:  ({:.4}, {:.4}, {:.4})",
                 result.landing_position.x, result.landing_position.y, result.landing_position.z
             );
+            println!("     outcome:      {:?}", result.outcome);
             println!();
 
             // Print tick-by-tick trajectory
-            println!("   Pearl Trajectory (pearl_trace):");
+            println!("   Pearl Trajectory (records):");
             println!("   {:>4}  {:>12}  {:>10}  {:>12}", "Tick", "X", "Y", "Z");
             println!("   {}", "-".repeat(44));
 
-            for (tick, pos) in result.pearl_trace.iter().enumerate() {
+            for record in &result.records {
+                let pos = record.position;
                 println!(
                     "   {:>4}  {:>12.4}  {:>10.4}  {:>12.4}",
-                    tick, pos.x, pos.y, pos.z
+                    record.tick, pos.x, pos.y, pos.z
                 );
             }
             println!();
 
             // Verify the solution tick matches expected distance
-            if let Some(pos) = result.pearl_trace.get(best.tick as usize) {
+            if let Some(pos) = result
+                .records
+                .get(best.tick as usize)
+                .map(|record| record.position)
+            {
                 let dist_2d =
                     ((pos.x - destination.x).powi(2) + (pos.z - destination.z).powi(2)).sqrt();
                 println!("   Verification at tick {}:", best.tick);
@@ -1,8 +1,15 @@
 pub mod calculation;
 pub mod inputs;
+pub mod lookup;
 mod optimizer;
+pub mod progress;
+pub mod ranking;
+pub mod reachability;
 pub mod results;
+pub mod robustness;
 pub mod simulation;
+pub mod simulator;
 mod solver;
+mod stages;
 mod trace;
 mod vectors;
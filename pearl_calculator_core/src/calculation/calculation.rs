@@ -1,10 +1,31 @@
-use crate::calculation::inputs::Cannon;
-use crate::calculation::results::TNTResult;
+use crate::calculation::inputs::{Cannon, GroupCannon};
+use crate::calculation::progress::{CancellationToken, SolveObserver, SolveProgress};
+use crate::calculation::results::{TNTResult, TNTSearch};
+use crate::calculation::stages::AimerLaunch;
+use crate::calculation::trace::FlightCheck;
+use crate::calculation::vectors::ChargeAxes;
 use crate::physics::constants::constants::FLOAT_PRECISION_EPSILON;
-use crate::physics::entities::movement::PearlVersion;
+use crate::physics::entities::explosion::ExplosionKind;
+use crate::physics::entities::profile::PhysicsProfile;
+use crate::physics::world::collision_world::CollisionWorld;
 use crate::physics::world::direction::Direction;
 use crate::physics::world::space::Space3D;
 
+// Candidates simulated between progress reports and cancellation checks.
+const VALIDATION_BATCH: usize = 256;
+
+/// The limits of a solve and the world it is solved in.
+#[derive(Debug, Clone, Copy)]
+pub struct SolveOptions<'a> {
+    pub max_tnt: u32,
+    pub max_vertical_tnt: Option<u32>,
+    pub max_ticks: u32,
+    pub max_distance: f64,
+    pub world_collisions: &'a CollisionWorld,
+    pub profile: &'a PhysicsProfile,
+    pub plane_intercept_y: bool,
+}
+
 pub fn calculate_tnt_amount(
     cannon: &Cannon,
     destination: Space3D,
@@ -12,69 +33,436 @@ pub fn calculate_tnt_amount(
     max_vertical_tnt: Option<u32>,
     max_ticks: u32,
     max_distance: f64,
-    version: PearlVersion,
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
     plane_intercept_y: bool,
 ) -> Vec<TNTResult> {
+    let options = SolveOptions {
+        max_tnt,
+        max_vertical_tnt,
+        max_ticks,
+        max_distance,
+        world_collisions,
+        profile,
+        plane_intercept_y,
+    };
+    search_tnt_amount(cannon, destination, &options).results
+}
+
+/// Same as [`calculate_tnt_amount`], but also reports how much of the charge lattice was searched.
+pub fn search_tnt_amount(
+    cannon: &Cannon,
+    destination: Space3D,
+    options: &SolveOptions,
+) -> TNTSearch {
+    search_observed(cannon, destination, options, &mut SolveObserver::silent())
+}
+
+/// Same as [`search_tnt_amount`], but reports progress after every batch of simulated candidates
+/// and stops with the results so far once `cancel` is set.
+pub fn search_tnt_amount_with_progress(
+    cannon: &Cannon,
+    destination: Space3D,
+    options: &SolveOptions,
+    cancel: &CancellationToken,
+    on_progress: &mut dyn FnMut(&SolveProgress),
+) -> TNTSearch {
+    search_observed(
+        cannon,
+        destination,
+        options,
+        &mut SolveObserver::new(cancel, on_progress),
+    )
+}
+
+/// The candidate counts of one flight direction of one launch, keyed by `K`, with whatever `C`
+/// it takes to fly them.
+struct Plan<K, C> {
+    direction: Direction,
+    context: C,
+    candidates: Vec<(K, Vec<u32>)>,
+}
+
+/// Where a four-corner cannon's aimers fire from and the pearl they push.
+struct AimerPlan<'a> {
+    launch: &'a AimerLaunch,
+    axes: ChargeAxes,
+}
+
+/// The charge vectors of a group cannon's groups that fire in one flight direction.
+struct GroupPlan {
+    axes: ChargeAxes,
+    active: Vec<usize>,
+    check_3d: bool,
+}
+
+fn search_observed(
+    cannon: &Cannon,
+    destination: Space3D,
+    options: &SolveOptions,
+    observer: &mut SolveObserver,
+) -> TNTSearch {
+    let true_distance = destination - cannon.pearl.position;
+
+    if true_distance.length_sq() < FLOAT_PRECISION_EPSILON {
+        return TNTSearch::default();
+    }
+
+    // Stages have to go off before the aimers and leave them some flight time to solve over.
+    if cannon
+        .stages
+        .iter()
+        .any(|stage| stage.fuse >= cannon.aim_fuse)
+        || options.max_ticks <= cannon.aim_fuse
+    {
+        return TNTSearch::default();
+    }
+
+    let mut search = TNTSearch::default();
+    let (stage_counts, explored) = super::stages::stage_candidates(cannon, destination, options);
+    search.explored += explored;
+    let mut launches = Vec::new();
+    for counts in stage_counts {
+        if observer.is_cancelled() {
+            search.cancelled = true;
+            return search;
+        }
+        launches.extend(super::stages::launch_through_stages(
+            cannon,
+            &counts,
+            options.world_collisions,
+            options.profile,
+        ));
+    }
+
+    // Every candidate is picked before any is simulated, so progress counts over the whole
+    // search rather than restarting for each launch and direction.
+    let mut plans = Vec::new();
+    for launch in &launches {
+        if observer.is_cancelled() {
+            search.cancelled = true;
+            return search;
+        }
+        plans.extend(plan_aimers(
+            cannon,
+            launch,
+            destination,
+            options,
+            &mut search.explored,
+        ));
+    }
+
+    validate_plans(plans, observer, &mut search, |plan, direction, batch| {
+        let launch = plan.launch;
+        super::trace::validate_candidates(
+            batch,
+            &plan.axes,
+            &FlightCheck {
+                pearl_motion: launch.motion,
+                destination,
+                max_distance_sq: options.max_distance * options.max_distance,
+                plane_intercept_y: options.plane_intercept_y,
+                world_collisions: options.world_collisions,
+                profile: options.profile,
+                projectile: cannon.projectile,
+            },
+            direction,
+        )
+        .into_iter()
+        .map(|mut result| {
+            result.tick += cannon.aim_fuse;
+            result.total += launch.stage_counts.iter().sum::<u32>();
+            result.stages = launch.stage_counts.clone();
+            result.peak_y = result.peak_y.max(launch.peak_y);
+            result
+        })
+        .collect()
+    });
+    search
+}
+
+/// Simulates every plan's candidates in batches, reporting progress over all of them and
+/// stopping with the results so far once the observer is cancelled. Each plan's results are
+/// added to `search` closest first.
+fn validate_plans<K, C>(
+    plans: Vec<Plan<K, C>>,
+    observer: &mut SolveObserver,
+    search: &mut TNTSearch,
+    mut validate: impl FnMut(&C, Direction, Vec<(K, Vec<u32>)>) -> Vec<TNTResult>,
+) {
+    let total = plans.iter().map(|plan| plan.candidates.len()).sum();
+    let mut validated = 0;
+    for plan in plans {
+        let mut remaining = plan.candidates;
+        let mut plan_results: Vec<TNTResult> = Vec::new();
+        observer.report(plan.direction, validated, total, &[]);
+
+        while !remaining.is_empty() {
+            if observer.is_cancelled() {
+                search.cancelled = true;
+                break;
+            }
+            let rest = remaining.split_off(remaining.len().min(VALIDATION_BATCH));
+            let batch = std::mem::replace(&mut remaining, rest);
+            validated += batch.len();
+
+            let results = validate(&plan.context, plan.direction, batch);
+            observer.report(plan.direction, validated, total, &results);
+            plan_results.extend(results);
+        }
+
+        plan_results.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap());
+        search.results.extend(plan_results);
+        if search.cancelled {
+            break;
+        }
+    }
+}
+
+/// Picks the red, blue and vertical counts to simulate in every flight direction for a pearl
+/// that is at `launch.position` when the aimers explode.
+fn plan_aimers<'a>(
+    cannon: &Cannon,
+    launch: &'a AimerLaunch,
+    destination: Space3D,
+    options: &SolveOptions,
+    explored: &mut u64,
+) -> Vec<Plan<(u32, u32, u32), AimerPlan<'a>>> {
+    let true_distance = destination - launch.position;
+
+    if true_distance.length_sq() < FLOAT_PRECISION_EPSILON {
+        return Vec::new();
+    }
+
+    let yaw = launch.position.angle_to_yaw(&destination);
+    let world_collisions = options.world_collisions;
+    let profile = options.profile;
+
+    Direction::from_angle_with_fallbacks(yaw)
+        .into_iter()
+        .map(|flight_direction| {
+            let axes = super::vectors::resolve_axes_for_direction(
+                cannon,
+                flight_direction,
+                launch.position,
+                world_collisions,
+                profile,
+            );
+            let (red_vec, blue_vec, vert_vec) = (axes.vectors[0], axes.vectors[1], axes.vectors[2]);
+
+            let solver_input = super::solver::SolverInput {
+                red_vec,
+                blue_vec,
+                vert_vec,
+                start_pos: launch.position,
+                start_motion: launch.motion,
+                destination,
+                max_ticks: options.max_ticks - cannon.aim_fuse,
+                tick_order: profile.tick_order_for(cannon.projectile),
+                physics: profile.projectile_physics(cannon.projectile),
+                plane_intercept_y: options.plane_intercept_y,
+            };
+            let targets = super::solver::solve_theoretical_tnt(&solver_input);
+
+            let search_params = super::optimizer::SearchParams {
+                max_distance: options.max_distance,
+                max_tnt: options.max_tnt,
+                max_vertical_tnt: options.max_vertical_tnt,
+                has_vertical: cannon.vertical_tnt.is_some(),
+                is_valid_3d: vert_vec.length_sq() > FLOAT_PRECISION_EPSILON,
+                cannon_mode: cannon.mode,
+            };
+            let search = super::optimizer::generate_candidates(targets, &search_params);
+            *explored += search.explored;
+
+            Plan {
+                direction: flight_direction,
+                context: AimerPlan { launch, axes },
+                candidates: search.candidates,
+            }
+        })
+        .collect()
+}
+
+/// Solves a cannon with any number of charge groups, picking a count for every group active in
+/// each flight direction. With `aim_3d`, lands on the destination itself rather than anywhere
+/// in its column, provided the active groups can push the pearl in all three dimensions.
+///
+/// Each group's own bounds take the place of `options.max_tnt` and `options.max_vertical_tnt`.
+pub fn calculate_group_tnt_amount(
+    cannon: &GroupCannon,
+    destination: Space3D,
+    options: &SolveOptions,
+    aim_3d: bool,
+) -> Vec<TNTResult> {
+    search_group_tnt_amount(cannon, destination, options, aim_3d).results
+}
+
+/// Same as [`calculate_group_tnt_amount`], but also reports how much of the charge lattice was
+/// searched and whether any of it was cut short.
+pub fn search_group_tnt_amount(
+    cannon: &GroupCannon,
+    destination: Space3D,
+    options: &SolveOptions,
+    aim_3d: bool,
+) -> TNTSearch {
+    search_groups_observed(
+        cannon,
+        destination,
+        options,
+        aim_3d,
+        &mut SolveObserver::silent(),
+    )
+}
+
+/// Same as [`search_group_tnt_amount`], but reports progress after every batch of simulated
+/// candidates and stops with the results so far once `cancel` is set.
+pub fn search_group_tnt_amount_with_progress(
+    cannon: &GroupCannon,
+    destination: Space3D,
+    options: &SolveOptions,
+    aim_3d: bool,
+    cancel: &CancellationToken,
+    on_progress: &mut dyn FnMut(&SolveProgress),
+) -> TNTSearch {
+    search_groups_observed(
+        cannon,
+        destination,
+        options,
+        aim_3d,
+        &mut SolveObserver::new(cancel, on_progress),
+    )
+}
+
+fn search_groups_observed(
+    cannon: &GroupCannon,
+    destination: Space3D,
+    options: &SolveOptions,
+    aim_3d: bool,
+    observer: &mut SolveObserver,
+) -> TNTSearch {
     let pearl_start_pos = cannon.pearl.position;
     let true_distance = destination - pearl_start_pos;
 
     if true_distance.length_sq() < FLOAT_PRECISION_EPSILON {
-        return Vec::new();
+        return TNTSearch::default();
     }
 
     let yaw = pearl_start_pos.angle_to_yaw(&destination);
-    let flight_directions = Direction::from_angle_with_fallbacks(yaw);
+    let max_ticks = options.max_ticks;
+    let plane_intercept_y = options.plane_intercept_y;
+    let world_collisions = options.world_collisions;
+    let profile = options.profile;
+    let mut search = TNTSearch::default();
+    let mut plans = Vec::new();
 
-    let max_distance_sq = max_distance * max_distance;
-    let mut all_results: Vec<TNTResult> = Vec::new();
+    for flight_direction in Direction::from_angle_with_fallbacks(yaw) {
+        if observer.is_cancelled() {
+            search.cancelled = true;
+            return search;
+        }
+        let active: Vec<usize> = (0..cannon.groups.len())
+            .filter(|index| cannon.groups[*index].is_active(flight_direction))
+            .collect();
+        if active.is_empty() {
+            continue;
+        }
 
-    for flight_direction in flight_directions {
-        let (red_vec, blue_vec, vert_vec) =
-            super::vectors::resolve_vectors_for_direction(cannon, flight_direction);
+        let charge_axes = ChargeAxes::measure(pearl_start_pos, |position| {
+            active
+                .iter()
+                .map(|index| {
+                    super::simulation::calculate_explosion_motion(
+                        position,
+                        cannon.groups[*index].position,
+                        ExplosionKind::Tnt,
+                        cannon.projectile,
+                        world_collisions,
+                        profile,
+                    )
+                })
+                .collect()
+        });
+        let axes = &charge_axes.vectors;
+        let bounds: Vec<(u32, u32)> = active
+            .iter()
+            .map(|index| (cannon.groups[*index].min_tnt, cannon.groups[*index].max_tnt))
+            .collect();
+        // Spare groups can also steer the height the pearl is at on each tick, which puts it
+        // on the destination's plane there instead of wherever the horizontal fit leaves it.
+        let solve_3d = (aim_3d || plane_intercept_y) && super::solver::spans_3d(axes);
 
-        let solver_input = super::solver::SolverInput {
-            red_vec,
-            blue_vec,
-            vert_vec,
+        let targets = super::solver::solve_group_targets(&super::solver::GroupSolverInput {
+            axes: axes.clone(),
+            offsets: Vec::new(),
             start_pos: pearl_start_pos,
             start_motion: cannon.pearl.motion,
             destination,
             max_ticks,
-            version,
-            plane_intercept_y,
-        };
-        let theoretical_groups = super::solver::solve_theoretical_tnt(&solver_input);
+            tick_order: profile.tick_order_for(cannon.projectile),
+            physics: profile.projectile_physics(cannon.projectile),
+            solve_3d,
+        });
 
-        let is_valid_3d = vert_vec.length_sq() > FLOAT_PRECISION_EPSILON;
-
-        let search_params = super::optimizer::SearchParams {
-            max_tnt,
-            max_vertical_tnt,
-            search_radius: 5,
-            has_vertical: cannon.vertical_tnt.is_some(),
-            is_valid_3d,
-            cannon_mode: cannon.mode,
+        // The plane crossing falls between ticks, so without the height in the fit a tick's own
+        // landing error says little about it and every tick is worth flying.
+        let max_error = if plane_intercept_y && !solve_3d {
+            f64::INFINITY
+        } else {
+            options.max_distance
         };
-        let candidates = super::optimizer::generate_candidates(theoretical_groups, &search_params);
-
-        let results = super::trace::validate_candidates(
-            candidates,
-            red_vec,
-            blue_vec,
-            vert_vec,
-            cannon.pearl.position,
-            cannon.pearl.motion,
-            destination,
-            max_distance_sq,
-            plane_intercept_y,
-            version,
-            flight_direction,
-        );
+        let mut lattice = super::optimizer::generate_group_candidates(targets, &bounds, max_error);
+        search.explored += lattice.explored;
+        search.truncated |= lattice.truncated;
+        // A pearl that is on the plane at the end of a tick may cross it on the next one.
+        if plane_intercept_y {
+            for (_, ticks) in &mut lattice.candidates {
+                let next: Vec<u32> = ticks
+                    .iter()
+                    .map(|tick| tick + 1)
+                    .filter(|tick| *tick <= max_ticks)
+                    .collect();
+                ticks.extend(next);
+                ticks.sort_unstable();
+                ticks.dedup();
+            }
+        }
 
-        all_results.extend(results);
+        plans.push(Plan {
+            direction: flight_direction,
+            context: GroupPlan {
+                axes: charge_axes,
+                active,
+                check_3d: plane_intercept_y || solve_3d,
+            },
+            candidates: lattice.candidates,
+        });
     }
 
-    all_results
+    let flight = FlightCheck {
+        pearl_motion: cannon.pearl.motion,
+        destination,
+        max_distance_sq: options.max_distance * options.max_distance,
+        plane_intercept_y,
+        world_collisions,
+        profile,
+        projectile: cannon.projectile,
+    };
+    validate_plans(plans, observer, &mut search, |plan, direction, batch| {
+        super::trace::validate_group_candidates(
+            batch,
+            &plan.axes,
+            &plan.active,
+            cannon.groups.len(),
+            plan.check_3d,
+            &flight,
+            direction,
+        )
+    });
+    search
+        .results
+        .sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    search
 }
 
-pub use super::trace::{calculate_pearl_trace, calculate_raw_trace};
+pub use super::trace::{calculate_chunk_report, calculate_pearl_trace, calculate_raw_trace};
@@ -1,3 +1,6 @@
+use crate::physics::entities::explosion::ExplosionKind;
+use crate::physics::entities::projectile::ProjectileKind;
+use crate::physics::world::direction::Direction;
 use crate::physics::world::layout_direction::LayoutDirection;
 use crate::physics::world::space::Space3D;
 use crate::settings::{CannonMode, CannonSettings};
@@ -8,12 +11,40 @@ pub struct GeneralData {
     pub pearl_position: Space3D,
     pub pearl_motion: Space3D,
     pub tnt_charges: Vec<TNT>,
+    #[serde(default)]
+    pub projectile: ProjectileKind,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TNT {
     pub position: Space3D,
     pub fuse: u32,
+    #[serde(default)]
+    pub motion: Space3D,
+    #[serde(default)]
+    pub source: ExplosionKind,
+    /// Identical charges stacked on this one.
+    #[serde(default = "single_charge")]
+    pub amount: u32,
+    /// Held in place until it detonates, like TNT resting in water or on a block.
+    #[serde(default)]
+    pub anchored: bool,
+}
+
+fn single_charge() -> u32 {
+    1
+}
+
+/// A charge that detonates before the aimers, such as the booster of a two-stage cannon.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CannonStage {
+    /// Where the charge explodes.
+    pub position: Space3D,
+    /// Tick after the pearl is released on which the charge explodes.
+    pub fuse: u32,
+    #[serde(rename = "MaxTNT")]
+    pub max_tnt: u32,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
@@ -22,7 +53,7 @@ pub struct Pearl {
     pub motion: Space3D,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Cannon {
     pub pearl: Pearl,
     pub red_tnt_override: Option<Space3D>,
@@ -35,6 +66,10 @@ pub struct Cannon {
     pub south_east_tnt: Space3D,
     pub default_red_duper: Option<LayoutDirection>,
     pub default_blue_duper: Option<LayoutDirection>,
+    pub projectile: ProjectileKind,
+    pub stages: Vec<CannonStage>,
+    /// Tick on which the red, blue and vertical charges explode. Every stage fires before it.
+    pub aim_fuse: u32,
 }
 
 impl Cannon {
@@ -54,6 +89,110 @@ impl Cannon {
             south_east_tnt: settings.south_east_tnt,
             default_red_duper: settings.default_red_direction,
             default_blue_duper: settings.default_blue_direction,
+            projectile: settings.projectile,
+            stages: settings.stages.clone(),
+            aim_fuse: settings.aim_fuse,
+        }
+    }
+}
+
+/// One charge position of a cannon described by its groups rather than by four corners.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TNTGroup {
+    pub name: String,
+    pub position: Space3D,
+    /// Fewest charges the group fires whenever it is active.
+    #[serde(default, rename = "MinTNT")]
+    pub min_tnt: u32,
+    #[serde(rename = "MaxTNT")]
+    pub max_tnt: u32,
+    /// Flight directions the group fires for. Empty means every direction.
+    #[serde(default)]
+    pub directions: Vec<Direction>,
+}
+
+impl TNTGroup {
+    pub fn is_active(&self, direction: Direction) -> bool {
+        self.directions.is_empty() || self.directions.contains(&direction)
+    }
+}
+
+/// A cannon with any number of charge groups. For every flight direction the solver picks a
+/// count for each group active in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupCannon {
+    pub pearl: Pearl,
+    pub groups: Vec<TNTGroup>,
+    pub projectile: ProjectileKind,
+}
+
+impl GroupCannon {
+    pub fn from_settings(settings: &CannonSettings) -> Self {
+        Self {
+            pearl: Pearl {
+                position: settings.pearl.position,
+                motion: settings.pearl.motion,
+            },
+            groups: settings.groups.clone(),
+            projectile: settings.projectile,
+        }
+    }
+
+    /// The corner and vertical charges of `cannon` as groups, each active for the directions
+    /// the four-corner solver fires it in. Cannons with stages have no group equivalent.
+    pub fn from_cannon(
+        cannon: &Cannon,
+        max_tnt: u32,
+        max_vertical_tnt: Option<u32>,
+    ) -> Option<Self> {
+        if !cannon.stages.is_empty() || cannon.aim_fuse > 0 {
+            return None;
         }
+
+        let corners = [
+            ("NorthWest", cannon.north_west_tnt),
+            ("NorthEast", cannon.north_east_tnt),
+            ("SouthWest", cannon.south_west_tnt),
+            ("SouthEast", cannon.south_east_tnt),
+        ];
+        let mut groups: Vec<TNTGroup> = corners
+            .into_iter()
+            .map(|(name, position)| TNTGroup {
+                name: name.to_string(),
+                position,
+                min_tnt: 0,
+                max_tnt,
+                directions: [
+                    Direction::North,
+                    Direction::South,
+                    Direction::East,
+                    Direction::West,
+                ]
+                .into_iter()
+                .filter(|direction| {
+                    let (red, blue) = super::vectors::resolve_tnt_for_direction(cannon, *direction);
+                    red == position || blue == position
+                })
+                .collect(),
+            })
+            .filter(|group| !group.directions.is_empty())
+            .collect();
+
+        if let Some(position) = cannon.vertical_tnt {
+            groups.push(TNTGroup {
+                name: "Vertical".to_string(),
+                position,
+                min_tnt: 0,
+                max_tnt: max_vertical_tnt.unwrap_or(max_tnt),
+                directions: Vec::new(),
+            });
+        }
+
+        Some(Self {
+            pearl: cannon.pearl,
+            groups,
+            projectile: cannon.projectile,
+        })
     }
 }
@@ -0,0 +1,422 @@
+use crate::calculation::calculation::{SolveOptions, search_tnt_amount};
+use crate::calculation::inputs::Cannon;
+use crate::calculation::ranking::{Ranking, rank_results};
+use crate::calculation::results::TNTResult;
+use crate::calculation::trace::FlightCheck;
+use crate::physics::aabb::aabb_box::AABBBox;
+use crate::physics::entities::profile::{PhysicsProfile, Precision, TickOrder};
+use crate::physics::entities::projectile::ProjectileKind;
+use crate::physics::world::collision_world::CollisionWorld;
+use crate::physics::world::direction::Direction;
+use crate::physics::world::fluid::FluidKind;
+use crate::physics::world::layout_direction::LayoutDirection;
+use crate::physics::world::space::Space3D;
+use crate::settings::types::CannonMode;
+use crate::utils::parallel::*;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"PCLT";
+const FORMAT_VERSION: u8 = 3;
+const HEADER_LEN: usize = 4 + 1 + 8 + 4 + 4 + 8 + 4;
+
+// Destinations this close to a sampled block are served from the table.
+const SAMPLE_EPSILON: f64 = 1e-6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LookupEntry {
+    pub red: u16,
+    pub blue: u16,
+    pub tick: u16,
+    pub direction: Direction,
+}
+
+/// The best solution for every `step`-th block within `radius` of the pearl, stored on a square
+/// grid centred on it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LookupTable {
+    /// Identifies the cannon, world, physics and limits the table was built for.
+    pub fingerprint: u64,
+    pub radius: u32,
+    pub step: u32,
+    pub max_distance: f64,
+    entries: Vec<Option<LookupEntry>>,
+}
+
+impl LookupTable {
+    /// Solves every sampled block and keeps the result the default [`Ranking`] puts first, the
+    /// closest one. Only horizontal cannons are supported, since their answers do not depend on
+    /// the destination height.
+    pub fn build(
+        cannon: &Cannon,
+        radius: u32,
+        step: u32,
+        options: &SolveOptions,
+    ) -> Result<Self, Box<dyn Error>> {
+        if step == 0 {
+            return Err("Lookup table step must be at least one block".into());
+        }
+        if cannon.vertical_tnt.is_some() || !cannon.stages.is_empty() || cannon.aim_fuse > 0 {
+            return Err("Lookup tables only support cannons without vertical or staged TNT".into());
+        }
+        if options.plane_intercept_y {
+            return Err("Lookup tables do not support landing on the destination's plane".into());
+        }
+
+        let half = (radius / step) as i64;
+        let side = (2 * half + 1) as usize;
+        let entries: Vec<Option<LookupEntry>> = (0..side * side)
+            .into_par_iter()
+            .map(|index| {
+                let (dx, dz) = offset_of(index, side, half, step);
+                let destination = cannon.pearl.position + Space3D::new(dx as f64, 0.0, dz as f64);
+                let search = search_tnt_amount(cannon, destination, options);
+                rank_results(search.results, &Ranking::default())
+                    .first()
+                    .and_then(|best| {
+                        Some(LookupEntry {
+                            red: best.red.try_into().ok()?,
+                            blue: best.blue.try_into().ok()?,
+                            tick: best.tick.try_into().ok()?,
+                            direction: best.direction,
+                        })
+                    })
+            })
+            .collect();
+
+        Ok(Self {
+            fingerprint: fingerprint(cannon, options),
+            radius,
+            step,
+            max_distance: options.max_distance,
+            entries,
+        })
+    }
+
+    /// Whether the table was built for this cannon, world, physics and limits.
+    pub fn matches(&self, cannon: &Cannon, options: &SolveOptions) -> bool {
+        !options.plane_intercept_y && self.fingerprint == fingerprint(cannon, options)
+    }
+
+    /// The stored solution for `destination`, if it is one of the sampled blocks.
+    pub fn get(&self, cannon: &Cannon, destination: Space3D) -> Option<LookupEntry> {
+        let offset = destination - cannon.pearl.position;
+        let half = (self.radius / self.step) as i64;
+        let cell = |value: f64| {
+            let scaled = value / self.step as f64;
+            let rounded = scaled.round();
+            ((scaled - rounded).abs() * self.step as f64 <= SAMPLE_EPSILON
+                && rounded.abs() <= half as f64)
+                .then_some((rounded as i64 + half) as usize)
+        };
+        let side = (2 * half + 1) as usize;
+        self.entries[cell(offset.x)? * side + cell(offset.z)?]
+    }
+
+    /// Replays the stored solution for `destination` to rebuild its full result.
+    pub fn resolve(
+        &self,
+        cannon: &Cannon,
+        destination: Space3D,
+        world_collisions: &CollisionWorld,
+        profile: &PhysicsProfile,
+    ) -> Option<TNTResult> {
+        let entry = self.get(cannon, destination)?;
+        let axes = super::vectors::resolve_axes_for_direction(
+            cannon,
+            entry.direction,
+            cannon.pearl.position,
+            world_collisions,
+            profile,
+        );
+        super::trace::validate_candidates(
+            vec![(
+                (entry.red as u32, entry.blue as u32, 0),
+                vec![entry.tick as u32],
+            )],
+            &axes,
+            &FlightCheck {
+                pearl_motion: cannon.pearl.motion,
+                destination,
+                max_distance_sq: self.max_distance * self.max_distance,
+                plane_intercept_y: false,
+                world_collisions,
+                profile,
+                projectile: cannon.projectile,
+            },
+            entry.direction,
+        )
+        .into_iter()
+        .next()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.entries.len() * 7);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(FORMAT_VERSION);
+        bytes.extend_from_slice(&self.fingerprint.to_le_bytes());
+        bytes.extend_from_slice(&self.radius.to_le_bytes());
+        bytes.extend_from_slice(&self.step.to_le_bytes());
+        bytes.extend_from_slice(&self.max_distance.to_le_bytes());
+        bytes.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+
+        // Each cell starts with its direction bit, or zero when nothing reaches it.
+        for entry in &self.entries {
+            match entry {
+                Some(entry) => {
+                    bytes.push(entry.direction as u8);
+                    bytes.extend_from_slice(&entry.red.to_le_bytes());
+                    bytes.extend_from_slice(&entry.blue.to_le_bytes());
+                    bytes.extend_from_slice(&entry.tick.to_le_bytes());
+                }
+                None => bytes.push(0),
+            }
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
+        if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC {
+            return Err("Not a pearl calculator lookup table".into());
+        }
+        if bytes[4] != FORMAT_VERSION {
+            return Err(format!("Unsupported lookup table version {}", bytes[4]).into());
+        }
+
+        let mut cursor = 5;
+        let mut take = |len: usize| -> Result<&[u8], Box<dyn Error>> {
+            let slice = bytes
+                .get(cursor..cursor + len)
+                .ok_or("Lookup table is truncated")?;
+            cursor += len;
+            Ok(slice)
+        };
+
+        let fingerprint = u64::from_le_bytes(take(8)?.try_into()?);
+        let radius = u32::from_le_bytes(take(4)?.try_into()?);
+        let step = u32::from_le_bytes(take(4)?.try_into()?);
+        let max_distance = f64::from_le_bytes(take(8)?.try_into()?);
+        let count = u32::from_le_bytes(take(4)?.try_into()?) as usize;
+
+        if step == 0 {
+            return Err("Lookup table has a zero step".into());
+        }
+        let side = 2 * (radius / step) as usize + 1;
+        if count != side * side {
+            return Err(format!(
+                "Lookup table holds {count} cells but its grid needs {}",
+                side * side
+            )
+            .into());
+        }
+
+        let mut entries = Vec::with_capacity(count);
+        for _ in 0..count {
+            let direction = match take(1)?[0] {
+                0 => {
+                    entries.push(None);
+                    continue;
+                }
+                1 => Direction::North,
+                2 => Direction::South,
+                4 => Direction::East,
+                8 => Direction::West,
+                other => return Err(format!("Invalid direction {other} in lookup table").into()),
+            };
+            let mut read_u16 =
+                || -> Result<u16, Box<dyn Error>> { Ok(u16::from_le_bytes(take(2)?.try_into()?)) };
+            entries.push(Some(LookupEntry {
+                red: read_u16()?,
+                blue: read_u16()?,
+                tick: read_u16()?,
+                direction,
+            }));
+        }
+
+        Ok(Self {
+            fingerprint,
+            radius,
+            step,
+            max_distance,
+            entries,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
+
+fn offset_of(index: usize, side: usize, half: i64, step: u32) -> (i64, i64) {
+    let x = (index / side) as i64 - half;
+    let z = (index % side) as i64 - half;
+    (x * step as i64, z * step as i64)
+}
+
+// FNV-1a over every field that changes the answers, written out one by one so that a new
+// field or a reordered enum changes the format version rather than silently every hash.
+fn fingerprint(cannon: &Cannon, options: &SolveOptions) -> u64 {
+    let mut hash = Fingerprint::new();
+
+    hash.space(cannon.pearl.position);
+    hash.space(cannon.pearl.motion);
+    hash.optional_space(cannon.red_tnt_override);
+    hash.optional_space(cannon.blue_tnt_override);
+    hash.optional_space(cannon.vertical_tnt);
+    hash.u32(match cannon.mode {
+        CannonMode::Standard => 0,
+        CannonMode::Accumulation => 1,
+    });
+    for position in [
+        cannon.north_west_tnt,
+        cannon.north_east_tnt,
+        cannon.south_west_tnt,
+        cannon.south_east_tnt,
+    ] {
+        hash.space(position);
+    }
+    hash.u32(layout_tag(cannon.default_red_duper));
+    hash.u32(layout_tag(cannon.default_blue_duper));
+    hash.u32(match cannon.projectile {
+        ProjectileKind::EnderPearl => 0,
+        ProjectileKind::Snowball => 1,
+        ProjectileKind::Egg => 2,
+        ProjectileKind::Arrow => 3,
+        ProjectileKind::Trident => 4,
+        ProjectileKind::WindCharge => 5,
+    });
+    hash.u32(cannon.stages.len() as u32);
+    for stage in &cannon.stages {
+        hash.space(stage.position);
+        hash.u32(stage.fuse);
+        hash.u32(stage.max_tnt);
+    }
+    hash.u32(cannon.aim_fuse);
+
+    hash.u32(options.max_tnt);
+    hash.u32(options.max_ticks);
+    hash.f64(options.max_distance);
+
+    let profile = options.profile;
+    hash.u32(match profile.precision {
+        Precision::Float => 0,
+        Precision::Double => 1,
+    });
+    hash.u32(match profile.tick_order {
+        TickOrder::MoveFirst => 0,
+        TickOrder::GravityFirst => 1,
+    });
+    hash.optional_f64(profile.gravity);
+    hash.optional_f64(profile.drag);
+    hash.optional_f64(profile.water_drag);
+    hash.f64(profile.explosion.knockback_scale);
+    hash.f64(profile.explosion.tnt_center_offset);
+    hash.u32(match profile.pearls_load_chunks {
+        None => 0,
+        Some(false) => 1,
+        Some(true) => 2,
+    });
+
+    let world = options.world_collisions;
+    hash.u32(world.boxes().len() as u32);
+    for aabb in world.boxes() {
+        hash.aabb(aabb);
+    }
+    hash.u32(world.fluids().len() as u32);
+    for fluid in world.fluids() {
+        hash.aabb(&fluid.bounds);
+        hash.u32(match fluid.kind {
+            FluidKind::Water => 0,
+            FluidKind::Lava => 1,
+        });
+    }
+    hash.optional_f64(world.void_floor());
+    hash.optional_f64(world.border_radius());
+    hash.space(world.origin());
+    match world.chunk_loading() {
+        Some(loading) => {
+            hash.u32(1);
+            hash.u32(loading.tickets().len() as u32);
+            for ticket in loading.tickets() {
+                hash.space(ticket.position);
+                hash.u32(ticket.level);
+            }
+        }
+        None => hash.u32(0),
+    }
+
+    hash.0
+}
+
+fn layout_tag(layout: Option<LayoutDirection>) -> u32 {
+    match layout {
+        None => 0,
+        Some(LayoutDirection::NorthWest) => 1,
+        Some(LayoutDirection::NorthEast) => 2,
+        Some(LayoutDirection::SouthWest) => 3,
+        Some(LayoutDirection::SouthEast) => 4,
+        Some(LayoutDirection::North) => 5,
+        Some(LayoutDirection::South) => 6,
+        Some(LayoutDirection::West) => 7,
+        Some(LayoutDirection::East) => 8,
+    }
+}
+
+struct Fingerprint(u64);
+
+impl Fingerprint {
+    fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    fn f64(&mut self, value: f64) {
+        self.bytes(&value.to_bits().to_le_bytes());
+    }
+
+    fn optional_f64(&mut self, value: Option<f64>) {
+        match value {
+            Some(value) => {
+                self.u32(1);
+                self.f64(value);
+            }
+            None => self.u32(0),
+        }
+    }
+
+    fn space(&mut self, value: Space3D) {
+        self.f64(value.x);
+        self.f64(value.y);
+        self.f64(value.z);
+    }
+
+    fn aabb(&mut self, value: &AABBBox) {
+        self.space(Space3D::new(value.min_x, value.min_y, value.min_z));
+        self.space(Space3D::new(value.max_x, value.max_y, value.max_z));
+    }
+
+    fn optional_space(&mut self, value: Option<Space3D>) {
+        match value {
+            Some(value) => {
+                self.u32(1);
+                self.space(value);
+            }
+            None => self.u32(0),
+        }
+    }
+}
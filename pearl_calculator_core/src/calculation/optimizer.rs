@@ -1,65 +1,569 @@
+use crate::physics::constants::constants::FLOAT_PRECISION_EPSILON;
+use crate::physics::world::space::Space3D;
 use crate::settings::types::CannonMode;
-use std::collections::HashMap;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap};
 
 pub struct SearchParams {
+    /// How far from the destination, in the linear model, a candidate may land.
+    pub max_distance: f64,
     pub max_tnt: u32,
     pub max_vertical_tnt: Option<u32>,
-    pub search_radius: i32,
     pub has_vertical: bool,
     pub is_valid_3d: bool,
     pub cannon_mode: CannonMode,
 }
 
-pub fn generate_candidates(
-    theoretical_groups: HashMap<(i32, i32, i32), Vec<u32>>,
-    params: &SearchParams,
-) -> Vec<((u32, u32, u32), Vec<u32>)> {
-    let v_range = if params.has_vertical && params.is_valid_3d {
-        -1..=1
-    } else {
-        0..=0
-    };
+/// The continuous charge counts that hit the destination on `tick`, and how far the landing
+/// point moves per charge along each axis.
+#[derive(Debug, Clone)]
+pub struct LatticeTarget {
+    pub tick: u32,
+    pub ideal: Vec<f64>,
+    pub axes: Vec<Space3D>,
+    /// Distance from the destination that no counts can close, because the axes do not reach it.
+    pub residual: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatticePoint {
+    pub counts: Vec<u32>,
+    pub error: f64,
+}
+
+// Candidates kept per tick, closest first, so a blocked or drifting one has alternatives.
+const POINTS_PER_TICK: usize = 8;
+// Nodes searched per tick for those alternatives.
+const ALTERNATIVE_NODE_LIMIT: u64 = 65536;
+
+// Nodes searched per target when the axes are redundant and the lattice has no single best
+// region to close in on.
+const REDUNDANT_NODE_LIMIT: u64 = 65536;
+
+/// Candidate counts, keyed by `K`, with the ticks each is expected to land on.
+pub struct SearchOutcome<K = (u32, u32, u32)> {
+    pub candidates: Vec<(K, Vec<u32>)>,
+    pub explored: u64,
+    /// Set when a target's search hit its node limit, so closer counts may have been missed.
+    pub truncated: bool,
+}
+
+/// The closest counts a lattice search found.
+pub struct LatticeSearch {
+    pub best: Option<LatticePoint>,
+    pub explored: u64,
+    /// Set when the node limit stopped the search before `best` was proven closest.
+    pub truncated: bool,
+}
+
+impl SearchParams {
+    /// The most red or blue charges, and the most vertical charges, a candidate may use.
+    pub fn limits(&self) -> (u32, u32) {
+        let side_limit = if self.max_tnt > 0
+            && self.cannon_mode != CannonMode::Accumulation
+            && !self.has_vertical
+        {
+            self.max_tnt
+        } else {
+            u32::MAX
+        };
+        let vertical_limit = if self.has_vertical && self.is_valid_3d {
+            self.max_vertical_tnt.unwrap_or(u32::MAX)
+        } else {
+            0
+        };
+        (side_limit, vertical_limit)
+    }
+}
+
+pub fn generate_candidates(targets: Vec<LatticeTarget>, params: &SearchParams) -> SearchOutcome {
+    let (side_limit, vertical_limit) = params.limits();
+
     let mut unique_candidates: HashMap<(u32, u32, u32), Vec<u32>> = HashMap::new();
+    let mut explored = 0;
+
+    for target in targets {
+        let mut bounds = vec![(0, side_limit), (0, side_limit)];
+        if target.axes.len() > 2 {
+            bounds.push((0, vertical_limit));
+        }
+
+        let (points, visited) = search_lattice_within(
+            &target.ideal,
+            &target.axes,
+            &bounds,
+            params.max_distance,
+            POINTS_PER_TICK,
+        );
+        explored += visited;
+
+        for point in points {
+            let counts = &point.counts;
+            unique_candidates
+                .entry((counts[0], counts[1], counts.get(2).copied().unwrap_or(0)))
+                .or_default()
+                .push(target.tick);
+        }
+    }
+
+    SearchOutcome {
+        candidates: unique_candidates.into_iter().collect(),
+        explored,
+        truncated: false,
+    }
+}
+
+/// Picks the best counts within `bounds` for every target whose landing could come within
+/// `max_error` of the destination. The axes may outnumber the dimensions they span, as with a
+/// cannon that has several charges pushing the same way, in which case each search stops after
+/// a fixed number of nodes.
+pub fn generate_group_candidates(
+    targets: Vec<LatticeTarget>,
+    bounds: &[(u32, u32)],
+    max_error: f64,
+) -> SearchOutcome<Vec<u32>> {
+    let mut unique_candidates: HashMap<Vec<u32>, Vec<u32>> = HashMap::new();
+    let mut explored = 0;
+    let mut truncated = false;
+
+    for target in targets {
+        let node_limit = if invert(gram(&target.axes)).is_some() {
+            u64::MAX
+        } else {
+            REDUNDANT_NODE_LIMIT
+        };
+        let search = search_lattice_limited(&target.ideal, &target.axes, bounds, node_limit);
+        explored += search.explored;
+        truncated |= search.truncated;
+
+        if let Some(best) = search.best
+            && best.error.hypot(target.residual) <= max_error
+        {
+            unique_candidates
+                .entry(best.counts)
+                .or_default()
+                .push(target.tick);
+        }
+    }
+
+    SearchOutcome {
+        candidates: unique_candidates.into_iter().collect(),
+        explored,
+        truncated,
+    }
+}
+
+/// Finds the integer counts within `bounds` whose landing point lies closest to the one reached
+/// with the continuous `ideal` counts, returning it with the number of search nodes visited.
+///
+/// Bounds may be open-ended (`u32::MAX`) as long as the axes are linearly independent, in which
+/// case they are tightened around the ideal before searching.
+pub fn search_lattice(
+    ideal: &[f64],
+    axes: &[Space3D],
+    bounds: &[(u32, u32)],
+) -> (Option<LatticePoint>, u64) {
+    let search = search_lattice_limited(ideal, axes, bounds, u64::MAX);
+    (search.best, search.explored)
+}
+
+/// Same as [`search_lattice`], but gives up after `node_limit` nodes with the closest counts
+/// found so far.
+pub fn search_lattice_limited(
+    ideal: &[f64],
+    axes: &[Space3D],
+    bounds: &[(u32, u32)],
+    node_limit: u64,
+) -> LatticeSearch {
+    if ideal.iter().any(|value| !value.is_finite()) || bounds.iter().any(|(lo, hi)| lo > hi) {
+        return LatticeSearch {
+            best: None,
+            explored: 0,
+            truncated: false,
+        };
+    }
+
+    let incumbent: Vec<f64> = ideal
+        .iter()
+        .zip(bounds)
+        .map(|(value, (lo, hi))| value.round().clamp(*lo as f64, *hi as f64))
+        .collect();
+    let mut best = LatticePoint {
+        counts: incumbent.iter().map(|count| *count as u32).collect(),
+        error: offset_error(ideal, axes, &incumbent),
+    };
+
+    let Some(bounds) = tighten_bounds(ideal, axes, bounds, &best) else {
+        return LatticeSearch {
+            best: Some(best),
+            explored: 1,
+            truncated: false,
+        };
+    };
+
+    let root = LatticeBox {
+        lower_bound: 0.0,
+        ranges: bounds,
+    };
+    let mut queue = BinaryHeap::from([Reverse(root)]);
+    let mut explored = 0;
+    let mut truncated = false;
+
+    while let Some(Reverse(node)) = queue.pop() {
+        if node.lower_bound >= best.error {
+            break;
+        }
+        if explored >= node_limit {
+            truncated = true;
+            break;
+        }
+        explored += 1;
+
+        match split(&node, ideal, axes) {
+            Some(children) => queue.extend(
+                children
+                    .into_iter()
+                    .filter(|child| child.lower_bound < best.error)
+                    .map(Reverse),
+            ),
+            None => {
+                let point = leaf(&node, ideal, axes);
+                if point.error < best.error {
+                    best = point;
+                }
+            }
+        }
+    }
+
+    LatticeSearch {
+        best: Some(best),
+        explored,
+        truncated,
+    }
+}
+
+/// The closest counts within `bounds`, followed by every other count landing within `radius`
+/// of the ideal, up to `keep` points in all, closest first.
+pub fn search_lattice_within(
+    ideal: &[f64],
+    axes: &[Space3D],
+    bounds: &[(u32, u32)],
+    radius: f64,
+    keep: usize,
+) -> (Vec<LatticePoint>, u64) {
+    let (best, mut explored) = search_lattice(ideal, axes, bounds);
+    let Some(best) = best else {
+        return (Vec::new(), explored);
+    };
+    if best.error > radius || keep <= 1 {
+        return (vec![best], explored);
+    }
+
+    let reach = LatticePoint {
+        counts: best.counts.clone(),
+        error: radius,
+    };
+    let Some(ranges) = tighten_bounds(ideal, axes, bounds, &reach) else {
+        return (vec![best], explored);
+    };
 
-    for ((center_red, center_blue, center_vert), valid_ticks) in theoretical_groups {
-        for r_offset in -params.search_radius..=params.search_radius {
-            for b_offset in -params.search_radius..=params.search_radius {
-                for v_offset in v_range.clone() {
-                    let current_red = center_red + r_offset;
-                    let current_blue = center_blue + b_offset;
-                    let current_vert = center_vert + v_offset;
-
-                    if current_red < 0 || current_blue < 0 || current_vert < 0 {
-                        continue;
-                    }
-
-                    let r_u32 = current_red as u32;
-                    let b_u32 = current_blue as u32;
-                    let v_u32 = current_vert as u32;
-
-                    let max_single_side = r_u32.max(b_u32);
-                    if params.max_tnt > 0
-                        && params.cannon_mode != CannonMode::Accumulation
-                        && !params.has_vertical
-                        && max_single_side > params.max_tnt
-                    {
-                        continue;
-                    }
-
-                    if let Some(max_v) = params.max_vertical_tnt {
-                        if v_u32 > max_v {
-                            continue;
-                        }
-                    }
-
-                    unique_candidates
-                        .entry((r_u32, b_u32, v_u32))
-                        .or_default()
-                        .extend(valid_ticks.iter().cloned());
+    let mut points = Vec::new();
+    let mut pending = vec![LatticeBox {
+        lower_bound: 0.0,
+        ranges,
+    }];
+    while let Some(node) = pending.pop() {
+        if explored >= ALTERNATIVE_NODE_LIMIT {
+            break;
+        }
+        explored += 1;
+        match split(&node, ideal, axes) {
+            Some(children) => pending.extend(
+                children
+                    .into_iter()
+                    .filter(|child| child.lower_bound <= radius),
+            ),
+            None => {
+                let point = leaf(&node, ideal, axes);
+                if point.error <= radius && point.counts != best.counts {
+                    points.push(point);
                 }
             }
         }
     }
 
-    unique_candidates.into_iter().collect()
+    points.sort_by(|a, b| a.error.total_cmp(&b.error));
+    points.truncate(keep - 1);
+    points.insert(0, best);
+    (points, explored)
+}
+
+fn offset_error(ideal: &[f64], axes: &[Space3D], counts: &[f64]) -> f64 {
+    axes.iter()
+        .zip(ideal)
+        .zip(counts)
+        .fold(Space3D::default(), |offset, ((axis, ideal), count)| {
+            offset + *axis * (count - ideal)
+        })
+        .length()
+}
+
+/// The counts of a box that has been narrowed to a single point.
+fn leaf(node: &LatticeBox, ideal: &[f64], axes: &[Space3D]) -> LatticePoint {
+    let counts: Vec<f64> = node.ranges.iter().map(|(lo, _)| *lo as f64).collect();
+    LatticePoint {
+        error: offset_error(ideal, axes, &counts),
+        counts: node.ranges.iter().map(|(lo, _)| *lo).collect(),
+    }
+}
+
+/// Halves the box along its widest range, or returns `None` once it holds a single point.
+fn split(node: &LatticeBox, ideal: &[f64], axes: &[Space3D]) -> Option<[LatticeBox; 2]> {
+    let (split, _) = node
+        .ranges
+        .iter()
+        .zip(axes)
+        .enumerate()
+        .filter(|(_, ((lo, hi), _))| hi > lo)
+        .max_by(|(_, ((lo_a, hi_a), axis_a)), (_, ((lo_b, hi_b), axis_b))| {
+            let span_a = (hi_a - lo_a) as f64 * axis_a.length();
+            let span_b = (hi_b - lo_b) as f64 * axis_b.length();
+            span_a.total_cmp(&span_b)
+        })?;
+
+    let (lo, hi) = node.ranges[split];
+    let middle = lo + (hi - lo) / 2;
+    Some([(lo, middle), (middle + 1, hi)].map(|half| {
+        let mut ranges = node.ranges.clone();
+        ranges[split] = half;
+
+        // The landing offset is linear in the counts, so no point in the box can be closer
+        // than its centre minus the reach of the box's half extents.
+        let centre: Vec<f64> = ranges
+            .iter()
+            .map(|(lo, hi)| (*lo as f64 + *hi as f64) / 2.0)
+            .collect();
+        let reach: f64 = ranges
+            .iter()
+            .zip(axes)
+            .map(|((lo, hi), axis)| (hi - lo) as f64 / 2.0 * axis.length())
+            .sum();
+        LatticeBox {
+            lower_bound: (offset_error(ideal, axes, &centre) - reach).max(0.0),
+            ranges,
+        }
+    }))
+}
+
+/// Shrinks every range to the counts that could still land at least as close as `incumbent`.
+/// Returns `None` when the axes are degenerate and a range is left unbounded.
+fn tighten_bounds(
+    ideal: &[f64],
+    axes: &[Space3D],
+    bounds: &[(u32, u32)],
+    incumbent: &LatticePoint,
+) -> Option<Vec<(u32, u32)>> {
+    let radius = incumbent.error * (1.0 + FLOAT_PRECISION_EPSILON) + FLOAT_PRECISION_EPSILON;
+    let reach = invert(gram(axes)).map(|inverse| {
+        // Row `i` of the pseudo-inverse maps a landing offset back to a change in count `i`,
+        // and its norm is sqrt of the Gram inverse's diagonal.
+        (0..axes.len())
+            .map(|i| inverse[i][i].max(0.0).sqrt() * radius)
+            .collect::<Vec<f64>>()
+    });
+
+    let mut ranges = Vec::with_capacity(bounds.len());
+    for (index, (lo, hi)) in bounds.iter().enumerate() {
+        let range = match &reach {
+            Some(reach) => {
+                let min = (ideal[index] - reach[index]).ceil().max(*lo as f64);
+                let max = (ideal[index] + reach[index]).floor().min(*hi as f64);
+                if min > max {
+                    (incumbent.counts[index], incumbent.counts[index])
+                } else {
+                    (min as u32, max as u32)
+                }
+            }
+            None if *hi == u32::MAX => return None,
+            None => (*lo, *hi),
+        };
+        ranges.push(range);
+    }
+    Some(ranges)
+}
+
+/// Dot products of every pair of axes, invertible only when the axes are linearly independent.
+fn gram(axes: &[Space3D]) -> Vec<Vec<f64>> {
+    axes.iter()
+        .map(|a| axes.iter().map(|b| a.dot(*b)).collect())
+        .collect()
+}
+
+pub fn invert(mut matrix: Vec<Vec<f64>>) -> Option<Vec<Vec<f64>>> {
+    let size = matrix.len();
+    let mut inverse: Vec<Vec<f64>> = (0..size)
+        .map(|i| (0..size).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+
+    for column in 0..size {
+        let pivot = (column..size).max_by(|a, b| {
+            matrix[*a][column]
+                .abs()
+                .total_cmp(&matrix[*b][column].abs())
+        })?;
+        if matrix[pivot][column].abs() < FLOAT_PRECISION_EPSILON {
+            return None;
+        }
+        matrix.swap(column, pivot);
+        inverse.swap(column, pivot);
+
+        let scale = matrix[column][column];
+        for j in 0..size {
+            matrix[column][j] /= scale;
+            inverse[column][j] /= scale;
+        }
+        for row in 0..size {
+            if row != column {
+                let factor = matrix[row][column];
+                for j in 0..size {
+                    matrix[row][j] -= factor * matrix[column][j];
+                    inverse[row][j] -= factor * inverse[column][j];
+                }
+            }
+        }
+    }
+    Some(inverse)
+}
+
+#[derive(Debug)]
+struct LatticeBox {
+    lower_bound: f64,
+    ranges: Vec<(u32, u32)>,
+}
+
+impl PartialEq for LatticeBox {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for LatticeBox {}
+
+impl PartialOrd for LatticeBox {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LatticeBox {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.lower_bound.total_cmp(&other.lower_bound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every count within `bounds`, with its landing error, closest first.
+    fn brute_force(ideal: &[f64], axes: &[Space3D], bounds: &[(u32, u32)]) -> Vec<LatticePoint> {
+        let mut points: Vec<LatticePoint> = bounds
+            .iter()
+            .fold(vec![Vec::new()], |prefixes, (lo, hi)| {
+                prefixes
+                    .into_iter()
+                    .flat_map(|prefix: Vec<u32>| {
+                        (*lo..=*hi).map(move |count| {
+                            let mut counts = prefix.clone();
+                            counts.push(count);
+                            counts
+                        })
+                    })
+                    .collect()
+            })
+            .into_iter()
+            .map(|counts| {
+                let values: Vec<f64> = counts.iter().map(|count| *count as f64).collect();
+                LatticePoint {
+                    error: offset_error(ideal, axes, &values),
+                    counts,
+                }
+            })
+            .collect();
+        points.sort_by(|a, b| a.error.total_cmp(&b.error));
+        points
+    }
+
+    type Case = (Vec<f64>, Vec<Space3D>, Vec<(u32, u32)>);
+
+    // Small axes of varied lengths and angles, from a fixed linear congruential sequence.
+    fn cases() -> Vec<Case> {
+        let mut state = 0x2545f4914f6cdd1du64;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        };
+
+        (0..60)
+            .map(|case| {
+                let dimensions = 2 + case % 3;
+                let axes: Vec<Space3D> = (0..dimensions)
+                    .map(|_| {
+                        let y = if dimensions > 2 {
+                            next() * 4.0 - 2.0
+                        } else {
+                            0.0
+                        };
+                        Space3D::new(next() * 4.0 - 2.0, y, next() * 4.0 - 2.0)
+                    })
+                    .collect();
+                let bounds: Vec<(u32, u32)> = (0..dimensions)
+                    .map(|_| {
+                        let lo = (next() * 3.0) as u32;
+                        (lo, lo + 2 + (next() * 6.0) as u32)
+                    })
+                    .collect();
+                let ideal = (0..dimensions).map(|_| next() * 12.0 - 2.0).collect();
+                (ideal, axes, bounds)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lattice_search_finds_the_brute_force_optimum() {
+        for (ideal, axes, bounds) in cases() {
+            let expected = brute_force(&ideal, &axes, &bounds);
+            let (best, _) = search_lattice(&ideal, &axes, &bounds);
+            let best = best.expect("bounds are valid");
+            assert!(
+                (best.error - expected[0].error).abs() < 1e-9,
+                "{ideal:?} {axes:?} {bounds:?}: {best:?} vs {:?}",
+                expected[0]
+            );
+        }
+    }
+
+    #[test]
+    fn points_within_the_radius_match_brute_force() {
+        for (ideal, axes, bounds) in cases() {
+            let expected = brute_force(&ideal, &axes, &bounds);
+            let radius = expected[0].error + 1.5;
+            let within: Vec<&LatticePoint> = expected
+                .iter()
+                .filter(|point| point.error <= radius)
+                .collect();
+
+            let (points, _) = search_lattice_within(&ideal, &axes, &bounds, radius, usize::MAX);
+            assert_eq!(points.len(), within.len(), "{ideal:?} {axes:?} {bounds:?}");
+            assert!((points[0].error - expected[0].error).abs() < 1e-9);
+            for (point, expected) in points.iter().zip(&within) {
+                assert!((point.error - expected.error).abs() < 1e-9);
+            }
+
+            let (kept, _) = search_lattice_within(&ideal, &axes, &bounds, radius, 3);
+            assert_eq!(kept.len(), within.len().min(3));
+        }
+    }
 }
@@ -0,0 +1,90 @@
+use crate::calculation::results::TNTResult;
+use crate::physics::world::direction::Direction;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Reports carry only the closest few results so far.
+const BEST_SO_FAR: usize = 10;
+
+/// Shared flag that stops a running solve at its next batch of candidates.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolveProgress {
+    /// Flight direction of the candidates being simulated.
+    pub direction: Direction,
+    /// Candidates simulated so far, out of `total` across every direction and stage launch.
+    pub validated: usize,
+    pub total: usize,
+    /// The closest results found so far across every direction.
+    pub best: Vec<TNTResult>,
+}
+
+pub(crate) struct SolveObserver<'a> {
+    cancel: Option<&'a CancellationToken>,
+    on_progress: Option<&'a mut dyn FnMut(&SolveProgress)>,
+    best: Vec<TNTResult>,
+}
+
+impl<'a> SolveObserver<'a> {
+    pub(crate) fn new(
+        cancel: &'a CancellationToken,
+        on_progress: &'a mut dyn FnMut(&SolveProgress),
+    ) -> Self {
+        Self {
+            cancel: Some(cancel),
+            on_progress: Some(on_progress),
+            best: Vec::new(),
+        }
+    }
+
+    pub(crate) fn silent() -> Self {
+        Self {
+            cancel: None,
+            on_progress: None,
+            best: Vec::new(),
+        }
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancel.is_some_and(CancellationToken::is_cancelled)
+    }
+
+    pub(crate) fn report(
+        &mut self,
+        direction: Direction,
+        validated: usize,
+        total: usize,
+        found: &[TNTResult],
+    ) {
+        let Some(on_progress) = self.on_progress.as_mut() else {
+            return;
+        };
+
+        self.best.extend_from_slice(found);
+        self.best.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        self.best.truncate(BEST_SO_FAR);
+
+        on_progress(&SolveProgress {
+            direction,
+            validated,
+            total,
+            best: self.best.clone(),
+        });
+    }
+}
@@ -0,0 +1,128 @@
+use crate::calculation::results::TNTResult;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Objective {
+    Distance,
+    Total,
+    Tick,
+    PeakY,
+    Sensitivity,
+}
+
+impl Objective {
+    /// The result's score on this objective, where lower is always better.
+    pub fn cost(self, result: &TNTResult) -> f64 {
+        match self {
+            Objective::Distance => result.distance,
+            Objective::Total => result.total as f64,
+            Objective::Tick => result.tick as f64,
+            Objective::PeakY => result.peak_y,
+            Objective::Sensitivity => result.sensitivity,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WeightedObjective {
+    pub objective: Objective,
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Ranking {
+    /// Orders every result by the weighted sum of its objectives, each rescaled to the spread of
+    /// the whole result set so that blocks and ticks can be weighed against each other.
+    Weighted(Vec<WeightedObjective>),
+    /// Keeps only the results for which no other result is at least as good on every objective
+    /// and strictly better on one.
+    Pareto(Vec<Objective>),
+}
+
+impl Default for Ranking {
+    fn default() -> Self {
+        Ranking::Weighted(vec![WeightedObjective {
+            objective: Objective::Distance,
+            weight: 1.0,
+        }])
+    }
+}
+
+pub fn rank_results(results: Vec<TNTResult>, ranking: &Ranking) -> Vec<TNTResult> {
+    match ranking {
+        Ranking::Weighted(objectives) => rank_weighted(results, objectives),
+        Ranking::Pareto(objectives) => pareto_front(results, objectives),
+    }
+}
+
+pub fn rank_weighted(results: Vec<TNTResult>, objectives: &[WeightedObjective]) -> Vec<TNTResult> {
+    let spans: Vec<(f64, f64)> = objectives
+        .iter()
+        .map(|weighted| {
+            results
+                .iter()
+                .map(|result| weighted.objective.cost(result))
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), cost| {
+                    (min.min(cost), max.max(cost))
+                })
+        })
+        .collect();
+
+    let mut scored: Vec<(f64, TNTResult)> = results
+        .into_iter()
+        .map(|result| {
+            let score = objectives
+                .iter()
+                .zip(&spans)
+                .map(|(weighted, (min, max))| {
+                    let spread = max - min;
+                    if spread > 0.0 {
+                        weighted.weight * (weighted.objective.cost(&result) - min) / spread
+                    } else {
+                        0.0
+                    }
+                })
+                .sum();
+            (score, result)
+        })
+        .collect();
+
+    scored.sort_by(|(score_a, a), (score_b, b)| {
+        score_a
+            .total_cmp(score_b)
+            .then_with(|| a.distance.total_cmp(&b.distance))
+            .then_with(|| a.tick.cmp(&b.tick))
+    });
+    scored.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Results on the front are ordered by distance.
+pub fn pareto_front(results: Vec<TNTResult>, objectives: &[Objective]) -> Vec<TNTResult> {
+    let costs: Vec<Vec<f64>> = results
+        .iter()
+        .map(|result| {
+            objectives
+                .iter()
+                .map(|objective| objective.cost(result))
+                .collect()
+        })
+        .collect();
+
+    let dominates = |a: &[f64], b: &[f64]| {
+        a.iter().zip(b).all(|(a, b)| a <= b) && a.iter().zip(b).any(|(a, b)| a < b)
+    };
+
+    let mut front: Vec<TNTResult> = results
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| !costs.iter().any(|other| dominates(other, &costs[*index])))
+        .map(|(_, result)| result)
+        .collect();
+
+    front.sort_by(|a, b| {
+        a.distance
+            .total_cmp(&b.distance)
+            .then_with(|| a.tick.cmp(&b.tick))
+    });
+    front
+}
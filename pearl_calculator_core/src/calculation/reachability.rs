@@ -0,0 +1,320 @@
+use crate::calculation::inputs::{Cannon, GeneralData};
+use crate::calculation::results::PearlOutcome;
+use crate::calculation::simulation;
+use crate::physics::entities::profile::PhysicsProfile;
+use crate::physics::world::collision_world::CollisionWorld;
+use crate::physics::world::direction::Direction;
+use crate::physics::world::space::Space3D;
+use crate::utils::parallel::*;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::error::Error;
+
+const CELL_SIZE: f64 = 8.0;
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::North,
+    Direction::South,
+    Direction::East,
+    Direction::West,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReachablePoint {
+    pub position: Space3D,
+    pub red: u32,
+    pub blue: u32,
+    pub vertical: u32,
+    pub direction: Direction,
+    pub tick: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageReport {
+    pub samples: usize,
+    /// Largest distance from any sampled block to its nearest reachable landing point.
+    pub worst_error: f64,
+    pub worst_at: Space3D,
+    /// Sampled blocks with no landing point within the tolerance.
+    pub gaps: Vec<Space3D>,
+}
+
+/// Every landing point a cannon can reach, bucketed on a horizontal grid for nearest-point queries.
+#[derive(Debug, Clone, Default)]
+pub struct ReachabilityMap {
+    points: Vec<ReachablePoint>,
+    cells: HashMap<(i64, i64), Vec<u32>>,
+}
+
+impl ReachabilityMap {
+    pub fn new(points: Vec<ReachablePoint>) -> Self {
+        let mut cells: HashMap<(i64, i64), Vec<u32>> = HashMap::new();
+        for (index, point) in points.iter().enumerate() {
+            cells
+                .entry(cell_of(point.position.x, point.position.z))
+                .or_default()
+                .push(index as u32);
+        }
+        Self { points, cells }
+    }
+
+    pub fn points(&self) -> &[ReachablePoint] {
+        &self.points
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The landing point horizontally closest to `target`, with its distance.
+    pub fn nearest(&self, target: Space3D) -> Option<(&ReachablePoint, f64)> {
+        if self.points.is_empty() {
+            return None;
+        }
+
+        let centre = cell_of(target.x, target.z);
+        let mut best: Option<(&ReachablePoint, f64)> = None;
+        for ring in 0.. {
+            // Anything in a ring further out is at least this far away.
+            let ring_floor = (ring as f64 - 1.0).max(0.0) * CELL_SIZE;
+            if best.is_some_and(|(_, distance)| distance <= ring_floor) {
+                break;
+            }
+
+            for (x, z) in ring_cells(centre, ring) {
+                for index in self.cells.get(&(x, z)).into_iter().flatten() {
+                    let point = &self.points[*index as usize];
+                    let distance = point.position.distance_2d(&target);
+                    if best.is_none_or(|(_, best_distance)| distance < best_distance) {
+                        best = Some((point, distance));
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// Landing points within `radius` blocks of `target`, measured horizontally.
+    pub fn within(&self, target: Space3D, radius: f64) -> Vec<&ReachablePoint> {
+        let min = cell_of(target.x - radius, target.z - radius);
+        let max = cell_of(target.x + radius, target.z + radius);
+
+        let mut found = Vec::new();
+        for x in min.0..=max.0 {
+            for z in min.1..=max.1 {
+                for index in self.cells.get(&(x, z)).into_iter().flatten() {
+                    let point = &self.points[*index as usize];
+                    if point.position.distance_2d(&target) <= radius {
+                        found.push(point);
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    /// Samples every `step` blocks within `radius` of `centre` and reports how far each sample is
+    /// from the nearest reachable landing point.
+    pub fn coverage(
+        &self,
+        centre: Space3D,
+        radius: f64,
+        step: f64,
+        tolerance: f64,
+    ) -> CoverageReport {
+        let mut report = CoverageReport {
+            samples: 0,
+            worst_error: 0.0,
+            worst_at: centre,
+            gaps: Vec::new(),
+        };
+        let step = step.max(f64::EPSILON);
+        let steps = (radius / step).floor() as i64;
+
+        for i in -steps..=steps {
+            for j in -steps..=steps {
+                let sample = centre + Space3D::new(i as f64 * step, 0.0, j as f64 * step);
+                if sample.distance_2d(&centre) > radius {
+                    continue;
+                }
+                report.samples += 1;
+
+                let error = self
+                    .nearest(sample)
+                    .map_or(f64::INFINITY, |(_, distance)| distance);
+                if error > report.worst_error {
+                    report.worst_error = error;
+                    report.worst_at = sample;
+                }
+                if error > tolerance {
+                    report.gaps.push(sample);
+                }
+            }
+        }
+        report
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("x,y,z,red,blue,vertical,direction,tick\n");
+        for point in &self.points {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{:?},{}\n",
+                point.position.x,
+                point.position.y,
+                point.position.z,
+                point.red,
+                point.blue,
+                point.vertical,
+                point.direction,
+                point.tick
+            ));
+        }
+        csv
+    }
+
+    /// A GeoJSON feature collection with the horizontal plane as its `[x, z]` coordinates and
+    /// the height as the third one.
+    pub fn to_geojson(&self) -> Value {
+        let features: Vec<Value> = self
+            .points
+            .iter()
+            .map(|point| {
+                json!({
+                    "type": "Feature",
+                    "geometry": {
+                        "type": "Point",
+                        "coordinates": [point.position.x, point.position.z, point.position.y],
+                    },
+                    "properties": {
+                        "red": point.red,
+                        "blue": point.blue,
+                        "vertical": point.vertical,
+                        "direction": format!("{:?}", point.direction),
+                        "tick": point.tick,
+                    },
+                })
+            })
+            .collect();
+
+        json!({
+            "type": "FeatureCollection",
+            "features": features,
+        })
+    }
+}
+
+/// Flies every red, blue and vertical count up to the limits in all four directions.
+///
+/// With `landing_y` set, a flight lands where it first falls through that height; otherwise it
+/// has to hit a block in `world_collisions`. The charges' pushes are added straight to the
+/// pearl's motion, so staged cannons and cannons with an aim fuse are rejected.
+pub fn calculate_reachability(
+    cannon: &Cannon,
+    max_tnt: u32,
+    max_vertical_tnt: u32,
+    max_ticks: u32,
+    landing_y: Option<f64>,
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
+) -> Result<ReachabilityMap, Box<dyn Error>> {
+    if !cannon.stages.is_empty() || cannon.aim_fuse > 0 {
+        return Err(
+            "Reachability maps only support cannons without staged TNT or an aim fuse".into(),
+        );
+    }
+
+    let pearl_position = cannon.pearl.position;
+
+    let mut shots = Vec::new();
+    for direction in DIRECTIONS {
+        let (red_vec, blue_vec, vert_vec) = super::vectors::resolve_vectors_for_direction(
+            cannon,
+            direction,
+            pearl_position,
+            world_collisions,
+            profile,
+        );
+        let max_vertical = if vert_vec.length_sq() > 0.0 {
+            max_vertical_tnt
+        } else {
+            0
+        };
+        for red in 0..=max_tnt {
+            for blue in 0..=max_tnt {
+                for vertical in 0..=max_vertical {
+                    let motion = cannon.pearl.motion
+                        + red_vec * red as f64
+                        + blue_vec * blue as f64
+                        + vert_vec * vertical as f64;
+                    shots.push((direction, red, blue, vertical, motion));
+                }
+            }
+        }
+    }
+
+    let points: Vec<ReachablePoint> = shots
+        .into_par_iter()
+        .filter_map(|(direction, red, blue, vertical, motion)| {
+            let data = GeneralData {
+                pearl_position,
+                pearl_motion: motion,
+                tnt_charges: vec![],
+                projectile: cannon.projectile,
+            };
+            let result = simulation::run(&data, None, max_ticks, world_collisions, profile)?;
+
+            let (position, tick) = match landing_y {
+                Some(y) => descent_through(&result.pearl_trace(), y)?,
+                None => match result.outcome {
+                    PearlOutcome::Landed(_) => (result.landing_position, result.tick),
+                    _ => return None,
+                },
+            };
+            Some(ReachablePoint {
+                position,
+                red,
+                blue,
+                vertical,
+                direction,
+                tick,
+            })
+        })
+        .collect();
+
+    Ok(ReachabilityMap::new(points))
+}
+
+fn descent_through(trace: &[Space3D], y: f64) -> Option<(Space3D, u32)> {
+    trace.windows(2).enumerate().find_map(|(tick, segment)| {
+        let (from, to) = (segment[0], segment[1]);
+        if from.y >= y && to.y < y {
+            let t = (from.y - y) / (from.y - to.y);
+            Some((from + (to - from) * t, tick as u32 + 1))
+        } else {
+            None
+        }
+    })
+}
+
+fn cell_of(x: f64, z: f64) -> (i64, i64) {
+    (
+        (x / CELL_SIZE).floor() as i64,
+        (z / CELL_SIZE).floor() as i64,
+    )
+}
+
+fn ring_cells(centre: (i64, i64), ring: i64) -> Vec<(i64, i64)> {
+    if ring == 0 {
+        return vec![centre];
+    }
+    let mut cells = Vec::with_capacity(8 * ring as usize);
+    for offset in -ring..=ring {
+        cells.push((centre.0 + offset, centre.1 - ring));
+        cells.push((centre.0 + offset, centre.1 + ring));
+    }
+    for offset in -ring + 1..ring {
+        cells.push((centre.0 - ring, centre.1 + offset));
+        cells.push((centre.0 + ring, centre.1 + offset));
+    }
+    cells
+}
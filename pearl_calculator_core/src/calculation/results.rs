@@ -1,28 +1,186 @@
-use crate::physics::world::direction::Direction;
+use crate::physics::entities::pearl_entities::PearlEntity;
+use crate::physics::world::chunks::{chunk_of, region_of};
+use crate::physics::world::collision_world::BlockHit;
+use crate::physics::world::direction::{BlockFace, Direction};
 use crate::physics::world::space::Space3D;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TNTResult {
     pub distance: f64,
     pub tick: u32,
     pub blue: u32,
     pub red: u32,
     pub vertical: u32,
+    /// Charges fired in each of the cannon's stages, in order.
+    pub stages: Vec<u32>,
+    /// Charges fired by each of a [`GroupCannon`](crate::calculation::inputs::GroupCannon)'s
+    /// groups, in order, with groups inactive for `direction` firing none. Empty for a cannon
+    /// with red and blue charges.
+    pub counts: Vec<u32>,
     pub yaw: f64,
     pub pitch: f64,
     pub total: u32,
     pub pearl_end_pos: Space3D,
     pub pearl_end_motion: Space3D,
     pub direction: Direction,
+    /// Highest point of the flight before it reaches `pearl_end_pos`.
+    pub peak_y: f64,
+    /// Blocks the landing moves per block the pearl starts away from where it was solved for,
+    /// in the worst direction. Lower is more forgiving of a misplaced pearl.
+    pub sensitivity: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TNTSearch {
+    pub results: Vec<TNTResult>,
+    /// Lattice nodes the optimiser visited while picking candidates.
+    pub explored: u64,
+    /// Set when the search was stopped early and `results` only covers part of it.
+    pub cancelled: bool,
+    /// Set when the optimiser gave up on a search over redundant charges, so closer counts than
+    /// the ones in `results` may exist.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PearlOutcome {
+    Landed(BlockHit),
+    TimedOut,
+    Void,
+    OutsideBorder,
+    /// Froze on entering a chunk that does not tick entities.
+    Stalled,
+}
+
+/// The pearl's state at the end of one tick.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TickRecord {
+    pub tick: u32,
+    pub position: Space3D,
+    pub motion: Space3D,
+    pub on_ground: bool,
+    pub is_collided_horizontally: bool,
+    pub is_collided_vertically: bool,
+    /// Indices of the charges that exploded at the start of this tick.
+    pub exploded: Vec<usize>,
+}
+
+impl TickRecord {
+    pub(crate) fn of(tick: u32, pearl: &PearlEntity, exploded: Vec<usize>) -> Self {
+        Self {
+            tick,
+            position: pearl.data.position,
+            motion: pearl.data.motion,
+            on_ground: pearl.data.on_ground,
+            is_collided_horizontally: pearl.data.is_collided_horizontally,
+            is_collided_vertically: pearl.data.is_collided_vertically,
+            exploded,
+        }
+    }
+
+    /// Sets the collision flags for a pearl whose path hit `face` of a block during the tick.
+    pub(crate) fn collide(&mut self, face: BlockFace) {
+        match face {
+            BlockFace::Up => {
+                self.on_ground = true;
+                self.is_collided_vertically = true;
+            }
+            BlockFace::Down => self.is_collided_vertically = true,
+            _ => self.is_collided_horizontally = true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct CalculationResult {
     pub landing_position: Space3D,
-    pub pearl_trace: Vec<Space3D>,
-    pub pearl_motion_trace: Vec<Space3D>,
+    /// One record per tick, starting from the launch state on tick zero.
+    pub records: Vec<TickRecord>,
     pub is_successful: bool,
     pub tick: u32,
     pub final_motion: Space3D,
     pub distance: f64,
+    pub outcome: PearlOutcome,
+}
+
+impl CalculationResult {
+    pub fn pearl_trace(&self) -> Vec<Space3D> {
+        self.records.iter().map(|record| record.position).collect()
+    }
+
+    pub fn pearl_motion_trace(&self) -> Vec<Space3D> {
+        self.records.iter().map(|record| record.motion).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkVisit {
+    pub tick: u32,
+    pub chunk: (i64, i64),
+    pub region: (i64, i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkCrossing {
+    /// First tick the pearl is in `to`.
+    pub tick: u32,
+    pub from: (i64, i64),
+    pub to: (i64, i64),
+}
+
+/// The chunk and region file the pearl is in on every tick of a trace.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChunkReport {
+    pub ticks: Vec<ChunkVisit>,
+    pub crossings: Vec<ChunkCrossing>,
+}
+
+impl ChunkReport {
+    /// `origin` is the absolute position that the trace's coordinates are relative to.
+    pub fn from_trace(trace: &[Space3D], origin: Space3D) -> Self {
+        let ticks: Vec<ChunkVisit> = trace
+            .iter()
+            .enumerate()
+            .map(|(tick, position)| {
+                let chunk = chunk_of(*position + origin);
+                ChunkVisit {
+                    tick: tick as u32,
+                    chunk,
+                    region: region_of(chunk),
+                }
+            })
+            .collect();
+        let crossings = ticks
+            .windows(2)
+            .filter(|pair| pair[0].chunk != pair[1].chunk)
+            .map(|pair| ChunkCrossing {
+                tick: pair[1].tick,
+                from: pair[0].chunk,
+                to: pair[1].chunk,
+            })
+            .collect();
+        Self { ticks, crossings }
+    }
+
+    /// Every chunk the pearl is in, in the order it first reaches them.
+    pub fn chunks(&self) -> Vec<(i64, i64)> {
+        let mut chunks: Vec<(i64, i64)> = Vec::new();
+        for visit in &self.ticks {
+            if !chunks.contains(&visit.chunk) {
+                chunks.push(visit.chunk);
+            }
+        }
+        chunks
+    }
+
+    /// The first tick the pearl is within `radius` chunks of `centre`, such as the spawn chunks.
+    pub fn first_tick_within(&self, centre: (i64, i64), radius: i64) -> Option<u32> {
+        self.ticks
+            .iter()
+            .find(|visit| {
+                (visit.chunk.0 - centre.0).abs() <= radius
+                    && (visit.chunk.1 - centre.1).abs() <= radius
+            })
+            .map(|visit| visit.tick)
+    }
 }
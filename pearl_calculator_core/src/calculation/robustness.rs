@@ -0,0 +1,226 @@
+use crate::calculation::inputs::{Cannon, GeneralData, TNT};
+use crate::calculation::results::TNTResult;
+use crate::calculation::simulation;
+use crate::physics::constants::constants::{
+    TNT_DRAG_MULTIPLIER, TNT_GRAVITY_ACCELERATION, TNT_GROUND_FRICTION,
+};
+use crate::physics::entities::profile::PhysicsProfile;
+use crate::physics::world::collision_world::CollisionWorld;
+use crate::physics::world::space::Space3D;
+use crate::utils::parallel::*;
+use std::f64::consts::TAU;
+
+/// The randomness a dispenser adds to every charge it primes, plus the error in the measured
+/// charge positions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JitterModel {
+    /// Horizontal speed in a random direction, in blocks per tick.
+    pub horizontal_speed: f64,
+    /// Upward speed of the hop, in blocks per tick.
+    pub jump_speed: f64,
+    /// Ticks between the charge being dispensed and exploding.
+    pub fuse_ticks: u32,
+    /// Standard deviation of the error in each charge group's measured position, in blocks.
+    pub position_error: f64,
+    pub trials: u32,
+    /// Landings this close to the destination, horizontally, count as successes.
+    pub tolerance: f64,
+    pub seed: u64,
+}
+
+impl Default for JitterModel {
+    fn default() -> Self {
+        Self {
+            horizontal_speed: 0.02,
+            jump_speed: 0.2,
+            fuse_ticks: 80,
+            position_error: 0.0,
+            trials: 500,
+            tolerance: 1.0,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RobustnessReport {
+    pub trials: u32,
+    pub successes: u32,
+    pub success_probability: f64,
+    pub mean_error: f64,
+    pub median_error: f64,
+    pub p95_error: f64,
+    pub max_error: f64,
+    /// Horizontal landing error of every trial, in ascending order.
+    pub errors: Vec<f64>,
+}
+
+/// Fires `result` `jitter.trials` times with every charge displaced by its own dispenser hop and
+/// reports how far from `destination` the pearl ends up on the result's tick.
+pub fn analyse_robustness(
+    cannon: &Cannon,
+    result: &TNTResult,
+    destination: Space3D,
+    jitter: &JitterModel,
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
+) -> RobustnessReport {
+    let charges = super::trace::result_charges(
+        cannon,
+        result.red,
+        result.blue,
+        result.vertical,
+        &result.stages,
+        result.direction,
+    );
+
+    let mut errors: Vec<f64> = (0..jitter.trials)
+        .into_par_iter()
+        .map(|trial| {
+            let mut rng = Rng::new(jitter.seed ^ (trial as u64).wrapping_mul(0x9e3779b97f4a7c15));
+            let data = GeneralData {
+                pearl_position: cannon.pearl.position,
+                pearl_motion: cannon.pearl.motion,
+                tnt_charges: jittered_charges(&charges, jitter, &mut rng),
+                projectile: cannon.projectile,
+            };
+            simulation::run(
+                &data,
+                Some(destination),
+                result.tick,
+                world_collisions,
+                profile,
+            )
+            .map_or(f64::INFINITY, |flight| flight.distance)
+        })
+        .collect();
+    errors.sort_by(f64::total_cmp);
+
+    let successes = errors
+        .iter()
+        .filter(|error| **error <= jitter.tolerance)
+        .count() as u32;
+    let percentile = |fraction: f64| {
+        errors
+            .get(((errors.len() as f64 - 1.0) * fraction).round() as usize)
+            .copied()
+            .unwrap_or(0.0)
+    };
+
+    RobustnessReport {
+        trials: jitter.trials,
+        successes,
+        success_probability: successes as f64 / jitter.trials.max(1) as f64,
+        mean_error: errors.iter().sum::<f64>() / errors.len().max(1) as f64,
+        median_error: percentile(0.5),
+        p95_error: percentile(0.95),
+        max_error: errors.last().copied().unwrap_or(0.0),
+        errors,
+    }
+}
+
+/// Analyses every result and orders them from most to least likely to succeed, breaking ties on
+/// the mean error.
+pub fn rank_by_robustness(
+    cannon: &Cannon,
+    results: Vec<TNTResult>,
+    destination: Space3D,
+    jitter: &JitterModel,
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
+) -> Vec<(TNTResult, RobustnessReport)> {
+    let mut analysed: Vec<(TNTResult, RobustnessReport)> = results
+        .into_iter()
+        .map(|result| {
+            let report = analyse_robustness(
+                cannon,
+                &result,
+                destination,
+                jitter,
+                world_collisions,
+                profile,
+            );
+            (result, report)
+        })
+        .collect();
+
+    analysed.sort_by(|(_, a), (_, b)| {
+        b.success_probability
+            .total_cmp(&a.success_probability)
+            .then_with(|| a.mean_error.total_cmp(&b.mean_error))
+    });
+    analysed
+}
+
+// Every charge in a group shares the group's measurement error but hops on its own.
+fn jittered_charges(charges: &[TNT], jitter: &JitterModel, rng: &mut Rng) -> Vec<TNT> {
+    let mut jittered = Vec::new();
+    for charge in charges {
+        let measured = charge.position
+            + Space3D::new(rng.normal(), rng.normal(), rng.normal()) * jitter.position_error;
+        for _ in 0..charge.amount {
+            jittered.push(TNT {
+                position: measured + dispense_drift(rng.next_f64() * TAU, jitter),
+                amount: 1,
+                ..*charge
+            });
+        }
+    }
+    jittered
+}
+
+// Primed TNT hops off the block it was dispensed onto and slides to a stop, losing 2% of its
+// speed every tick. On every tick it ends on the ground it loses 30% of its horizontal speed and
+// bounces back up at half its falling speed, as vanilla does.
+fn dispense_drift(angle: f64, jitter: &JitterModel) -> Space3D {
+    let mut offset = Space3D::default();
+    let mut motion = Space3D::new(
+        -angle.sin() * jitter.horizontal_speed,
+        jitter.jump_speed,
+        -angle.cos() * jitter.horizontal_speed,
+    );
+
+    for _ in 0..jitter.fuse_ticks {
+        motion.y -= TNT_GRAVITY_ACCELERATION;
+        offset += motion;
+        let on_ground = offset.y <= 0.0;
+        if on_ground {
+            offset.y = 0.0;
+        }
+        motion *= TNT_DRAG_MULTIPLIER;
+        if on_ground {
+            motion.x *= TNT_GROUND_FRICTION.0;
+            motion.y *= TNT_GROUND_FRICTION.1;
+            motion.z *= TNT_GROUND_FRICTION.2;
+        }
+    }
+    offset
+}
+
+// SplitMix64, so trials are reproducible from the seed on every platform.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Box-Muller.
+    fn normal(&mut self) -> f64 {
+        let u = 1.0 - self.next_f64();
+        let v = self.next_f64();
+        (-2.0 * u.ln()).sqrt() * (TAU * v).cos()
+    }
+}
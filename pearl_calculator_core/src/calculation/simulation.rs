@@ -1,75 +1,61 @@
-use crate::calculation::inputs::GeneralData;
-use crate::calculation::results::CalculationResult;
+use crate::calculation::inputs::{GeneralData, TNT};
+use crate::calculation::results::{CalculationResult, PearlOutcome, TickRecord};
 use crate::physics::aabb::aabb_box::AABBBox;
-use crate::physics::constants::constants::{
-    FLOAT_PRECISION_EPSILON, PEARL_EXPLOSION_Y_FACTOR, PEARL_HEIGHT, TNT_ENTITY_Y_OFFSET,
-    TNT_EXPLOSION_RADIUS,
-};
-use crate::physics::entities::movement::{
-    MovementLegacy, MovementPost1205, MovementPost1212, PearlMovement, PearlVersion,
-};
+use crate::physics::constants::constants::{FLOAT_PRECISION_EPSILON, TNT_HEIGHT, TNT_RADIUS};
+use crate::physics::entities::explosion::ExplosionKind;
+use crate::physics::entities::movement::run_tick_sequence;
 use crate::physics::entities::pearl_entities::PearlEntity;
+use crate::physics::entities::profile::PhysicsProfile;
+use crate::physics::entities::projectile::ProjectileKind;
 use crate::physics::entities::tnt_entities::TNTEntity;
+use crate::physics::world::collision_world::{BlockHit, CollisionWorld};
 use crate::physics::world::space::Space3D;
-use std::collections::LinkedList;
 
 pub struct SimResult {
     pub tick: u32,
     pub position: Space3D,
     pub motion: Space3D,
     pub distance: f64,
+    /// Highest point the pearl reached on its way to `position`.
+    pub peak_y: f64,
 }
 
 pub fn run(
     data: &GeneralData,
     destination: Option<Space3D>,
     max_ticks: u32,
-    world_collisions: &[AABBBox],
-    version: PearlVersion,
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
 ) -> Option<CalculationResult> {
-    match version {
-        PearlVersion::Legacy => {
-            run_internal::<MovementLegacy>(data, destination, max_ticks, world_collisions)
-        }
-        PearlVersion::Post1205 => {
-            run_internal::<MovementPost1205>(data, destination, max_ticks, world_collisions)
-        }
-        PearlVersion::Post1212 => {
-            run_internal::<MovementPost1212>(data, destination, max_ticks, world_collisions)
-        }
-    }
-}
-
-fn run_internal<M: PearlMovement + Clone>(
-    data: &GeneralData,
-    destination: Option<Space3D>,
-    max_ticks: u32,
-    world_collisions: &[AABBBox],
-) -> Option<CalculationResult> {
-    let mut pearl = PearlEntity::<M>::new(data.pearl_position, data.pearl_motion);
-    let mut tnt_entities: Vec<TNTEntity> = data
-        .tnt_charges
-        .iter()
-        .map(|tnt| TNTEntity::new(tnt.position, tnt.fuse))
-        .collect();
+    let mut pearl = PearlEntity::with_kind(
+        data.pearl_position,
+        data.pearl_motion,
+        data.projectile,
+        profile,
+    );
+    let mut tnt_entities = spawn_tnt_entities(data);
 
-    let mut traces: LinkedList<Space3D> = LinkedList::new();
-    let mut motion_traces: LinkedList<Space3D> = LinkedList::new();
+    let mut records = vec![TickRecord::of(0, &pearl, Vec::new())];
 
-    traces.push_back(pearl.data.position);
-    motion_traces.push_back(pearl.data.motion);
+    let mut outcome = PearlOutcome::TimedOut;
+    let mut final_tick = max_ticks;
 
     for tick in 0..max_ticks {
-        for tnt in &mut tnt_entities {
-            if tnt.fuse == tick {
-                pearl.data.motion += calculate_tnt_motion(pearl.data.position, tnt.data.position);
-            }
-        }
-
-        M::run_tick_sequence(&mut pearl, world_collisions);
+        let (record, ended) = advance(
+            &mut pearl,
+            &mut tnt_entities,
+            tick,
+            data.projectile,
+            world_collisions,
+            profile,
+        );
+        records.extend(record);
 
-        traces.push_back(pearl.data.position);
-        motion_traces.push_back(pearl.data.motion);
+        if let Some(ended) = ended {
+            final_tick = records.last().map_or(tick, |record| record.tick);
+            outcome = ended;
+            break;
+        }
     }
 
     let final_landing_pos = pearl.data.position;
@@ -77,135 +63,127 @@ fn run_internal<M: PearlMovement + Clone>(
     let (distance_to_dest, is_success) = match destination {
         Some(dest) => {
             let distance = final_landing_pos.distance_2d(&dest);
-            (distance, distance <= 0.25)
+            (
+                distance,
+                distance <= 0.25
+                    && !matches!(outcome, PearlOutcome::Void | PearlOutcome::OutsideBorder),
+            )
         }
         None => (0.0, false),
     };
 
-    let mut final_traces: Vec<Space3D> = traces.into_iter().collect();
-    let mut final_motion_traces: Vec<Space3D> = motion_traces.into_iter().collect();
-
-    final_traces.dedup();
-    final_motion_traces.dedup();
-
     Some(CalculationResult {
         landing_position: final_landing_pos,
-        pearl_trace: final_traces,
-        pearl_motion_trace: final_motion_traces,
+        records,
         is_successful: is_success,
-        tick: max_ticks,
+        tick: final_tick,
         final_motion: pearl.data.motion,
         distance: distance_to_dest,
+        outcome,
     })
 }
 
-pub fn scan_trajectory(
-    data: &GeneralData,
-    destination: Space3D,
-    max_tick: u32,
-    valid_ticks: &[bool],
-    world_collisions: &[AABBBox],
-    version: PearlVersion,
-    max_distance_sq: f64,
-    check_3d: bool,
-    plane_intercept_y: bool,
-) -> Vec<SimResult> {
-    match version {
-        PearlVersion::Legacy => scan_internal::<MovementLegacy>(
-            data,
-            destination,
-            max_tick,
-            valid_ticks,
-            world_collisions,
-            max_distance_sq,
-            check_3d,
-            plane_intercept_y,
-        ),
-        PearlVersion::Post1205 => scan_internal::<MovementPost1205>(
-            data,
-            destination,
-            max_tick,
-            valid_ticks,
-            world_collisions,
-            max_distance_sq,
-            check_3d,
-            plane_intercept_y,
-        ),
-        PearlVersion::Post1212 => scan_internal::<MovementPost1212>(
-            data,
-            destination,
-            max_tick,
-            valid_ticks,
-            world_collisions,
-            max_distance_sq,
-            check_3d,
-            plane_intercept_y,
-        ),
+/// Runs tick `tick` of a flight: detonates the charges due, then moves the pearl.
+/// Returns the pearl's record for the end of the tick, unless it stalled before the tick ran,
+/// and the outcome if the flight ended. A stalled tick changes nothing, so it can be run again.
+pub(crate) fn advance(
+    pearl: &mut PearlEntity,
+    tnt_entities: &mut [TNTEntity],
+    tick: u32,
+    projectile: ProjectileKind,
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
+) -> (Option<TickRecord>, Option<PearlOutcome>) {
+    if stalls(projectile, world_collisions, profile, pearl.data.position) {
+        return (None, Some(PearlOutcome::Stalled));
     }
-}
 
-pub fn check_landing(
-    data: &GeneralData,
-    destination: Space3D,
-    max_ticks: u32,
-    world_collisions: &[AABBBox],
-    version: PearlVersion,
-    max_distance_sq: f64,
-) -> Option<(Space3D, Space3D, u32)> {
-    match version {
-        PearlVersion::Legacy => check_internal::<MovementLegacy>(
-            data,
-            destination,
-            max_ticks,
-            world_collisions,
-            max_distance_sq,
-        ),
-        PearlVersion::Post1205 => check_internal::<MovementPost1205>(
-            data,
-            destination,
-            max_ticks,
-            world_collisions,
-            max_distance_sq,
-        ),
-        PearlVersion::Post1212 => check_internal::<MovementPost1212>(
-            data,
-            destination,
-            max_ticks,
-            world_collisions,
-            max_distance_sq,
-        ),
+    let exploded = tick_tnt_entities(tnt_entities, tick, pearl, world_collisions, profile);
+
+    let hit = run_tick_sequence(pearl, world_collisions);
+    let mut record = TickRecord::of(tick + 1, pearl, exploded);
+    if let Some((_, face)) = hit {
+        record.collide(face);
     }
+
+    let ended = match hit {
+        Some((position, face)) => Some(PearlOutcome::Landed(BlockHit {
+            position,
+            face,
+            tick: tick + 1,
+        })),
+        None => flight_ended(world_collisions, pearl.data.position),
+    };
+    (Some(record), ended)
 }
 
-fn scan_internal<M: PearlMovement + Clone>(
+pub fn scan_trajectory(
     data: &GeneralData,
     destination: Space3D,
     max_tick: u32,
     valid_ticks: &[bool],
-    world_collisions: &[AABBBox],
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
     max_distance_sq: f64,
     check_3d: bool,
     plane_intercept_y: bool,
 ) -> Vec<SimResult> {
     let mut results = Vec::new();
-    let mut pearl = PearlEntity::<M>::new(data.pearl_position, data.pearl_motion);
-    let mut tnt_entities: Vec<TNTEntity> = data
-        .tnt_charges
-        .iter()
-        .map(|tnt| TNTEntity::new(tnt.position, tnt.fuse))
-        .collect();
+    let mut pearl = PearlEntity::with_kind(
+        data.pearl_position,
+        data.pearl_motion,
+        data.projectile,
+        profile,
+    );
+    let mut tnt_entities = spawn_tnt_entities(data);
     let mut previous_pos = pearl.data.position;
+    let mut peak_y = previous_pos.y;
 
     for tick in 1..=max_tick {
-        for tnt in &mut tnt_entities {
-            if tnt.fuse == tick - 1 {
-                pearl.data.motion += calculate_tnt_motion(pearl.data.position, tnt.data.position);
-            }
+        tick_tnt_entities(
+            &mut tnt_entities,
+            tick - 1,
+            &mut pearl,
+            world_collisions,
+            profile,
+        );
+
+        // Anything past a stall is never reached.
+        if stalls(
+            data.projectile,
+            world_collisions,
+            profile,
+            pearl.data.position,
+        ) {
+            break;
         }
 
-        M::run_tick_sequence(&mut pearl, world_collisions);
+        let hit = run_tick_sequence(&mut pearl, world_collisions);
 
         let current_pos = pearl.data.position;
+        peak_y = peak_y.max(current_pos.y);
+
+        // The pearl teleports on its first contact, so that is where it really lands.
+        if hit.is_some() {
+            let dist_sq = if check_3d {
+                current_pos.distance_sq(&destination)
+            } else {
+                current_pos.distance_2d_sq(&destination)
+            };
+            if dist_sq <= max_distance_sq {
+                results.push(SimResult {
+                    tick,
+                    position: current_pos,
+                    motion: pearl.data.motion,
+                    distance: dist_sq.sqrt(),
+                    peak_y,
+                });
+            }
+            break;
+        }
+        if flight_ended(world_collisions, current_pos).is_some() {
+            break;
+        }
 
         if (tick as usize) < valid_ticks.len() && valid_ticks[tick as usize] {
             if let Some((hit_pos, dist_sq)) = measure_hit(
@@ -221,6 +199,7 @@ fn scan_internal<M: PearlMovement + Clone>(
                         position: hit_pos,
                         motion: pearl.data.motion,
                         distance: dist_sq.sqrt(),
+                        peak_y,
                     });
                 }
             }
@@ -235,28 +214,45 @@ fn scan_internal<M: PearlMovement + Clone>(
     results
 }
 
-fn check_internal<M: PearlMovement + Clone>(
+pub fn check_landing(
     data: &GeneralData,
     destination: Space3D,
     max_ticks: u32,
-    world_collisions: &[AABBBox],
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
     max_distance_sq: f64,
 ) -> Option<(Space3D, Space3D, u32)> {
-    let mut pearl = PearlEntity::<M>::new(data.pearl_position, data.pearl_motion);
-    let mut tnt_entities: Vec<TNTEntity> = data
-        .tnt_charges
-        .iter()
-        .map(|tnt| TNTEntity::new(tnt.position, tnt.fuse))
-        .collect();
+    let mut pearl = PearlEntity::with_kind(
+        data.pearl_position,
+        data.pearl_motion,
+        data.projectile,
+        profile,
+    );
+    let mut tnt_entities = spawn_tnt_entities(data);
 
     for tick in 0..max_ticks {
-        for tnt in &mut tnt_entities {
-            if tnt.fuse == tick {
-                pearl.data.motion += calculate_tnt_motion(pearl.data.position, tnt.data.position);
-            }
-        }
+        tick_tnt_entities(
+            &mut tnt_entities,
+            tick,
+            &mut pearl,
+            world_collisions,
+            profile,
+        );
 
-        M::run_tick_sequence(&mut pearl, world_collisions);
+        if stalls(
+            data.projectile,
+            world_collisions,
+            profile,
+            pearl.data.position,
+        ) {
+            return None;
+        }
+        if run_tick_sequence(&mut pearl, world_collisions).is_some() {
+            break;
+        }
+        if flight_ended(world_collisions, pearl.data.position).is_some() {
+            return None;
+        }
 
         if pearl.data.motion.length_sq() < FLOAT_PRECISION_EPSILON {
             break;
@@ -272,22 +268,190 @@ fn check_internal<M: PearlMovement + Clone>(
     }
 }
 
-pub fn calculate_tnt_motion(pearl_pos: Space3D, tnt_pos: Space3D) -> Space3D {
-    let mut tnt_pos_adjusted = tnt_pos;
-    tnt_pos_adjusted.y += TNT_ENTITY_Y_OFFSET;
+fn flight_ended(world_collisions: &CollisionWorld, position: Space3D) -> Option<PearlOutcome> {
+    if world_collisions.is_below_void(position.y) {
+        Some(PearlOutcome::Void)
+    } else if world_collisions.is_outside_border(position) {
+        Some(PearlOutcome::OutsideBorder)
+    } else {
+        None
+    }
+}
+
+// From 1.21.2 ender pearls load the chunk they are in, so only other projectiles freeze.
+fn stalls(
+    projectile: ProjectileKind,
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
+    position: Space3D,
+) -> bool {
+    !(profile.pearls_load_chunks() && projectile == ProjectileKind::EnderPearl)
+        && !world_collisions.is_entity_ticking(position)
+}
 
-    let distance_vec = pearl_pos - tnt_pos_adjusted;
+fn spawn_tnt_entities(data: &GeneralData) -> Vec<TNTEntity> {
+    data.tnt_charges.iter().map(spawn_tnt).collect()
+}
+
+pub(crate) fn spawn_tnt(tnt: &TNT) -> TNTEntity {
+    let mut entity = TNTEntity::new(tnt.position, tnt.fuse);
+    entity.data.motion = tnt.motion;
+    entity.source = tnt.source;
+    entity.amount = tnt.amount;
+    entity.anchored = tnt.anchored;
+    entity
+}
+
+/// Detonates every charge whose fuse ends on `tick`, then moves the charges still primed.
+/// A charge explodes where it stands before its final move, matching a fuse counted from zero.
+fn tick_tnt_entities(
+    tnt_entities: &mut [TNTEntity],
+    tick: u32,
+    pearl: &mut PearlEntity,
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
+) -> Vec<usize> {
+    let mut exploded = Vec::new();
+    for index in 0..tnt_entities.len() {
+        if tnt_entities[index].fuse != tick {
+            continue;
+        }
+        // An empty group keeps its index but has nothing to explode.
+        if tnt_entities[index].amount > 0 {
+            exploded.push(index);
+        }
+        let source = tnt_entities[index].data.position;
+        let kind = tnt_entities[index].source;
+        let amount = tnt_entities[index].amount;
+        detonate(
+            source,
+            kind,
+            amount,
+            pearl,
+            tnt_entities.iter_mut().filter(|other| other.fuse > tick),
+            world_collisions,
+            profile,
+        );
+    }
+
+    for tnt in tnt_entities
+        .iter_mut()
+        .filter(|tnt| tnt.fuse > tick && !tnt.anchored && tnt.source.is_simulated())
+    {
+        tnt.tick_motion(world_collisions);
+    }
+    exploded
+}
+
+/// Pushes the pearl and every charge in `targets` away from `amount` explosions at `source`.
+pub(crate) fn detonate<'a>(
+    source: Space3D,
+    kind: ExplosionKind,
+    amount: u32,
+    pearl: &mut PearlEntity,
+    targets: impl Iterator<Item = &'a mut TNTEntity>,
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
+) {
+    let amount = amount as f64;
+    pearl.data.motion += explosion_knockback(
+        pearl.data.position,
+        pearl.data.position.y + pearl.physics.eye_height,
+        &pearl.data.bounding_box,
+        source,
+        kind,
+        world_collisions,
+        profile,
+    ) * amount;
+
+    for other in targets {
+        other.data.motion += explosion_knockback(
+            other.data.position,
+            other.data.position.y,
+            &other.data.bounding_box,
+            source,
+            kind,
+            world_collisions,
+            profile,
+        ) * amount;
+    }
+}
+
+pub fn calculate_tnt_motion(
+    pearl_pos: Space3D,
+    tnt_pos: Space3D,
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
+) -> Space3D {
+    calculate_explosion_motion(
+        pearl_pos,
+        tnt_pos,
+        ExplosionKind::Tnt,
+        ProjectileKind::EnderPearl,
+        world_collisions,
+        profile,
+    )
+}
+
+pub fn calculate_explosion_motion(
+    projectile_pos: Space3D,
+    source_pos: Space3D,
+    kind: ExplosionKind,
+    projectile: ProjectileKind,
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
+) -> Space3D {
+    let physics = profile.projectile_physics(projectile);
+    explosion_knockback(
+        projectile_pos,
+        projectile_pos.y + physics.eye_height,
+        &AABBBox::from_feet(projectile_pos, physics.half_width, physics.height),
+        source_pos,
+        kind,
+        world_collisions,
+        profile,
+    )
+}
+
+/// Explosions push primed TNT from its feet rather than its eye height.
+pub fn calculate_tnt_motion_on_tnt(
+    target_pos: Space3D,
+    tnt_pos: Space3D,
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
+) -> Space3D {
+    explosion_knockback(
+        target_pos,
+        target_pos.y,
+        &AABBBox::from_feet(target_pos, TNT_RADIUS, TNT_HEIGHT),
+        tnt_pos,
+        ExplosionKind::Tnt,
+        world_collisions,
+        profile,
+    )
+}
+
+fn explosion_knockback(
+    target_pos: Space3D,
+    target_y: f64,
+    target_box: &AABBBox,
+    source_pos: Space3D,
+    kind: ExplosionKind,
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
+) -> Space3D {
+    let mut center = source_pos;
+    center.y += profile.explosion_center_offset(kind);
+    let radius = kind.radius();
+
+    let distance_vec = target_pos - center;
     let distance_scalar = distance_vec.length();
 
-    if distance_scalar >= TNT_EXPLOSION_RADIUS {
+    if distance_scalar >= radius {
         return Space3D::default();
     }
 
-    let mut explosion_vec = Space3D::new(
-        distance_vec.x,
-        pearl_pos.y + (PEARL_EXPLOSION_Y_FACTOR * PEARL_HEIGHT) - tnt_pos_adjusted.y,
-        distance_vec.z,
-    );
+    let mut explosion_vec = Space3D::new(distance_vec.x, target_y - center.y, distance_vec.z);
 
     let explosion_vec_len = explosion_vec.length();
     if explosion_vec_len.abs() < FLOAT_PRECISION_EPSILON {
@@ -295,7 +459,10 @@ pub fn calculate_tnt_motion(pearl_pos: Space3D, tnt_pos: Space3D) -> Space3D {
     }
     explosion_vec /= explosion_vec_len;
 
-    let explosion_strength = 1.0 - (distance_scalar / TNT_EXPLOSION_RADIUS);
+    let explosion_strength = (1.0 - (distance_scalar / radius))
+        * world_collisions.exposure(center, target_box)
+        * kind.knockback_multiplier()
+        * profile.explosion.knockback_scale;
 
     explosion_vec * explosion_strength
 }
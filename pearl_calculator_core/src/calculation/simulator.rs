@@ -0,0 +1,162 @@
+use crate::calculation::inputs::{GeneralData, TNT};
+use crate::calculation::results::{PearlOutcome, TickRecord};
+use crate::calculation::simulation;
+use crate::physics::entities::explosion::ExplosionKind;
+use crate::physics::entities::pearl_entities::PearlEntity;
+use crate::physics::entities::profile::PhysicsProfile;
+use crate::physics::entities::projectile::ProjectileKind;
+use crate::physics::entities::tnt_entities::TNTEntity;
+use crate::physics::world::collision_world::CollisionWorld;
+use crate::physics::world::space::Space3D;
+
+/// Everything that changes while a flight runs, so it can be saved and restored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatorState {
+    /// Ticks run so far. The next step runs this tick.
+    pub tick: u32,
+    pub pearl: PearlEntity,
+    pub tnt_entities: Vec<TNTEntity>,
+    /// One record per tick run, starting from the launch state on tick zero.
+    pub records: Vec<TickRecord>,
+    /// Set once the flight has ended; stepping does nothing until it is cleared.
+    pub outcome: Option<PearlOutcome>,
+}
+
+/// A flight advanced one tick at a time, with the same physics as [`simulation::run`].
+#[derive(Debug, Clone)]
+pub struct Simulator {
+    projectile: ProjectileKind,
+    world_collisions: CollisionWorld,
+    profile: PhysicsProfile,
+    state: SimulatorState,
+}
+
+impl Simulator {
+    pub fn new(
+        data: &GeneralData,
+        world_collisions: CollisionWorld,
+        profile: PhysicsProfile,
+    ) -> Self {
+        let pearl = PearlEntity::with_kind(
+            data.pearl_position,
+            data.pearl_motion,
+            data.projectile,
+            &profile,
+        );
+        let records = vec![TickRecord::of(0, &pearl, Vec::new())];
+
+        Self {
+            projectile: data.projectile,
+            state: SimulatorState {
+                tick: 0,
+                pearl,
+                tnt_entities: data.tnt_charges.iter().map(simulation::spawn_tnt).collect(),
+                records,
+                outcome: None,
+            },
+            world_collisions,
+            profile,
+        }
+    }
+
+    /// Runs up to `ticks` ticks, stopping early if the flight ends, and returns the records of
+    /// the ticks that ran.
+    pub fn step(&mut self, ticks: u32) -> &[TickRecord] {
+        let first = self.state.records.len();
+        for _ in 0..ticks {
+            if self.state.outcome.is_some() {
+                break;
+            }
+
+            let (record, ended) = simulation::advance(
+                &mut self.state.pearl,
+                &mut self.state.tnt_entities,
+                self.state.tick,
+                self.projectile,
+                &self.world_collisions,
+                &self.profile,
+            );
+            // A stalled pearl never finishes the tick.
+            if let Some(record) = record {
+                self.state.tick = record.tick;
+                self.state.records.push(record);
+            }
+            self.state.outcome = ended;
+        }
+        &self.state.records[first..]
+    }
+
+    /// Pushes the pearl and every charge that has not exploded yet away from `amount` explosions
+    /// at `source`, as if they went off at the start of the current tick.
+    pub fn inject_explosion(&mut self, source: Space3D, kind: ExplosionKind, amount: u32) {
+        let tick = self.state.tick;
+        simulation::detonate(
+            source,
+            kind,
+            amount,
+            &mut self.state.pearl,
+            self.state
+                .tnt_entities
+                .iter_mut()
+                .filter(|tnt| tnt.fuse >= tick),
+            &self.world_collisions,
+            &self.profile,
+        );
+    }
+
+    /// Adds a charge, whose fuse counts from the launch like every other charge, and returns
+    /// its index.
+    pub fn add_tnt(&mut self, tnt: &TNT) -> usize {
+        self.state.tnt_entities.push(simulation::spawn_tnt(tnt));
+        self.state.tnt_entities.len() - 1
+    }
+
+    pub fn tick(&self) -> u32 {
+        self.state.tick
+    }
+
+    pub fn outcome(&self) -> Option<PearlOutcome> {
+        self.state.outcome
+    }
+
+    /// Lets a flight that has ended run again, such as after moving a landed pearl.
+    pub fn clear_outcome(&mut self) {
+        self.state.outcome = None;
+    }
+
+    pub fn records(&self) -> &[TickRecord] {
+        &self.state.records
+    }
+
+    pub fn pearl(&self) -> &PearlEntity {
+        &self.state.pearl
+    }
+
+    pub fn pearl_mut(&mut self) -> &mut PearlEntity {
+        &mut self.state.pearl
+    }
+
+    pub fn tnt_entities(&self) -> &[TNTEntity] {
+        &self.state.tnt_entities
+    }
+
+    pub fn tnt_entities_mut(&mut self) -> &mut [TNTEntity] {
+        &mut self.state.tnt_entities
+    }
+
+    pub fn world_collisions(&self) -> &CollisionWorld {
+        &self.world_collisions
+    }
+
+    pub fn profile(&self) -> &PhysicsProfile {
+        &self.profile
+    }
+
+    pub fn snapshot(&self) -> SimulatorState {
+        self.state.clone()
+    }
+
+    pub fn restore(&mut self, state: SimulatorState) {
+        self.state = state;
+    }
+}
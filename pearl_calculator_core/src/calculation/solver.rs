@@ -1,7 +1,11 @@
-use crate::physics::constants::constants::{FLOAT_PRECISION_EPSILON, PEARL_DRAG_MULTIPLIER};
-use crate::physics::entities::movement::PearlVersion;
+use crate::calculation::optimizer::{LatticeTarget, invert};
+use crate::physics::constants::constants::FLOAT_PRECISION_EPSILON;
+use crate::physics::entities::profile::TickOrder;
+use crate::physics::entities::projectile::ProjectilePhysics;
 use crate::physics::world::space::Space3D;
-use std::collections::HashMap;
+
+// Relative to the axes' squared lengths; small enough to leave the fit exact in practice.
+const FIT_DAMPING: f64 = 1e-9;
 
 pub struct SolverInput {
     pub red_vec: Space3D,
@@ -11,91 +15,72 @@ pub struct SolverInput {
     pub start_motion: Space3D,
     pub destination: Space3D,
     pub max_ticks: u32,
-    pub version: PearlVersion,
+    pub tick_order: TickOrder,
+    pub physics: ProjectilePhysics,
     pub plane_intercept_y: bool,
 }
 
-pub fn solve_theoretical_tnt(input: &SolverInput) -> HashMap<(i32, i32, i32), Vec<u32>> {
+pub fn solve_theoretical_tnt(input: &SolverInput) -> Vec<LatticeTarget> {
     let true_distance = input.destination - input.start_pos;
 
-    let mut groups: HashMap<(i32, i32, i32), Vec<u32>> = HashMap::new();
-    let drag_multiplier = PEARL_DRAG_MULTIPLIER;
-    let denominator_constant = 1.0 - drag_multiplier;
+    let mut targets = Vec::new();
 
     let denominator = input.red_vec.z * input.blue_vec.x - input.blue_vec.z * input.red_vec.x;
     let is_3d_solve = input.vert_vec.length_sq() > FLOAT_PRECISION_EPSILON;
 
     if !is_3d_solve && denominator.abs() < FLOAT_PRECISION_EPSILON {
-        return HashMap::new();
+        return Vec::new();
     }
 
-    let gravity = -crate::physics::constants::constants::PEARL_GRAVITY_ACCELERATION;
-    let mut sim_grav_vel = 0.0;
-    let mut sim_grav_pos = 0.0;
-    let mut sim_motion_vel = input.start_motion;
-    let mut sim_motion_pos = Space3D::default();
-    let mut previous_divider = 0.0;
-
-    for tick in 1..=input.max_ticks {
-        let previous_grav_pos = sim_grav_pos;
-        let previous_motion_pos = sim_motion_pos;
-
-        sim_grav_vel = input
-            .version
-            .apply_grav_drag_tick(sim_grav_vel, gravity, drag_multiplier);
-        sim_grav_pos += sim_grav_vel;
-
-        let (new_vx, dx) = input
-            .version
-            .apply_motion_tick(sim_motion_vel.x, drag_multiplier);
-        let (new_vy, dy) = input
-            .version
-            .apply_motion_tick(sim_motion_vel.y, drag_multiplier);
-        let (new_vz, dz) = input
-            .version
-            .apply_motion_tick(sim_motion_vel.z, drag_multiplier);
-        sim_motion_vel = Space3D::new(new_vx, new_vy, new_vz);
-        sim_motion_pos += Space3D::new(dx, dy, dz);
-
-        let mut compensated_distance = true_distance;
-        compensated_distance.y -= sim_grav_pos + sim_motion_pos.y;
-        compensated_distance.x -= sim_motion_pos.x;
-        compensated_distance.z -= sim_motion_pos.z;
+    let mut previous = FlightTick {
+        tick: 0,
+        base: Space3D::default(),
+        divider: 0.0,
+    };
 
-        let numerator = 1.0 - drag_multiplier.powi(tick as i32);
-        let divider = input.version.get_projection_multiplier(drag_multiplier) * numerator
-            / denominator_constant;
+    for flight_tick in unpowered_flight(
+        input.start_motion,
+        input.max_ticks,
+        input.tick_order,
+        input.physics,
+    ) {
+        let tick = flight_tick.tick;
+        let divider = flight_tick.divider;
+        let compensated_distance = true_distance - flight_tick.base;
 
         if is_3d_solve {
             let target_motion = compensated_distance / divider;
             if let Some((r, b, v)) =
                 solve_tnt_system_3d(input.red_vec, input.blue_vec, input.vert_vec, target_motion)
             {
-                push_candidate(&mut groups, tick, r, b, v);
+                push_target(
+                    &mut targets,
+                    tick,
+                    vec![r, b, v],
+                    vec![
+                        input.red_vec * divider,
+                        input.blue_vec * divider,
+                        input.vert_vec * divider,
+                    ],
+                );
             }
         } else if input.plane_intercept_y {
-            let previous_base = Space3D::new(
-                previous_motion_pos.x,
-                previous_grav_pos + previous_motion_pos.y,
-                previous_motion_pos.z,
-            );
-            let current_base = Space3D::new(
-                sim_motion_pos.x,
-                sim_grav_pos + sim_motion_pos.y,
-                sim_motion_pos.z,
-            );
-
-            if let Some((red, blue)) = solve_standard_plane_intercept_tnt(
+            if let Some((red, blue, divider_at_intercept)) = solve_standard_plane_intercept_tnt(
                 input.red_vec,
                 input.blue_vec,
                 true_distance,
-                previous_base,
-                current_base,
-                previous_divider,
+                previous.base,
+                flight_tick.base,
+                previous.divider,
                 divider,
                 denominator,
             ) {
-                push_candidate(&mut groups, tick, red, blue, 0.0);
+                push_target(
+                    &mut targets,
+                    tick,
+                    vec![red, blue],
+                    horizontal_axes(input, divider_at_intercept),
+                );
             }
         } else {
             let true_red = (compensated_distance.z * input.blue_vec.x
@@ -104,38 +89,200 @@ pub fn solve_theoretical_tnt(input: &SolverInput) -> HashMap<(i32, i32, i32), Ve
             let true_blue =
                 (compensated_distance.x - true_red * input.red_vec.x) / input.blue_vec.x;
 
-            push_candidate(
-                &mut groups,
+            push_target(
+                &mut targets,
                 tick,
-                true_red / divider,
-                true_blue / divider,
-                0.0,
+                vec![true_red / divider, true_blue / divider],
+                horizontal_axes(input, divider),
             );
         }
 
-        previous_divider = divider;
+        previous = flight_tick;
     }
 
-    groups
+    targets
+}
+
+/// Like [`SolverInput`], for a cannon with one push per charge of each of its active groups.
+pub struct GroupSolverInput {
+    pub axes: Vec<Space3D>,
+    /// How far one charge along each axis has already moved the pearl by the start, for charges
+    /// that went off before it. Missing entries are zero.
+    pub offsets: Vec<Space3D>,
+    pub start_pos: Space3D,
+    pub start_motion: Space3D,
+    pub destination: Space3D,
+    pub max_ticks: u32,
+    pub tick_order: TickOrder,
+    pub physics: ProjectilePhysics,
+    /// Aim for the destination's height as well, which needs axes spanning all three dimensions.
+    pub solve_3d: bool,
+}
+
+/// The continuous counts that bring the pearl closest to the destination on every tick.
+/// With more groups than dimensions, the smallest such counts are used as the lattice centre.
+pub fn solve_group_targets(input: &GroupSolverInput) -> Vec<LatticeTarget> {
+    let true_distance = input.destination - input.start_pos;
+    let flatten = |vector: Space3D| {
+        if input.solve_3d {
+            vector
+        } else {
+            Space3D::new(vector.x, 0.0, vector.z)
+        }
+    };
+    let offset_of = |index: usize| input.offsets.get(index).copied().unwrap_or_default();
+
+    unpowered_flight(
+        input.start_motion,
+        input.max_ticks,
+        input.tick_order,
+        input.physics,
+    )
+    .into_iter()
+    .filter_map(|flight_tick| {
+        let divider = flight_tick.divider;
+        let axes: Vec<Space3D> = input
+            .axes
+            .iter()
+            .enumerate()
+            .map(|(index, axis)| flatten(offset_of(index) + *axis * divider))
+            .collect();
+        let target = flatten(true_distance - flight_tick.base);
+        let ideal = fit_counts(&axes, target)?;
+
+        let reached = axes
+            .iter()
+            .zip(&ideal)
+            .fold(Space3D::default(), |sum, (axis, count)| {
+                sum + *axis * *count
+            });
+        Some(LatticeTarget {
+            tick: flight_tick.tick,
+            residual: (target - reached).length(),
+            ideal,
+            axes,
+        })
+    })
+    .collect()
+}
+
+/// Whether some three of the axes are linearly independent.
+pub fn spans_3d(axes: &[Space3D]) -> bool {
+    axes.iter().enumerate().any(|(i, a)| {
+        axes.iter().enumerate().skip(i + 1).any(|(j, b)| {
+            axes.iter()
+                .skip(j + 1)
+                .any(|c| a.dot(b.cross(*c)).abs() > FLOAT_PRECISION_EPSILON)
+        })
+    })
 }
 
-fn push_candidate(
-    groups: &mut HashMap<(i32, i32, i32), Vec<u32>>,
+/// Where the pearl would be relative to its start on `tick` with no charges fired, and how far
+/// one block per tick of extra launch motion would move it by then.
+struct FlightTick {
     tick: u32,
-    red: f64,
-    blue: f64,
-    vertical: f64,
-) {
-    let rounded_red = red.round() as i32;
-    let rounded_blue = blue.round() as i32;
-    let rounded_vertical = vertical.round() as i32;
-
-    if rounded_red >= 0 && rounded_blue >= 0 && rounded_vertical >= 0 {
-        groups
-            .entry((rounded_red, rounded_blue, rounded_vertical))
-            .or_default()
-            .push(tick);
+    base: Space3D,
+    divider: f64,
+}
+
+fn unpowered_flight(
+    start_motion: Space3D,
+    max_ticks: u32,
+    tick_order: TickOrder,
+    physics: ProjectilePhysics,
+) -> Vec<FlightTick> {
+    let drag_multiplier = physics.drag;
+    let gravity = -physics.gravity;
+
+    let mut sim_grav_vel = 0.0;
+    let mut sim_grav_pos = 0.0;
+    let mut sim_motion_vel = start_motion;
+    let mut sim_motion_pos = Space3D::default();
+    let mut flight = Vec::with_capacity(max_ticks as usize);
+
+    for tick in 1..=max_ticks {
+        sim_grav_vel = tick_order.apply_grav_drag_tick(sim_grav_vel, gravity, drag_multiplier);
+        sim_grav_pos += sim_grav_vel;
+
+        let (new_vx, dx) = tick_order.apply_motion_tick(sim_motion_vel.x, drag_multiplier);
+        let (new_vy, dy) = tick_order.apply_motion_tick(sim_motion_vel.y, drag_multiplier);
+        let (new_vz, dz) = tick_order.apply_motion_tick(sim_motion_vel.z, drag_multiplier);
+        sim_motion_vel = Space3D::new(new_vx, new_vy, new_vz);
+        sim_motion_pos += Space3D::new(dx, dy, dz);
+
+        flight.push(FlightTick {
+            tick,
+            base: Space3D::new(
+                sim_motion_pos.x,
+                sim_grav_pos + sim_motion_pos.y,
+                sim_motion_pos.z,
+            ),
+            divider: launch_divider(tick, tick_order, physics),
+        });
     }
+    flight
+}
+
+/// How far one block per tick of extra launch motion moves the pearl by `tick`.
+pub fn launch_divider(tick: u32, tick_order: TickOrder, physics: ProjectilePhysics) -> f64 {
+    let drag_multiplier = physics.drag;
+    let projection = tick_order.get_projection_multiplier(drag_multiplier);
+    // Without drag the geometric series is just one block per tick, as with wind charges.
+    if (1.0 - drag_multiplier).abs() < FLOAT_PRECISION_EPSILON {
+        return projection * tick as f64;
+    }
+    projection * (1.0 - drag_multiplier.powi(tick as i32)) / (1.0 - drag_multiplier)
+}
+
+// Least squares when the axes are independent. Otherwise the axes can reach the target in many
+// ways, and a slightly damped solve settles on the one with the smallest counts.
+fn fit_counts(axes: &[Space3D], target: Space3D) -> Option<Vec<f64>> {
+    let mut gram: Vec<Vec<f64>> = axes
+        .iter()
+        .map(|a| axes.iter().map(|b| a.dot(*b)).collect())
+        .collect();
+    let projected: Vec<f64> = axes.iter().map(|axis| axis.dot(target)).collect();
+
+    let inverse = match invert(gram.clone()) {
+        Some(inverse) => inverse,
+        None => {
+            let damping = FIT_DAMPING * (0..axes.len()).map(|i| gram[i][i]).sum::<f64>();
+            if damping <= 0.0 {
+                return None;
+            }
+            for (i, row) in gram.iter_mut().enumerate() {
+                row[i] += damping;
+            }
+            invert(gram)?
+        }
+    };
+
+    Some(
+        inverse
+            .iter()
+            .map(|row| row.iter().zip(&projected).map(|(a, b)| a * b).sum())
+            .collect(),
+    )
+}
+
+// Ticks that would need a negative charge count are out of reach of the cannon.
+fn push_target(targets: &mut Vec<LatticeTarget>, tick: u32, ideal: Vec<f64>, axes: Vec<Space3D>) {
+    if ideal.iter().all(|count| count.round() >= 0.0) {
+        targets.push(LatticeTarget {
+            tick,
+            ideal,
+            axes,
+            residual: 0.0,
+        });
+    }
+}
+
+// Only the horizontal landing error is scored when the height is left to the trajectory.
+fn horizontal_axes(input: &SolverInput, divider: f64) -> Vec<Space3D> {
+    [input.red_vec, input.blue_vec]
+        .into_iter()
+        .map(|axis| Space3D::new(axis.x * divider, 0.0, axis.z * divider))
+        .collect()
 }
 
 fn solve_standard_plane_intercept_tnt(
@@ -147,7 +294,7 @@ fn solve_standard_plane_intercept_tnt(
     previous_divider: f64,
     current_divider: f64,
     denominator: f64,
-) -> Option<(f64, f64)> {
+) -> Option<(f64, f64, f64)> {
     let y_from_x_coeff = (red_vec.z * blue_vec.y - blue_vec.z * red_vec.y) / denominator;
     let y_from_z_coeff = (blue_vec.x * red_vec.y - red_vec.x * blue_vec.y) / denominator;
 
@@ -188,7 +335,7 @@ fn solve_standard_plane_intercept_tnt(
     let red = (intercept_motion_z * blue_vec.x - intercept_motion_x * blue_vec.z) / denominator;
     let blue = (intercept_motion_x * red_vec.z - intercept_motion_z * red_vec.x) / denominator;
 
-    Some((red, blue))
+    Some((red, blue, divider_at_intercept))
 }
 
 fn solve_tnt_system_3d(
@@ -0,0 +1,282 @@
+use crate::calculation::calculation::SolveOptions;
+use crate::calculation::inputs::{Cannon, GeneralData, TNT};
+use crate::calculation::optimizer::{SearchParams, search_lattice_limited};
+use crate::calculation::results::PearlOutcome;
+use crate::calculation::simulation;
+use crate::calculation::solver::{GroupSolverInput, solve_group_targets, spans_3d};
+use crate::calculation::vectors::resolve_vectors_for_direction;
+use crate::physics::constants::constants::FLOAT_PRECISION_EPSILON;
+use crate::physics::entities::explosion::ExplosionKind;
+use crate::physics::entities::profile::PhysicsProfile;
+use crate::physics::world::collision_world::CollisionWorld;
+use crate::physics::world::direction::Direction;
+use crate::physics::world::space::Space3D;
+use std::collections::HashMap;
+
+// Stage combinations few enough to launch and solve one by one.
+const ENUMERATED_COMBINATIONS: u64 = 64;
+// Launches the aimers are re-linearised around in each round of the stage search.
+const LINEARISED_LAUNCHES: usize = 16;
+// Closest stage counts kept per launch in each round.
+const STAGE_BRANCHING: usize = 4;
+const LINEARISATION_ROUNDS: usize = 3;
+// Nodes searched per tick when picking stage counts, where stage and aimer axes always overlap.
+const STAGE_NODE_LIMIT: u64 = 4096;
+
+/// Where the pearl is when the aimers explode, and the stage counts that put it there.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AimerLaunch {
+    pub position: Space3D,
+    pub motion: Space3D,
+    pub stage_counts: Vec<u32>,
+    /// Highest point of the flight through the stages.
+    pub peak_y: f64,
+}
+
+/// The stage counts worth launching and solving the aimers for, with the number of lattice nodes
+/// searched to pick them.
+///
+/// Small cannons try every combination. Otherwise the launch is linear in each stage's count, so
+/// the stage and aimer counts are solved together in one lattice and the stage counts of the
+/// closest landings are kept.
+pub fn stage_candidates(
+    cannon: &Cannon,
+    destination: Space3D,
+    options: &SolveOptions,
+) -> (Vec<Vec<u32>>, u64) {
+    let combinations = cannon.stages.iter().fold(1u64, |product, stage| {
+        product.saturating_mul(stage.max_tnt as u64 + 1)
+    });
+    if combinations <= ENUMERATED_COMBINATIONS {
+        return (stage_combinations(cannon), 0);
+    }
+    linearised_stage_counts(cannon, destination, options)
+}
+
+/// Every combination of per-stage charge counts the cannon can fire.
+pub fn stage_combinations(cannon: &Cannon) -> Vec<Vec<u32>> {
+    cannon
+        .stages
+        .iter()
+        .fold(vec![Vec::new()], |combinations, stage| {
+            combinations
+                .into_iter()
+                .flat_map(|counts| {
+                    (0..=stage.max_tnt).map(move |amount| {
+                        let mut next = counts.clone();
+                        next.push(amount);
+                        next
+                    })
+                })
+                .collect()
+        })
+}
+
+pub fn stage_charges(cannon: &Cannon, counts: &[u32]) -> Vec<TNT> {
+    cannon
+        .stages
+        .iter()
+        .zip(counts)
+        .filter(|(_, amount)| **amount > 0)
+        .map(|(stage, amount)| anchored_charge(stage.position, stage.fuse, *amount))
+        .collect()
+}
+
+pub fn anchored_charge(position: Space3D, fuse: u32, amount: u32) -> TNT {
+    TNT {
+        position,
+        fuse,
+        motion: Space3D::default(),
+        source: ExplosionKind::Tnt,
+        amount,
+        anchored: true,
+    }
+}
+
+/// Flies the pearl through the stages to the aim tick, or returns `None` if its flight ends
+/// first. A cannon without stages or an aim fuse launches from where the pearl starts.
+pub fn launch_through_stages(
+    cannon: &Cannon,
+    counts: &[u32],
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
+) -> Option<AimerLaunch> {
+    if cannon.aim_fuse == 0 {
+        return Some(AimerLaunch {
+            position: cannon.pearl.position,
+            motion: cannon.pearl.motion,
+            stage_counts: counts.to_vec(),
+            peak_y: cannon.pearl.position.y,
+        });
+    }
+
+    let data = GeneralData {
+        pearl_position: cannon.pearl.position,
+        pearl_motion: cannon.pearl.motion,
+        tnt_charges: stage_charges(cannon, counts),
+        projectile: cannon.projectile,
+    };
+    let result = simulation::run(&data, None, cannon.aim_fuse, world_collisions, profile)?;
+    match result.outcome {
+        PearlOutcome::TimedOut => {
+            let peak_y = result
+                .records
+                .iter()
+                .fold(cannon.pearl.position.y, |peak, record| {
+                    peak.max(record.position.y)
+                });
+            Some(AimerLaunch {
+                position: result.landing_position,
+                motion: result.final_motion,
+                stage_counts: counts.to_vec(),
+                peak_y,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn linearised_stage_counts(
+    cannon: &Cannon,
+    destination: Space3D,
+    options: &SolveOptions,
+) -> (Vec<Vec<u32>>, u64) {
+    let world_collisions = options.world_collisions;
+    let profile = options.profile;
+    let unfired = vec![0; cannon.stages.len()];
+    let Some(base) = launch_through_stages(cannon, &unfired, world_collisions, profile) else {
+        return (Vec::new(), 0);
+    };
+
+    // How far one charge of each stage moves the pearl by the aim tick, and how much launch
+    // motion it adds.
+    let per_charge: Vec<(Space3D, Space3D)> = (0..cannon.stages.len())
+        .map(|index| {
+            let mut counts = unfired.clone();
+            counts[index] = 1;
+            launch_through_stages(cannon, &counts, world_collisions, profile)
+                .map(|launch| (launch.position - base.position, launch.motion - base.motion))
+                .unwrap_or_default()
+        })
+        .collect();
+
+    // The aimers push the pearl differently depending on where the stages left it, so they are
+    // re-linearised around the launches of the counts picked in the previous round.
+    let mut picked: Vec<Vec<u32>> = Vec::new();
+    let mut frontier = vec![base.clone()];
+    let mut explored = 0;
+    for _ in 0..LINEARISATION_ROUNDS {
+        let mut next = Vec::new();
+        for around in &frontier {
+            let (closest, visited) =
+                closest_stage_counts(cannon, &base, &per_charge, around, destination, options);
+            explored += visited;
+            for counts in closest.into_iter().take(STAGE_BRANCHING) {
+                if picked.contains(&counts) {
+                    continue;
+                }
+                next.extend(launch_through_stages(
+                    cannon,
+                    &counts,
+                    world_collisions,
+                    profile,
+                ));
+                picked.push(counts);
+            }
+        }
+        next.truncate(LINEARISED_LAUNCHES);
+        frontier = next;
+    }
+    (picked, explored)
+}
+
+/// Stage counts ranked by how close the linearised launch, with the aimers pushing as they would
+/// from `around`, lands to the destination.
+fn closest_stage_counts(
+    cannon: &Cannon,
+    base: &AimerLaunch,
+    per_charge: &[(Space3D, Space3D)],
+    around: &AimerLaunch,
+    destination: Space3D,
+    options: &SolveOptions,
+) -> (Vec<Vec<u32>>, u64) {
+    let profile = options.profile;
+    let yaw = around.position.angle_to_yaw(&destination);
+    let mut closest: HashMap<Vec<u32>, f64> = HashMap::new();
+    let mut explored = 0;
+
+    for flight_direction in Direction::from_angle_with_fallbacks(yaw) {
+        let (red_vec, blue_vec, vert_vec) = resolve_vectors_for_direction(
+            cannon,
+            flight_direction,
+            around.position,
+            options.world_collisions,
+            profile,
+        );
+        let params = SearchParams {
+            max_distance: options.max_distance,
+            max_tnt: options.max_tnt,
+            max_vertical_tnt: options.max_vertical_tnt,
+            has_vertical: cannon.vertical_tnt.is_some(),
+            is_valid_3d: vert_vec.length_sq() > FLOAT_PRECISION_EPSILON,
+            cannon_mode: cannon.mode,
+        };
+        let (side_limit, vertical_limit) = params.limits();
+
+        let mut axes: Vec<Space3D> = per_charge.iter().map(|(_, motion)| *motion).collect();
+        let mut bounds: Vec<(u32, u32)> = cannon
+            .stages
+            .iter()
+            .map(|stage| (0, stage.max_tnt))
+            .collect();
+        axes.extend([red_vec, blue_vec]);
+        bounds.extend([(0, side_limit), (0, side_limit)]);
+        if params.is_valid_3d {
+            axes.push(vert_vec);
+            bounds.push((0, vertical_limit));
+        }
+
+        let targets = solve_group_targets(&GroupSolverInput {
+            solve_3d: (params.is_valid_3d || options.plane_intercept_y) && spans_3d(&axes),
+            axes,
+            offsets: per_charge.iter().map(|(position, _)| *position).collect(),
+            start_pos: base.position,
+            start_motion: base.motion,
+            destination,
+            max_ticks: options.max_ticks - cannon.aim_fuse,
+            tick_order: profile.tick_order_for(cannon.projectile),
+            physics: profile.projectile_physics(cannon.projectile),
+        });
+
+        for target in targets {
+            let search =
+                search_lattice_limited(&target.ideal, &target.axes, &bounds, STAGE_NODE_LIMIT);
+            explored += search.explored;
+            if let Some(best) = search.best {
+                let error = best.error.hypot(target.residual);
+                let stage_counts = best.counts[..cannon.stages.len()].to_vec();
+                let entry = closest.entry(stage_counts).or_insert(f64::INFINITY);
+                *entry = entry.min(error);
+            }
+        }
+    }
+
+    // Counts the model expects to land in range come first, fewest charges first, since the
+    // linearisation holds best close to the launch it was made around.
+    let total = |counts: &[u32]| counts.iter().map(|count| *count as u64).sum::<u64>();
+    let mut ranked: Vec<(Vec<u32>, f64)> = closest.into_iter().collect();
+    ranked.sort_by(|a, b| {
+        let in_range = |error: f64| error <= options.max_distance;
+        in_range(b.1)
+            .cmp(&in_range(a.1))
+            .then_with(|| match in_range(a.1) {
+                true => total(&a.0).cmp(&total(&b.0)),
+                false => a.1.total_cmp(&b.1),
+            })
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    (
+        ranked.into_iter().map(|(counts, _)| counts).collect(),
+        explored,
+    )
+}
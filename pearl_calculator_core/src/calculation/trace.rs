@@ -1,39 +1,145 @@
-use crate::calculation::inputs::{Cannon, GeneralData};
-use crate::calculation::results::{CalculationResult, TNTResult};
-use crate::calculation::simulation;
-use crate::physics::aabb::aabb_box::AABBBox;
+use crate::calculation::inputs::{Cannon, GeneralData, TNT};
+use crate::calculation::results::{CalculationResult, ChunkReport, TNTResult};
+use crate::calculation::simulation::{self, SimResult};
+use crate::calculation::solver::launch_divider;
+use crate::calculation::vectors::ChargeAxes;
 use crate::physics::constants::constants::FLOAT_PRECISION_EPSILON;
-use crate::physics::entities::movement::PearlVersion;
+use crate::physics::entities::profile::PhysicsProfile;
+use crate::physics::entities::projectile::ProjectileKind;
+use crate::physics::world::collision_world::CollisionWorld;
 use crate::physics::world::direction::Direction;
 use crate::physics::world::space::Space3D;
 use crate::utils::parallel::*;
 use std::collections::HashMap;
 
+/// How a candidate's pearl is launched and how close it has to land.
+#[derive(Debug, Clone, Copy)]
+pub struct FlightCheck<'a> {
+    /// Motion the pearl has before the charges being solved for push it.
+    pub pearl_motion: Space3D,
+    pub destination: Space3D,
+    pub max_distance_sq: f64,
+    pub plane_intercept_y: bool,
+    pub world_collisions: &'a CollisionWorld,
+    pub profile: &'a PhysicsProfile,
+    pub projectile: ProjectileKind,
+}
+
+/// Flies every red, blue and vertical candidate from `axes.position`, keeping those that land
+/// in range, closest first.
 pub fn validate_candidates(
     candidates: Vec<((u32, u32, u32), Vec<u32>)>,
-    red_vec: Space3D,
-    blue_vec: Space3D,
-    vert_vec: Space3D,
-    pearl_position: Space3D,
-    pearl_motion: Space3D,
-    destination: Space3D,
-    max_distance_sq: f64,
-    plane_intercept_y: bool,
-    version: PearlVersion,
+    axes: &ChargeAxes,
+    flight: &FlightCheck,
+    calculation_direction: Direction,
+) -> Vec<TNTResult> {
+    let check_3d =
+        flight.plane_intercept_y || axes.vectors[2].length_sq() > FLOAT_PRECISION_EPSILON;
+    let pearl_position = axes.position;
+    let tick_order = flight.profile.tick_order_for(flight.projectile);
+    let physics = flight.profile.projectile_physics(flight.projectile);
+
+    validate_counts(
+        candidates
+            .into_iter()
+            .map(|((red, blue, vertical), ticks)| (vec![red, blue, vertical], ticks))
+            .collect(),
+        axes,
+        check_3d,
+        flight,
+    )
+    .into_iter()
+    .map(|(counts, hit)| {
+        let (yaw, pitch) = aim_of(hit.position - pearl_position);
+        TNTResult {
+            distance: hit.distance,
+            tick: hit.tick,
+            blue: counts[1],
+            red: counts[0],
+            vertical: counts[2],
+            stages: Vec::new(),
+            counts: Vec::new(),
+            total: counts.iter().sum(),
+            pearl_end_pos: hit.position,
+            pearl_end_motion: hit.motion,
+            direction: calculation_direction,
+            peak_y: hit.peak_y,
+            sensitivity: axes.sensitivity(
+                &counts,
+                launch_divider(hit.tick, tick_order, physics),
+                check_3d,
+            ),
+            yaw,
+            pitch,
+        }
+    })
+    .collect()
+}
+
+/// Same as [`validate_candidates`] for a cannon with one axis per active group. `groups` maps
+/// each axis to its group's index in the cannon, and `group_count` is how many groups it has.
+pub fn validate_group_candidates(
+    candidates: Vec<(Vec<u32>, Vec<u32>)>,
+    axes: &ChargeAxes,
+    groups: &[usize],
+    group_count: usize,
+    check_3d: bool,
+    flight: &FlightCheck,
     calculation_direction: Direction,
 ) -> Vec<TNTResult> {
-    let pearl_start_absolute_pos = pearl_position;
-    let check_3d = plane_intercept_y || vert_vec.length_sq() > FLOAT_PRECISION_EPSILON;
+    let pearl_position = axes.position;
+    let tick_order = flight.profile.tick_order_for(flight.projectile);
+    let physics = flight.profile.projectile_physics(flight.projectile);
+
+    validate_counts(candidates, axes, check_3d, flight)
+        .into_iter()
+        .map(|(active_counts, hit)| {
+            let mut counts = vec![0; group_count];
+            for (group, count) in groups.iter().zip(&active_counts) {
+                counts[*group] = *count;
+            }
+            let (yaw, pitch) = aim_of(hit.position - pearl_position);
+            TNTResult {
+                distance: hit.distance,
+                tick: hit.tick,
+                blue: 0,
+                red: 0,
+                vertical: 0,
+                stages: Vec::new(),
+                total: counts.iter().sum(),
+                counts,
+                pearl_end_pos: hit.position,
+                pearl_end_motion: hit.motion,
+                direction: calculation_direction,
+                peak_y: hit.peak_y,
+                sensitivity: axes.sensitivity(
+                    &active_counts,
+                    launch_divider(hit.tick, tick_order, physics),
+                    check_3d,
+                ),
+                yaw,
+                pitch,
+            }
+        })
+        .collect()
+}
 
-    let raw_results: Vec<TNTResult> = candidates
+/// Flies every candidate and keeps its closest hit on one of its ticks, closest first.
+fn validate_counts(
+    candidates: Vec<(Vec<u32>, Vec<u32>)>,
+    axes: &ChargeAxes,
+    check_3d: bool,
+    flight: &FlightCheck,
+) -> Vec<(Vec<u32>, SimResult)> {
+    let raw_results: Vec<(Vec<u32>, SimResult)> = candidates
         .into_par_iter()
-        .flat_map(|((r_u32, b_u32, v_u32), mut ticks)| {
+        .filter_map(|(counts, mut ticks)| {
             ticks.sort_unstable();
             ticks.dedup();
 
             let max_sim_tick = *ticks.last().unwrap_or(&0);
             if max_sim_tick == 0 {
-                return Vec::new();
+                return None;
             }
 
             let mut valid_ticks_map = vec![false; (max_sim_tick + 1) as usize];
@@ -41,154 +147,151 @@ pub fn validate_candidates(
                 valid_ticks_map[t as usize] = true;
             }
 
-            let total = r_u32 + b_u32 + v_u32;
-
-            let tnt_impact =
-                red_vec * (r_u32 as f64) + blue_vec * (b_u32 as f64) + vert_vec * (v_u32 as f64);
+            let tnt_impact = axes
+                .vectors
+                .iter()
+                .zip(&counts)
+                .fold(Space3D::default(), |impact, (axis, count)| {
+                    impact + *axis * (*count as f64)
+                });
 
             let data = GeneralData {
-                pearl_position,
-                pearl_motion: pearl_motion + tnt_impact,
+                pearl_position: axes.position,
+                pearl_motion: flight.pearl_motion + tnt_impact,
                 tnt_charges: vec![],
+                projectile: flight.projectile,
             };
 
             let hits = simulation::scan_trajectory(
                 &data,
-                destination,
+                flight.destination,
                 max_sim_tick,
                 &valid_ticks_map,
-                &[],
-                version,
-                max_distance_sq,
+                flight.world_collisions,
+                flight.profile,
+                flight.max_distance_sq,
                 check_3d,
-                plane_intercept_y,
+                flight.plane_intercept_y,
             );
 
-            let mut results = Vec::new();
-
-            if let Some(best_hit) = hits.into_iter().min_by(|a, b| {
-                a.distance
-                    .partial_cmp(&b.distance)
-                    .unwrap()
-                    .then_with(|| a.tick.cmp(&b.tick))
-            }) {
-                let flight = best_hit.position - pearl_start_absolute_pos;
-                let h_dist = (flight.x.powi(2) + flight.z.powi(2)).sqrt();
-                let yaw = (-flight.x).atan2(flight.z).to_degrees();
-                let pitch = (-flight.y).atan2(h_dist).to_degrees();
-
-                let out_dir = calculation_direction;
-
-                results.push(TNTResult {
-                    distance: best_hit.distance,
-                    tick: best_hit.tick,
-                    blue: b_u32,
-                    red: r_u32,
-                    vertical: v_u32,
-                    total,
-                    pearl_end_pos: best_hit.position,
-                    pearl_end_motion: best_hit.motion,
-                    direction: out_dir,
-                    yaw,
-                    pitch,
-                });
-            }
-            results
+            hits.into_iter()
+                .min_by(|a, b| {
+                    a.distance
+                        .partial_cmp(&b.distance)
+                        .unwrap()
+                        .then_with(|| a.tick.cmp(&b.tick))
+                })
+                .map(|best_hit| (counts, best_hit))
         })
         .collect();
 
-    let mut best_map: HashMap<(u32, u32, u32), TNTResult> = HashMap::new();
-    for res in raw_results {
-        let key = (res.red, res.blue, res.vertical);
-        match best_map.entry(key) {
+    let mut best_map: HashMap<Vec<u32>, SimResult> = HashMap::new();
+    for (counts, hit) in raw_results {
+        match best_map.entry(counts) {
             std::collections::hash_map::Entry::Vacant(e) => {
-                e.insert(res);
+                e.insert(hit);
             }
             std::collections::hash_map::Entry::Occupied(mut e) => {
                 let curr = e.get();
-                if (res.distance - curr.distance).abs() < FLOAT_PRECISION_EPSILON {
-                    if res.tick < curr.tick {
-                        e.insert(res);
+                if (hit.distance - curr.distance).abs() < FLOAT_PRECISION_EPSILON {
+                    if hit.tick < curr.tick {
+                        e.insert(hit);
                     }
-                } else if res.distance < curr.distance {
-                    e.insert(res);
+                } else if hit.distance < curr.distance {
+                    e.insert(hit);
                 }
             }
         }
     }
 
-    let mut final_results: Vec<TNTResult> = best_map.into_values().collect();
-    final_results.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap());
+    let mut final_results: Vec<(Vec<u32>, SimResult)> = best_map.into_iter().collect();
+    final_results.sort_by(|a, b| a.1.distance.partial_cmp(&b.1.distance).unwrap());
     final_results
 }
 
+fn aim_of(flight: Space3D) -> (f64, f64) {
+    let h_dist = (flight.x.powi(2) + flight.z.powi(2)).sqrt();
+    let yaw = (-flight.x).atan2(flight.z).to_degrees();
+    let pitch = (-flight.y).atan2(h_dist).to_degrees();
+    (yaw, pitch)
+}
+
+/// Fires the solution's charges as entities, so the records show each one exploding. `stage_tnt`
+/// holds the charges fired in each of the cannon's stages, in order.
 pub fn calculate_pearl_trace(
     cannon: &Cannon,
     red_tnt: u32,
     blue_tnt: u32,
     vertical_tnt: u32,
+    stage_tnt: &[u32],
     direction: Direction,
     max_ticks: u32,
-    world_collisions: &[AABBBox],
-    version: PearlVersion,
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
 ) -> Option<CalculationResult> {
-    let (red_vec, blue_vec, vert_vec) =
-        super::vectors::resolve_vectors_for_direction(cannon, direction);
-
-    let total_tnt_motion = (red_vec * red_tnt as f64)
-        + (blue_vec * blue_tnt as f64)
-        + (vert_vec * vertical_tnt as f64);
-    let final_motion = cannon.pearl.motion + total_tnt_motion;
-
-    run_trace_internal(
-        cannon.pearl.position,
-        final_motion,
-        max_ticks,
-        world_collisions,
-        version,
-    )
+    let general_data = GeneralData {
+        pearl_position: cannon.pearl.position,
+        pearl_motion: cannon.pearl.motion,
+        tnt_charges: result_charges(
+            cannon,
+            red_tnt,
+            blue_tnt,
+            vertical_tnt,
+            stage_tnt,
+            direction,
+        ),
+        projectile: cannon.projectile,
+    };
+    simulation::run(&general_data, None, max_ticks, world_collisions, profile)
+}
+
+/// Lists the chunks a traced shot passes through. `origin` is the absolute position of the
+/// cannon's coordinate frame.
+pub fn calculate_chunk_report(result: &CalculationResult, origin: Space3D) -> ChunkReport {
+    ChunkReport::from_trace(&result.pearl_trace(), origin)
 }
 
+/// Every charge explodes on its own fuse tick, just before the pearl moves on that tick. Records
+/// index the charges as given, empty ones included.
 pub fn calculate_raw_trace(
     pearl_position: Space3D,
     pearl_motion: Space3D,
-    tnt_charges: Vec<(Space3D, u32)>,
+    tnt_charges: Vec<TNT>,
     max_ticks: u32,
-    world_collisions: &[AABBBox],
-    version: PearlVersion,
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
 ) -> Option<CalculationResult> {
-    let total_explosion_motion = tnt_charges
-        .iter()
-        .filter(|(_, count)| *count > 0)
-        .map(|(tnt_pos, count)| {
-            simulation::calculate_tnt_motion(pearl_position, *tnt_pos) * (*count as f64)
-        })
-        .fold(
-            Space3D::default(),
-            |accumulated_motion, motion_component| accumulated_motion + motion_component,
-        );
-
-    run_trace_internal(
+    let general_data = GeneralData {
         pearl_position,
-        pearl_motion + total_explosion_motion,
-        max_ticks,
-        world_collisions,
-        version,
-    )
+        pearl_motion,
+        tnt_charges,
+        projectile: ProjectileKind::EnderPearl,
+    };
+
+    simulation::run(&general_data, None, max_ticks, world_collisions, profile)
 }
 
-fn run_trace_internal(
-    position: Space3D,
-    motion: Space3D,
-    max_ticks: u32,
-    world_collisions: &[AABBBox],
-    version: PearlVersion,
-) -> Option<CalculationResult> {
-    let general_data = GeneralData {
-        pearl_position: position,
-        pearl_motion: motion,
-        tnt_charges: vec![],
-    };
+/// Every charge a solution fires, with the aimers detonating on the cannon's aim fuse.
+pub fn result_charges(
+    cannon: &Cannon,
+    red_tnt: u32,
+    blue_tnt: u32,
+    vertical_tnt: u32,
+    stage_tnt: &[u32],
+    direction: Direction,
+) -> Vec<TNT> {
+    let (red_pos, blue_pos) = super::vectors::resolve_tnt_for_direction(cannon, direction);
+    let aimers = [
+        (Some(red_pos), red_tnt),
+        (Some(blue_pos), blue_tnt),
+        (cannon.vertical_tnt, vertical_tnt),
+    ];
 
-    simulation::run(&general_data, None, max_ticks, world_collisions, version)
+    let mut tnt_charges = super::stages::stage_charges(cannon, stage_tnt);
+    tnt_charges.extend(aimers.into_iter().filter_map(|(position, amount)| {
+        position
+            .filter(|_| amount > 0)
+            .map(|position| super::stages::anchored_charge(position, cannon.aim_fuse, amount))
+    }));
+    tnt_charges
 }
@@ -1,15 +1,121 @@
 use crate::calculation::inputs::Cannon;
 use crate::calculation::simulation;
+use crate::physics::entities::explosion::ExplosionKind;
+use crate::physics::entities::profile::PhysicsProfile;
+use crate::physics::world::collision_world::CollisionWorld;
 use crate::physics::world::direction::Direction;
 use crate::physics::world::layout_direction::LayoutDirection;
 use crate::physics::world::space::Space3D;
 
+// Distance the pearl is moved either way to measure how the charge vectors change around it.
+const GRADIENT_STEP: f64 = 1.0 / 16.0;
+
+/// The push of one charge along each axis for a pearl at `position`, and how each push changes
+/// as the pearl moves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChargeAxes {
+    pub position: Space3D,
+    pub vectors: Vec<Space3D>,
+    /// Change in each vector per block the pearl moves along x, y and z.
+    pub gradients: [Vec<Space3D>; 3],
+}
+
+impl ChargeAxes {
+    pub fn measure(position: Space3D, push: impl Fn(Space3D) -> Vec<Space3D>) -> Self {
+        let gradients = [
+            Space3D::new(1.0, 0.0, 0.0),
+            Space3D::new(0.0, 1.0, 0.0),
+            Space3D::new(0.0, 0.0, 1.0),
+        ]
+        .map(|unit| {
+            let ahead = push(position + unit * GRADIENT_STEP);
+            let behind = push(position - unit * GRADIENT_STEP);
+            ahead
+                .iter()
+                .zip(&behind)
+                .map(|(ahead, behind)| (*ahead - *behind) / (2.0 * GRADIENT_STEP))
+                .collect()
+        });
+        Self {
+            position,
+            vectors: push(position),
+            gradients,
+        }
+    }
+
+    /// Blocks the landing moves per block the pearl starts away from `position`, along the
+    /// worst direction, when `counts` are fired and the pearl lands after `divider` blocks of
+    /// flight per block per tick of launch motion.
+    pub fn sensitivity(&self, counts: &[u32], divider: f64, check_3d: bool) -> f64 {
+        [
+            Space3D::new(1.0, 0.0, 0.0),
+            Space3D::new(0.0, 1.0, 0.0),
+            Space3D::new(0.0, 0.0, 1.0),
+        ]
+        .iter()
+        .zip(&self.gradients)
+        .map(|(unit, gradient)| {
+            let push = gradient
+                .iter()
+                .zip(counts)
+                .fold(Space3D::default(), |sum, (change, count)| {
+                    sum + *change * *count as f64
+                });
+            let shift = *unit + push * divider;
+            if check_3d {
+                shift.length()
+            } else {
+                Space3D::new(shift.x, 0.0, shift.z).length()
+            }
+        })
+        .fold(0.0, f64::max)
+    }
+}
+
+/// The red, blue and vertical charge axes for `direction`, measured around `pearl_calc_pos`.
+pub fn resolve_axes_for_direction(
+    cannon: &Cannon,
+    direction: Direction,
+    pearl_calc_pos: Space3D,
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
+) -> ChargeAxes {
+    ChargeAxes::measure(pearl_calc_pos, |position| {
+        let (red_vec, blue_vec, vert_vec) =
+            resolve_vectors_for_direction(cannon, direction, position, world_collisions, profile);
+        vec![red_vec, blue_vec, vert_vec]
+    })
+}
+
 pub fn resolve_vectors_for_direction(
     cannon: &Cannon,
     direction: Direction,
+    pearl_calc_pos: Space3D,
+    world_collisions: &CollisionWorld,
+    profile: &PhysicsProfile,
 ) -> (Space3D, Space3D, Space3D) {
-    let pearl_calc_pos = cannon.pearl.position;
+    let (red_tnt_loc, blue_tnt_loc) = resolve_tnt_for_direction(cannon, direction);
 
+    let push = |tnt_pos: Space3D| {
+        simulation::calculate_explosion_motion(
+            pearl_calc_pos,
+            tnt_pos,
+            ExplosionKind::Tnt,
+            cannon.projectile,
+            world_collisions,
+            profile,
+        )
+    };
+
+    let red_vec = push(red_tnt_loc);
+    let blue_vec = push(blue_tnt_loc);
+    let vert_vec = cannon.vertical_tnt.map(push).unwrap_or_default();
+
+    (red_vec, blue_vec, vert_vec)
+}
+
+/// Picks the red and blue charge positions that push the pearl towards `direction`.
+pub fn resolve_tnt_for_direction(cannon: &Cannon, direction: Direction) -> (Space3D, Space3D) {
     let blue_duper = cannon
         .default_blue_duper
         .unwrap_or(LayoutDirection::NorthEast);
@@ -39,16 +145,7 @@ pub fn resolve_vectors_for_direction(
         blue_tnt_loc = tnt_loc_from_layout(cannon, cardinal_bits_to_layout_direction(final_bits));
     }
 
-    let red_vec = simulation::calculate_tnt_motion(pearl_calc_pos, red_tnt_loc);
-    let blue_vec = simulation::calculate_tnt_motion(pearl_calc_pos, blue_tnt_loc);
-
-    let vert_vec = if let Some(v_pos) = cannon.vertical_tnt {
-        simulation::calculate_tnt_motion(pearl_calc_pos, v_pos)
-    } else {
-        Space3D::default()
-    };
-
-    (red_vec, blue_vec, vert_vec)
+    (red_tnt_loc, blue_tnt_loc)
 }
 
 fn tnt_loc_from_layout(cannon: &Cannon, dir: LayoutDirection) -> Space3D {
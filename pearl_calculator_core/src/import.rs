@@ -0,0 +1,5 @@
+pub mod anvil;
+pub mod block_shapes;
+pub mod block_volume;
+pub mod nbt;
+pub mod schematic;
@@ -0,0 +1,274 @@
+use crate::import::block_shapes::{self, BlockState};
+use crate::import::block_volume::BlockVolume;
+use crate::import::nbt::{self, NbtTag};
+use crate::import::schematic::{bits_for_palette, unpack_spanning};
+use crate::physics::aabb::aabb_box::AABBBox;
+use crate::physics::world::space::Space3D;
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+const SECTOR_BYTES: u64 = 4096;
+
+// Chunks saved before 1.16 pack block states across long boundaries.
+const FIRST_COMPACT_DATA_VERSION: i32 = 2527;
+
+type ChunkSections = HashMap<i32, BlockVolume>;
+
+pub struct AnvilWorld {
+    region_dir: PathBuf,
+    chunks: HashMap<(i32, i32), Option<ChunkSections>>,
+}
+
+impl AnvilWorld {
+    pub fn open(dimension_dir: &Path) -> Result<Self, Box<dyn Error>> {
+        let nested = dimension_dir.join("region");
+        let region_dir = if nested.is_dir() {
+            nested
+        } else {
+            dimension_dir.to_path_buf()
+        };
+
+        if !region_dir.is_dir() {
+            return Err(format!("Region directory {region_dir:?} does not exist").into());
+        }
+
+        Ok(Self {
+            region_dir,
+            chunks: HashMap::new(),
+        })
+    }
+
+    pub fn block_state(
+        &mut self,
+        x: i32,
+        y: i32,
+        z: i32,
+    ) -> Result<Option<&BlockState>, Box<dyn Error>> {
+        let sections = match self.load_chunk(x >> 4, z >> 4)? {
+            Some(sections) => sections,
+            None => return Ok(None),
+        };
+        Ok(sections.get(&(y >> 4)).and_then(|section| {
+            section.get(
+                x.rem_euclid(16) as usize,
+                y.rem_euclid(16) as usize,
+                z.rem_euclid(16) as usize,
+            )
+        }))
+    }
+
+    pub fn collisions_along(
+        &mut self,
+        trace: &[Space3D],
+        origin: Space3D,
+        margin: f64,
+    ) -> Result<Vec<AABBBox>, Box<dyn Error>> {
+        let reach = margin.max(0.0).ceil() as i32;
+        let mut blocks = BTreeSet::new();
+
+        for segment in trace.windows(2) {
+            let start = segment[0] + origin;
+            let end = segment[1] + origin;
+            let steps = (end - start).length().ceil().max(1.0) as u32;
+
+            for step in 0..=steps {
+                let point = start + (end - start) * (step as f64 / steps as f64);
+                let (bx, by, bz) = (
+                    point.x.floor() as i32,
+                    point.y.floor() as i32,
+                    point.z.floor() as i32,
+                );
+                for dx in -reach..=reach {
+                    for dy in -reach..=reach {
+                        for dz in -reach..=reach {
+                            blocks.insert((bx + dx, by + dy, bz + dz));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut boxes = Vec::new();
+        for (x, y, z) in blocks {
+            if let Some(state) = self.block_state(x, y, z)? {
+                let (local_x, local_y, local_z) = (
+                    x as f64 - origin.x,
+                    y as f64 - origin.y,
+                    z as f64 - origin.z,
+                );
+                boxes.extend(
+                    block_shapes::collision_boxes(state)
+                        .iter()
+                        .map(|shape| shape.offset(local_x, local_y, local_z)),
+                );
+            }
+        }
+        Ok(boxes)
+    }
+
+    fn load_chunk(
+        &mut self,
+        chunk_x: i32,
+        chunk_z: i32,
+    ) -> Result<Option<&ChunkSections>, Box<dyn Error>> {
+        if !self.chunks.contains_key(&(chunk_x, chunk_z)) {
+            let sections = match self.read_chunk_nbt(chunk_x, chunk_z)? {
+                Some(root) => Some(parse_sections(&root, chunk_x, chunk_z)?),
+                None => None,
+            };
+            self.chunks.insert((chunk_x, chunk_z), sections);
+        }
+        Ok(self.chunks[&(chunk_x, chunk_z)].as_ref())
+    }
+
+    fn read_chunk_nbt(&self, chunk_x: i32, chunk_z: i32) -> Result<Option<NbtTag>, Box<dyn Error>> {
+        let region_path = self
+            .region_dir
+            .join(format!("r.{}.{}.mca", chunk_x >> 5, chunk_z >> 5));
+        let mut file = match File::open(&region_path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let header_index = ((chunk_x & 31) + (chunk_z & 31) * 32) as u64;
+        let mut location = [0u8; 4];
+        file.seek(SeekFrom::Start(header_index * 4))?;
+        file.read_exact(&mut location)?;
+
+        let sector_offset = u32::from_be_bytes([0, location[0], location[1], location[2]]) as u64;
+        if sector_offset == 0 || location[3] == 0 {
+            return Ok(None);
+        }
+
+        let mut chunk_header = [0u8; 5];
+        file.seek(SeekFrom::Start(sector_offset * SECTOR_BYTES))?;
+        file.read_exact(&mut chunk_header)?;
+        let length = u32::from_be_bytes([
+            chunk_header[0],
+            chunk_header[1],
+            chunk_header[2],
+            chunk_header[3],
+        ]) as usize;
+        let compression = chunk_header[4];
+
+        let payload = if compression & 0x80 != 0 {
+            // Oversized chunks live in a sibling `.mcc` file.
+            std::fs::read(self.region_dir.join(format!("c.{chunk_x}.{chunk_z}.mcc")))?
+        } else {
+            let mut payload = vec![0u8; length.saturating_sub(1)];
+            file.read_exact(&mut payload)?;
+            payload
+        };
+
+        match compression & 0x7f {
+            1..=3 => Ok(Some(nbt::read_compressed(&payload)?)),
+            other => Err(format!(
+                "Chunk ({chunk_x}, {chunk_z}) uses unsupported compression type {other}"
+            )
+            .into()),
+        }
+    }
+}
+
+fn parse_sections(
+    root: &NbtTag,
+    chunk_x: i32,
+    chunk_z: i32,
+) -> Result<ChunkSections, Box<dyn Error>> {
+    let data_version = root
+        .get("DataVersion")
+        .and_then(NbtTag::as_i32)
+        .unwrap_or(FIRST_COMPACT_DATA_VERSION);
+
+    // 1.18 flattened the chunk root; older chunks keep everything under `Level`.
+    let (sections, legacy) = match root.get("sections") {
+        Some(sections) => (sections, false),
+        None => (
+            root.get("Level")
+                .and_then(|level| level.get("Sections"))
+                .ok_or_else(|| format!("Chunk ({chunk_x}, {chunk_z}) has no sections"))?,
+            true,
+        ),
+    };
+
+    let mut parsed = HashMap::new();
+    for section in sections.as_list().unwrap_or_default() {
+        let Some(section_y) = section.get("Y").and_then(NbtTag::as_i32) else {
+            continue;
+        };
+
+        let (palette_tag, packed) = if legacy {
+            (section.get("Palette"), section.get("BlockStates"))
+        } else {
+            let states = section.get("block_states");
+            (
+                states.and_then(|states| states.get("palette")),
+                states.and_then(|states| states.get("data")),
+            )
+        };
+        let Some(palette_tag) = palette_tag.and_then(NbtTag::as_list) else {
+            // Before 1.13 sections store numeric block ids instead of a palette.
+            if section.get("Blocks").is_some() {
+                return Err(format!(
+                    "Chunk ({chunk_x}, {chunk_z}) predates 1.13 and is not supported"
+                )
+                .into());
+            }
+            continue;
+        };
+
+        let palette: Vec<BlockState> = palette_tag
+            .iter()
+            .map(|entry| BlockState::from_nbt(entry).ok_or("malformed chunk palette entry"))
+            .collect::<Result<_, _>>()?;
+
+        let indices = match packed.and_then(NbtTag::as_long_array) {
+            Some(packed) if palette.len() > 1 => {
+                let bits = bits_for_palette(palette.len(), 4);
+                if data_version < FIRST_COMPACT_DATA_VERSION {
+                    unpack_spanning(packed, bits, 4096)?
+                } else {
+                    unpack_compact(packed, bits, 4096)?
+                }
+            }
+            _ => vec![0; 4096],
+        };
+
+        parsed.insert(
+            section_y,
+            BlockVolume::new(
+                (chunk_x * 16, section_y * 16, chunk_z * 16),
+                (16, 16, 16),
+                palette,
+                indices,
+            )?,
+        );
+    }
+
+    Ok(parsed)
+}
+
+fn unpack_compact(packed: &[i64], bits: u32, count: usize) -> Result<Vec<u32>, Box<dyn Error>> {
+    let per_long = (64 / bits) as usize;
+    if packed.len() < count.div_ceil(per_long) {
+        return Err(format!(
+            "packed block array holds {} longs but {} are needed",
+            packed.len(),
+            count.div_ceil(per_long)
+        )
+        .into());
+    }
+
+    let mask = (1u64 << bits) - 1;
+    Ok((0..count)
+        .map(|index| {
+            let long = packed[index / per_long] as u64;
+            let shift = (index % per_long) as u32 * bits;
+            ((long >> shift) & mask) as u32
+        })
+        .collect())
+}
@@ -0,0 +1,383 @@
+use crate::import::nbt::NbtTag;
+use crate::physics::aabb::aabb_box::AABBBox;
+use crate::physics::world::fluid::FluidKind;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlockState {
+    pub name: String,
+    pub properties: BTreeMap<String, String>,
+}
+
+impl BlockState {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            properties: BTreeMap::new(),
+        }
+    }
+
+    pub fn parse(state: &str) -> Self {
+        let (name, properties) = match state.split_once('[') {
+            Some((name, rest)) => (name, rest.trim_end_matches(']')),
+            None => (state, ""),
+        };
+
+        let properties = properties
+            .split(',')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .collect();
+
+        Self {
+            name: name.trim().to_string(),
+            properties,
+        }
+    }
+
+    pub fn from_nbt(tag: &NbtTag) -> Option<Self> {
+        let name = tag.get("Name")?.as_str()?;
+        let properties = tag
+            .get("Properties")
+            .and_then(NbtTag::as_compound)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            name: name.to_string(),
+            properties,
+        })
+    }
+
+    pub fn id(&self) -> &str {
+        self.name.strip_prefix("minecraft:").unwrap_or(&self.name)
+    }
+
+    pub fn property(&self, key: &str) -> Option<&str> {
+        self.properties.get(key).map(String::as_str)
+    }
+
+    fn is_set(&self, key: &str) -> bool {
+        matches!(self.property(key), Some("true" | "low" | "tall"))
+    }
+
+    pub fn is_full_cube(&self) -> bool {
+        matches!(shape_kind(self), ShapeKind::FullCube)
+    }
+}
+
+enum ShapeKind {
+    Empty,
+    FullCube,
+    Slab,
+    Stairs,
+    Wall,
+    Fence,
+    FenceGate,
+    Pane,
+    Carpet,
+    SnowLayer,
+}
+
+const NON_COLLIDING_BLOCKS: &[&str] = &[
+    "air",
+    "cave_air",
+    "void_air",
+    "water",
+    "lava",
+    "bubble_column",
+    "light",
+    "structure_void",
+    "fire",
+    "soul_fire",
+    "cobweb",
+    "nether_portal",
+    "end_portal",
+    "end_gateway",
+    "moving_piston",
+    "short_grass",
+    "grass",
+    "tall_grass",
+    "fern",
+    "large_fern",
+    "dead_bush",
+    "seagrass",
+    "tall_seagrass",
+    "kelp",
+    "kelp_plant",
+    "sugar_cane",
+    "wheat",
+    "carrots",
+    "potatoes",
+    "beetroots",
+    "nether_wart",
+    "sweet_berry_bush",
+    "redstone_wire",
+    "tripwire",
+    "tripwire_hook",
+    "lever",
+    "vine",
+    "glow_lichen",
+    "sculk_vein",
+    "hanging_roots",
+    "spore_blossom",
+    "weeping_vines",
+    "weeping_vines_plant",
+    "twisting_vines",
+    "twisting_vines_plant",
+    "cave_vines",
+    "cave_vines_plant",
+    "crimson_roots",
+    "warped_roots",
+    "nether_sprouts",
+    "dandelion",
+    "poppy",
+    "blue_orchid",
+    "allium",
+    "azure_bluet",
+    "oxeye_daisy",
+    "cornflower",
+    "lily_of_the_valley",
+    "wither_rose",
+    "torchflower",
+    "sunflower",
+    "lilac",
+    "rose_bush",
+    "peony",
+    "pink_petals",
+    "pumpkin_stem",
+    "melon_stem",
+    "attached_pumpkin_stem",
+    "attached_melon_stem",
+];
+
+const NON_COLLIDING_SUFFIXES: &[&str] = &[
+    "_button",
+    "_pressure_plate",
+    "_sign",
+    "_banner",
+    "_sapling",
+    "torch",
+    "rail",
+    "_tulip",
+    "_coral",
+    "_coral_fan",
+    "_coral_wall_fan",
+    "_mushroom",
+    "_fungus",
+];
+
+fn shape_kind(state: &BlockState) -> ShapeKind {
+    let id = state.id();
+
+    if NON_COLLIDING_BLOCKS.contains(&id)
+        || NON_COLLIDING_SUFFIXES
+            .iter()
+            .any(|suffix| id.ends_with(suffix))
+    {
+        ShapeKind::Empty
+    } else if id.ends_with("_slab") {
+        ShapeKind::Slab
+    } else if id.ends_with("_stairs") {
+        ShapeKind::Stairs
+    } else if id.ends_with("_wall") {
+        ShapeKind::Wall
+    } else if id.ends_with("_fence_gate") {
+        ShapeKind::FenceGate
+    } else if id.ends_with("_fence") {
+        ShapeKind::Fence
+    } else if id.ends_with("_pane") || id == "iron_bars" {
+        ShapeKind::Pane
+    } else if id.ends_with("_carpet") {
+        ShapeKind::Carpet
+    } else if id == "snow" {
+        ShapeKind::SnowLayer
+    } else {
+        ShapeKind::FullCube
+    }
+}
+
+fn px(pixels: f64) -> f64 {
+    pixels / 16.0
+}
+
+fn block_box(min_x: f64, min_y: f64, min_z: f64, max_x: f64, max_y: f64, max_z: f64) -> AABBBox {
+    AABBBox::new(
+        px(min_x),
+        px(min_y),
+        px(min_z),
+        px(max_x),
+        px(max_y),
+        px(max_z),
+    )
+}
+
+const SOURCE_FLUID_HEIGHT: f64 = 8.0 / 9.0;
+
+const ALWAYS_WATERLOGGED: &[&str] = &[
+    "bubble_column",
+    "seagrass",
+    "tall_seagrass",
+    "kelp",
+    "kelp_plant",
+];
+
+/// The fluid in a block and its surface height, assuming no matching fluid sits above it.
+pub fn fluid(state: &BlockState) -> Option<(FluidKind, f64)> {
+    let kind = match state.id() {
+        "water" => FluidKind::Water,
+        "lava" => FluidKind::Lava,
+        id if ALWAYS_WATERLOGGED.contains(&id) || state.property("waterlogged") == Some("true") => {
+            return Some((FluidKind::Water, SOURCE_FLUID_HEIGHT));
+        }
+        _ => return None,
+    };
+
+    // Levels 1-7 are flowing fluid that drops by a ninth per step; 8 and up are falling.
+    let height = match state
+        .property("level")
+        .and_then(|level| level.parse::<u32>().ok())
+    {
+        Some(level @ 1..=7) => (8 - level) as f64 / 9.0,
+        _ => SOURCE_FLUID_HEIGHT,
+    };
+    Some((kind, height))
+}
+
+pub fn collision_boxes(state: &BlockState) -> Vec<AABBBox> {
+    match shape_kind(state) {
+        ShapeKind::Empty => Vec::new(),
+        ShapeKind::FullCube => vec![block_box(0.0, 0.0, 0.0, 16.0, 16.0, 16.0)],
+        ShapeKind::Slab => match state.property("type") {
+            Some("top") => vec![block_box(0.0, 8.0, 0.0, 16.0, 16.0, 16.0)],
+            Some("double") => vec![block_box(0.0, 0.0, 0.0, 16.0, 16.0, 16.0)],
+            _ => vec![block_box(0.0, 0.0, 0.0, 16.0, 8.0, 16.0)],
+        },
+        ShapeKind::Stairs => stairs_boxes(state),
+        ShapeKind::Wall => cross_boxes(state, 4.0, 3.0, 24.0),
+        ShapeKind::Fence => cross_boxes(state, 2.0, 2.0, 24.0),
+        ShapeKind::Pane => cross_boxes(state, 1.0, 1.0, 16.0),
+        ShapeKind::FenceGate => {
+            if state.property("open") == Some("true") {
+                Vec::new()
+            } else if matches!(state.property("facing"), Some("east" | "west")) {
+                vec![block_box(6.0, 0.0, 0.0, 10.0, 24.0, 16.0)]
+            } else {
+                vec![block_box(0.0, 0.0, 6.0, 16.0, 24.0, 10.0)]
+            }
+        }
+        ShapeKind::Carpet => vec![block_box(0.0, 0.0, 0.0, 16.0, 1.0, 16.0)],
+        ShapeKind::SnowLayer => {
+            let layers: f64 = state
+                .property("layers")
+                .and_then(|layers| layers.parse().ok())
+                .unwrap_or(1.0);
+            if layers <= 1.0 {
+                Vec::new()
+            } else {
+                vec![block_box(0.0, 0.0, 0.0, 16.0, (layers - 1.0) * 2.0, 16.0)]
+            }
+        }
+    }
+}
+
+fn cross_boxes(state: &BlockState, node_width: f64, arm_width: f64, height: f64) -> Vec<AABBBox> {
+    let node_min = 8.0 - node_width;
+    let node_max = 8.0 + node_width;
+    let arm_min = 8.0 - arm_width;
+    let arm_max = 8.0 + arm_width;
+
+    let mut boxes = Vec::new();
+
+    // Walls only carry a post when `up` is set; fences and panes always have one.
+    if state.property("up").is_none_or(|up| up == "true") {
+        boxes.push(block_box(
+            node_min, 0.0, node_min, node_max, height, node_max,
+        ));
+    }
+    if state.is_set("north") {
+        boxes.push(block_box(arm_min, 0.0, 0.0, arm_max, height, arm_max));
+    }
+    if state.is_set("south") {
+        boxes.push(block_box(arm_min, 0.0, arm_min, arm_max, height, 16.0));
+    }
+    if state.is_set("west") {
+        boxes.push(block_box(0.0, 0.0, arm_min, arm_max, height, arm_max));
+    }
+    if state.is_set("east") {
+        boxes.push(block_box(arm_min, 0.0, arm_min, 16.0, height, arm_max));
+    }
+
+    boxes
+}
+
+#[derive(Clone, Copy)]
+enum Quadrant {
+    NorthWest,
+    NorthEast,
+    SouthWest,
+    SouthEast,
+}
+
+fn stairs_boxes(state: &BlockState) -> Vec<AABBBox> {
+    let is_top = state.property("half") == Some("top");
+    let (slab_min, slab_max, step_min, step_max) = if is_top {
+        (8.0, 16.0, 0.0, 8.0)
+    } else {
+        (0.0, 8.0, 8.0, 16.0)
+    };
+
+    let facing = state.property("facing").unwrap_or("north");
+    // (front-left, front-right, back-left, back-right) quadrants for each facing.
+    let (front_left, front_right, back_left, back_right) = match facing {
+        "south" => (
+            Quadrant::SouthEast,
+            Quadrant::SouthWest,
+            Quadrant::NorthEast,
+            Quadrant::NorthWest,
+        ),
+        "east" => (
+            Quadrant::NorthEast,
+            Quadrant::SouthEast,
+            Quadrant::NorthWest,
+            Quadrant::SouthWest,
+        ),
+        "west" => (
+            Quadrant::SouthWest,
+            Quadrant::NorthWest,
+            Quadrant::SouthEast,
+            Quadrant::NorthEast,
+        ),
+        _ => (
+            Quadrant::NorthWest,
+            Quadrant::NorthEast,
+            Quadrant::SouthWest,
+            Quadrant::SouthEast,
+        ),
+    };
+
+    let quadrants: &[Quadrant] = match state.property("shape").unwrap_or("straight") {
+        "outer_left" => &[front_left],
+        "outer_right" => &[front_right],
+        "inner_left" => &[front_left, front_right, back_left],
+        "inner_right" => &[front_left, front_right, back_right],
+        _ => &[front_left, front_right],
+    };
+
+    let mut boxes = vec![block_box(0.0, slab_min, 0.0, 16.0, slab_max, 16.0)];
+    boxes.extend(quadrants.iter().map(|quadrant| {
+        let (min_x, min_z) = match quadrant {
+            Quadrant::NorthWest => (0.0, 0.0),
+            Quadrant::NorthEast => (8.0, 0.0),
+            Quadrant::SouthWest => (0.0, 8.0),
+            Quadrant::SouthEast => (8.0, 8.0),
+        };
+        block_box(min_x, step_min, min_z, min_x + 8.0, step_max, min_z + 8.0)
+    }));
+    boxes
+}
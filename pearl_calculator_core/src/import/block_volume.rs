@@ -0,0 +1,151 @@
+use crate::import::block_shapes::{self, BlockState};
+use crate::physics::aabb::aabb_box::AABBBox;
+use crate::physics::world::fluid::FluidVolume;
+use crate::physics::world::space::Space3D;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockVolume {
+    pub offset: (i32, i32, i32),
+    pub size: (usize, usize, usize),
+    pub palette: Vec<BlockState>,
+    pub blocks: Vec<u32>,
+}
+
+impl BlockVolume {
+    pub fn new(
+        offset: (i32, i32, i32),
+        size: (usize, usize, usize),
+        palette: Vec<BlockState>,
+        blocks: Vec<u32>,
+    ) -> Result<Self, String> {
+        let expected = size.0 * size.1 * size.2;
+        if blocks.len() != expected {
+            return Err(format!(
+                "Block volume expects {expected} blocks but received {}",
+                blocks.len()
+            ));
+        }
+        if let Some(index) = blocks
+            .iter()
+            .find(|index| **index as usize >= palette.len())
+        {
+            return Err(format!(
+                "Block palette index {index} is out of range for {} entries",
+                palette.len()
+            ));
+        }
+
+        Ok(Self {
+            offset,
+            size,
+            palette,
+            blocks,
+        })
+    }
+
+    fn index(&self, x: usize, y: usize, z: usize) -> usize {
+        (y * self.size.2 + z) * self.size.0 + x
+    }
+
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Option<&BlockState> {
+        if x >= self.size.0 || y >= self.size.1 || z >= self.size.2 {
+            return None;
+        }
+        self.palette.get(self.blocks[self.index(x, y, z)] as usize)
+    }
+
+    pub fn fluid_volumes(&self, origin: Space3D) -> Vec<FluidVolume> {
+        let palette_fluids: Vec<_> = self.palette.iter().map(block_shapes::fluid).collect();
+        let fluid_at = |x: usize, y: usize, z: usize| {
+            palette_fluids[self.blocks[self.index(x, y, z)] as usize]
+        };
+
+        let mut volumes = Vec::new();
+        for y in 0..self.size.1 {
+            for z in 0..self.size.2 {
+                for x in 0..self.size.0 {
+                    let Some((kind, height)) = fluid_at(x, y, z) else {
+                        continue;
+                    };
+
+                    // Fluid with the same fluid above it fills the whole block.
+                    let covered = y + 1 < self.size.1
+                        && fluid_at(x, y + 1, z).is_some_and(|(above, _)| above == kind);
+                    let height = if covered { 1.0 } else { height };
+
+                    let block_x = origin.x + (self.offset.0 as f64) + (x as f64);
+                    let block_y = origin.y + (self.offset.1 as f64) + (y as f64);
+                    let block_z = origin.z + (self.offset.2 as f64) + (z as f64);
+
+                    volumes.push(FluidVolume::new(
+                        AABBBox::new(
+                            block_x,
+                            block_y,
+                            block_z,
+                            block_x + 1.0,
+                            block_y + height,
+                            block_z + 1.0,
+                        ),
+                        kind,
+                    ));
+                }
+            }
+        }
+        volumes
+    }
+
+    pub fn collision_boxes(&self, origin: Space3D) -> Vec<AABBBox> {
+        let palette_shapes: Vec<Vec<AABBBox>> = self
+            .palette
+            .iter()
+            .map(block_shapes::collision_boxes)
+            .collect();
+        let palette_full: Vec<bool> = self.palette.iter().map(BlockState::is_full_cube).collect();
+
+        let is_full = |x: usize, y: usize, z: usize| -> bool {
+            x < self.size.0
+                && y < self.size.1
+                && z < self.size.2
+                && palette_full[self.blocks[self.index(x, y, z)] as usize]
+        };
+
+        let mut boxes = Vec::new();
+        for y in 0..self.size.1 {
+            for z in 0..self.size.2 {
+                for x in 0..self.size.0 {
+                    let state = self.blocks[self.index(x, y, z)] as usize;
+                    let shapes = &palette_shapes[state];
+                    if shapes.is_empty() {
+                        continue;
+                    }
+
+                    // A full cube buried on every side can never be touched.
+                    if palette_full[state]
+                        && x > 0
+                        && y > 0
+                        && z > 0
+                        && is_full(x - 1, y, z)
+                        && is_full(x + 1, y, z)
+                        && is_full(x, y - 1, z)
+                        && is_full(x, y + 1, z)
+                        && is_full(x, y, z - 1)
+                        && is_full(x, y, z + 1)
+                    {
+                        continue;
+                    }
+
+                    let block_x = origin.x + (self.offset.0 as f64) + (x as f64);
+                    let block_y = origin.y + (self.offset.1 as f64) + (y as f64);
+                    let block_z = origin.z + (self.offset.2 as f64) + (z as f64);
+
+                    boxes.extend(
+                        shapes
+                            .iter()
+                            .map(|shape| shape.offset(block_x, block_y, block_z)),
+                    );
+                }
+            }
+        }
+        boxes
+    }
+}
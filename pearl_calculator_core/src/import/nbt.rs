@@ -0,0 +1,218 @@
+use flate2::read::{GzDecoder, ZlibDecoder};
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::Read;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NbtTag {
+    End,
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(Vec<NbtTag>),
+    Compound(HashMap<String, NbtTag>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl NbtTag {
+    pub fn get(&self, key: &str) -> Option<&NbtTag> {
+        match self {
+            NbtTag::Compound(entries) => entries.get(key),
+            _ => None,
+        }
+    }
+
+    pub fn as_compound(&self) -> Option<&HashMap<String, NbtTag>> {
+        match self {
+            NbtTag::Compound(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[NbtTag]> {
+        match self {
+            NbtTag::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            NbtTag::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            NbtTag::Byte(value) => Some(*value as i64),
+            NbtTag::Short(value) => Some(*value as i64),
+            NbtTag::Int(value) => Some(*value as i64),
+            NbtTag::Long(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_i32(&self) -> Option<i32> {
+        self.as_i64().map(|value| value as i32)
+    }
+
+    pub fn as_byte_array(&self) -> Option<&[i8]> {
+        match self {
+            NbtTag::ByteArray(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_int_array(&self) -> Option<&[i32]> {
+        match self {
+            NbtTag::IntArray(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_long_array(&self) -> Option<&[i64]> {
+        match self {
+            NbtTag::LongArray(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+pub fn read_compressed(bytes: &[u8]) -> Result<NbtTag, Box<dyn Error>> {
+    let mut decompressed = Vec::new();
+    match bytes {
+        [0x1f, 0x8b, ..] => {
+            GzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+        }
+        [0x78, ..] => {
+            ZlibDecoder::new(bytes).read_to_end(&mut decompressed)?;
+        }
+        _ => return read(bytes),
+    }
+    read(&decompressed)
+}
+
+pub fn read(bytes: &[u8]) -> Result<NbtTag, Box<dyn Error>> {
+    let mut reader = NbtReader { bytes, cursor: 0 };
+    let tag_type = reader.read_u8()?;
+    if tag_type != 10 {
+        return Err(format!("NBT root must be a compound, found tag type {tag_type}").into());
+    }
+    reader.read_string()?;
+    reader.read_payload(tag_type, 0)
+}
+
+const MAX_DEPTH: usize = 512;
+
+struct NbtReader<'a> {
+    bytes: &'a [u8],
+    cursor: usize,
+}
+
+impl NbtReader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], Box<dyn Error>> {
+        let end = self
+            .cursor
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or("Unexpected end of NBT data")?;
+        let slice = &self.bytes[self.cursor..end];
+        self.cursor = end;
+        Ok(slice)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], Box<dyn Error>> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Box<dyn Error>> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    fn read_i16(&mut self) -> Result<i16, Box<dyn Error>> {
+        Ok(i16::from_be_bytes(self.read_array()?))
+    }
+
+    fn read_i32(&mut self) -> Result<i32, Box<dyn Error>> {
+        Ok(i32::from_be_bytes(self.read_array()?))
+    }
+
+    fn read_i64(&mut self) -> Result<i64, Box<dyn Error>> {
+        Ok(i64::from_be_bytes(self.read_array()?))
+    }
+
+    fn read_len(&mut self) -> Result<usize, Box<dyn Error>> {
+        let len = self.read_i32()?;
+        usize::try_from(len).map_err(|_| format!("Negative NBT array length {len}").into())
+    }
+
+    fn read_string(&mut self) -> Result<String, Box<dyn Error>> {
+        let len = u16::from_be_bytes(self.read_array()?) as usize;
+        let raw = self.take(len)?;
+        Ok(String::from_utf8_lossy(raw).into_owned())
+    }
+
+    fn read_payload(&mut self, tag_type: u8, depth: usize) -> Result<NbtTag, Box<dyn Error>> {
+        if depth > MAX_DEPTH {
+            return Err("NBT nesting is too deep".into());
+        }
+
+        Ok(match tag_type {
+            0 => NbtTag::End,
+            1 => NbtTag::Byte(self.read_u8()? as i8),
+            2 => NbtTag::Short(self.read_i16()?),
+            3 => NbtTag::Int(self.read_i32()?),
+            4 => NbtTag::Long(self.read_i64()?),
+            5 => NbtTag::Float(f32::from_be_bytes(self.read_array()?)),
+            6 => NbtTag::Double(f64::from_be_bytes(self.read_array()?)),
+            7 => {
+                let len = self.read_len()?;
+                NbtTag::ByteArray(self.take(len)?.iter().map(|b| *b as i8).collect())
+            }
+            8 => NbtTag::String(self.read_string()?),
+            9 => {
+                let item_type = self.read_u8()?;
+                let len = self.read_len()?;
+                let mut items = Vec::with_capacity(len.min(self.bytes.len()));
+                for _ in 0..len {
+                    items.push(self.read_payload(item_type, depth + 1)?);
+                }
+                NbtTag::List(items)
+            }
+            10 => {
+                let mut entries = HashMap::new();
+                loop {
+                    let entry_type = self.read_u8()?;
+                    if entry_type == 0 {
+                        break;
+                    }
+                    let name = self.read_string()?;
+                    entries.insert(name, self.read_payload(entry_type, depth + 1)?);
+                }
+                NbtTag::Compound(entries)
+            }
+            11 => {
+                let len = self.read_len()?;
+                let raw = self.take(len.checked_mul(4).ok_or("NBT array too large")?)?;
+                let (chunks, _) = raw.as_chunks::<4>();
+                NbtTag::IntArray(chunks.iter().map(|c| i32::from_be_bytes(*c)).collect())
+            }
+            12 => {
+                let len = self.read_len()?;
+                let raw = self.take(len.checked_mul(8).ok_or("NBT array too large")?)?;
+                let (chunks, _) = raw.as_chunks::<8>();
+                NbtTag::LongArray(chunks.iter().map(|c| i64::from_be_bytes(*c)).collect())
+            }
+            other => return Err(format!("Unknown NBT tag type {other}").into()),
+        })
+    }
+}
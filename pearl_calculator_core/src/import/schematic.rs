@@ -0,0 +1,224 @@
+use crate::import::block_shapes::BlockState;
+use crate::import::block_volume::BlockVolume;
+use crate::import::nbt::{self, NbtTag};
+use crate::physics::aabb::aabb_box::AABBBox;
+use crate::physics::world::fluid::FluidVolume;
+use crate::physics::world::space::Space3D;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schematic {
+    pub regions: Vec<BlockVolume>,
+}
+
+impl Schematic {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let bytes = fs::read(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("litematic") => Self::from_litematic_bytes(&bytes),
+            Some("schem" | "schematic") => Self::from_sponge_bytes(&bytes),
+            _ => Err(format!("Unsupported schematic file {path:?}").into()),
+        }
+    }
+
+    pub fn from_sponge_bytes(bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let root = nbt::read_compressed(bytes)?;
+        // Sponge v3 nests everything under a `Schematic` compound and moves the palette into `Blocks`.
+        let schematic = root.get("Schematic").unwrap_or(&root);
+        let blocks = schematic.get("Blocks").unwrap_or(schematic);
+
+        let size = (
+            unsigned_short(schematic, "Width")?,
+            unsigned_short(schematic, "Height")?,
+            unsigned_short(schematic, "Length")?,
+        );
+
+        let palette_tag = blocks
+            .get("Palette")
+            .and_then(NbtTag::as_compound)
+            .ok_or("Sponge schematic is missing its block palette")?;
+        let mut palette = vec![BlockState::new("minecraft:air"); palette_tag.len()];
+        for (state, index) in palette_tag {
+            let index = index
+                .as_i32()
+                .and_then(|index| usize::try_from(index).ok())
+                .filter(|index| *index < palette.len())
+                .ok_or_else(|| format!("Invalid palette index for {state}"))?;
+            palette[index] = BlockState::parse(state);
+        }
+
+        let data = blocks
+            .get("BlockData")
+            .or_else(|| blocks.get("Data"))
+            .and_then(NbtTag::as_byte_array)
+            .ok_or("Sponge schematic is missing its block data")?;
+        let indices = decode_varints(data)?;
+
+        let volume = BlockVolume::new((0, 0, 0), size, palette, indices)?;
+        Ok(Self {
+            regions: vec![volume],
+        })
+    }
+
+    pub fn from_litematic_bytes(bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let root = nbt::read_compressed(bytes)?;
+        let regions_tag = root
+            .get("Regions")
+            .and_then(NbtTag::as_compound)
+            .ok_or("Litematic file has no regions")?;
+
+        let mut regions = Vec::with_capacity(regions_tag.len());
+        for (name, region) in regions_tag {
+            regions.push(
+                read_litematic_region(region)
+                    .map_err(|err| format!("Litematic region {name:?}: {err}"))?,
+            );
+        }
+
+        Ok(Self { regions })
+    }
+
+    pub fn collision_boxes(&self, origin: Space3D) -> Vec<AABBBox> {
+        self.regions
+            .iter()
+            .flat_map(|region| region.collision_boxes(origin))
+            .collect()
+    }
+
+    pub fn fluid_volumes(&self, origin: Space3D) -> Vec<FluidVolume> {
+        self.regions
+            .iter()
+            .flat_map(|region| region.fluid_volumes(origin))
+            .collect()
+    }
+}
+
+fn read_litematic_region(region: &NbtTag) -> Result<BlockVolume, Box<dyn Error>> {
+    let position = read_vec3(region, "Position")?;
+    let raw_size = read_vec3(region, "Size")?;
+
+    // Negative sizes extend the region backwards from its anchor position.
+    let axis = |position: i32, size: i32| -> (i32, usize) {
+        let min = if size < 0 {
+            position + size + 1
+        } else {
+            position
+        };
+        (min, size.unsigned_abs() as usize)
+    };
+    let (min_x, size_x) = axis(position.0, raw_size.0);
+    let (min_y, size_y) = axis(position.1, raw_size.1);
+    let (min_z, size_z) = axis(position.2, raw_size.2);
+
+    let palette: Vec<BlockState> = region
+        .get("BlockStatePalette")
+        .and_then(NbtTag::as_list)
+        .ok_or("missing block palette")?
+        .iter()
+        .map(|entry| BlockState::from_nbt(entry).ok_or("malformed palette entry"))
+        .collect::<Result<_, _>>()?;
+    let packed = region
+        .get("BlockStates")
+        .and_then(NbtTag::as_long_array)
+        .ok_or("missing block states")?;
+
+    let volume = size_x * size_y * size_z;
+    let bits = bits_for_palette(palette.len(), 2);
+    let indices = unpack_spanning(packed, bits, volume)?;
+
+    Ok(BlockVolume::new(
+        (min_x, min_y, min_z),
+        (size_x, size_y, size_z),
+        palette,
+        indices,
+    )?)
+}
+
+pub(crate) fn bits_for_palette(palette_len: usize, min_bits: u32) -> u32 {
+    let needed = usize::BITS - palette_len.saturating_sub(1).leading_zeros();
+    needed.max(min_bits)
+}
+
+pub(crate) fn unpack_spanning(
+    packed: &[i64],
+    bits: u32,
+    count: usize,
+) -> Result<Vec<u32>, Box<dyn Error>> {
+    let required = (count * bits as usize).div_ceil(64);
+    if packed.len() < required {
+        return Err(format!(
+            "packed block array holds {} longs but {required} are needed",
+            packed.len()
+        )
+        .into());
+    }
+
+    let mask = (1u64 << bits) - 1;
+    Ok((0..count)
+        .map(|index| {
+            let start_bit = index * bits as usize;
+            let start_long = start_bit >> 6;
+            let end_long = (start_bit + bits as usize - 1) >> 6;
+            let bit_offset = (start_bit & 63) as u32;
+
+            let low = (packed[start_long] as u64) >> bit_offset;
+            let value = if start_long == end_long {
+                low
+            } else {
+                low | ((packed[end_long] as u64) << (64 - bit_offset))
+            };
+            (value & mask) as u32
+        })
+        .collect())
+}
+
+fn decode_varints(data: &[i8]) -> Result<Vec<u32>, Box<dyn Error>> {
+    let mut values = Vec::with_capacity(data.len());
+    let mut value = 0u32;
+    let mut shift = 0;
+    for byte in data.iter().map(|b| *b as u8) {
+        value |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 {
+            values.push(value);
+            value = 0;
+            shift = 0;
+        } else {
+            shift += 7;
+            if shift > 28 {
+                return Err("Block data varint is too long".into());
+            }
+        }
+    }
+    if shift != 0 {
+        return Err("Block data ends in the middle of a varint".into());
+    }
+    Ok(values)
+}
+
+fn read_vec3(tag: &NbtTag, key: &str) -> Result<(i32, i32, i32), Box<dyn Error>> {
+    let vec = tag.get(key).ok_or_else(|| format!("missing {key}"))?;
+    Ok((
+        required_i32(vec, "x")?,
+        required_i32(vec, "y")?,
+        required_i32(vec, "z")?,
+    ))
+}
+
+fn required_i32(tag: &NbtTag, key: &str) -> Result<i32, Box<dyn Error>> {
+    tag.get(key)
+        .and_then(NbtTag::as_i32)
+        .ok_or_else(|| format!("missing {key}").into())
+}
+
+fn unsigned_short(tag: &NbtTag, key: &str) -> Result<usize, Box<dyn Error>> {
+    match tag.get(key) {
+        Some(NbtTag::Short(value)) => Ok(*value as u16 as usize),
+        Some(other) => other
+            .as_i64()
+            .and_then(|value| usize::try_from(value).ok())
+            .ok_or_else(|| format!("invalid {key}").into()),
+        None => Err(format!("missing {key}").into()),
+    }
+}
@@ -1,4 +1,6 @@
 pub mod calculation;
+#[cfg(feature = "world-import")]
+pub mod import;
 pub mod physics;
 pub mod settings;
 pub mod utils;
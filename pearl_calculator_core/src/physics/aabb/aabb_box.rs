@@ -1,5 +1,9 @@
+use crate::physics::world::direction::BlockFace;
+use crate::physics::world::space::Space3D;
 use serde::{Deserialize, Serialize};
 
+const CLIP_EPSILON: f64 = 1.0e-7;
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct AABBBox {
     pub min_x: f64,
@@ -22,6 +26,18 @@ impl AABBBox {
         }
     }
 
+    /// Box of an entity standing at `position`, which is the centre of its base.
+    pub fn from_feet(position: Space3D, half_width: f64, height: f64) -> Self {
+        Self::new(
+            position.x - half_width,
+            position.y,
+            position.z - half_width,
+            position.x + half_width,
+            position.y + height,
+            position.z + half_width,
+        )
+    }
+
     pub fn default() -> Self {
         Self {
             min_x: 0.0,
@@ -44,6 +60,85 @@ impl AABBBox {
         )
     }
 
+    pub fn deflate(&self, amount: f64) -> Self {
+        Self::new(
+            self.min_x + amount,
+            self.min_y + amount,
+            self.min_z + amount,
+            self.max_x - amount,
+            self.max_y - amount,
+            self.max_z - amount,
+        )
+    }
+
+    pub fn expand_towards(&self, x: f64, y: f64, z: f64) -> Self {
+        let mut expanded = *self;
+        if x < 0.0 {
+            expanded.min_x += x;
+        } else {
+            expanded.max_x += x;
+        }
+        if y < 0.0 {
+            expanded.min_y += y;
+        } else {
+            expanded.max_y += y;
+        }
+        if z < 0.0 {
+            expanded.min_z += z;
+        } else {
+            expanded.max_z += z;
+        }
+        expanded
+    }
+
+    pub fn contains(&self, point: Space3D) -> bool {
+        point.x >= self.min_x
+            && point.x < self.max_x
+            && point.y >= self.min_y
+            && point.y < self.max_y
+            && point.z >= self.min_z
+            && point.z < self.max_z
+    }
+
+    /// Returns the fraction of `from -> to` at which the segment enters this box, and the entered face.
+    pub fn clip(&self, from: Space3D, to: Space3D) -> Option<(f64, BlockFace)> {
+        let delta = to - from;
+        let from = [from.x, from.y, from.z];
+        let delta = [delta.x, delta.y, delta.z];
+        let min = [self.min_x, self.min_y, self.min_z];
+        let max = [self.max_x, self.max_y, self.max_z];
+        let faces = [
+            (BlockFace::West, BlockFace::East),
+            (BlockFace::Down, BlockFace::Up),
+            (BlockFace::North, BlockFace::South),
+        ];
+
+        let mut nearest: Option<(f64, BlockFace)> = None;
+        for axis in 0..3 {
+            let (plane, face) = if delta[axis] > CLIP_EPSILON {
+                (min[axis], faces[axis].0)
+            } else if delta[axis] < -CLIP_EPSILON {
+                (max[axis], faces[axis].1)
+            } else {
+                continue;
+            };
+
+            let t = (plane - from[axis]) / delta[axis];
+            if t <= 0.0 || t >= nearest.map_or(1.0, |(best, _)| best) {
+                continue;
+            }
+
+            let within = (0..3).filter(|other| *other != axis).all(|other| {
+                let value = from[other] + t * delta[other];
+                value > min[other] - CLIP_EPSILON && value < max[other] + CLIP_EPSILON
+            });
+            if within {
+                nearest = Some((t, face));
+            }
+        }
+        nearest
+    }
+
     pub fn y_offset(&self, other: &AABBBox, mut offset_y: f64) -> f64 {
         if other.max_x <= self.min_x || other.min_x >= self.max_x {
             return offset_y;
@@ -2,6 +2,11 @@ pub const PEARL_GRAVITY_ACCELERATION: f64 = 0.03;
 
 pub const PEARL_DRAG_MULTIPLIER: f64 = 0.99;
 
+pub const PEARL_WATER_DRAG_MULTIPLIER: f64 = 0.8;
+
+// Entities test fluid contact against their bounding box shrunk by this much.
+pub const FLUID_CONTACT_DEFLATION: f64 = 0.001;
+
 pub const PEARL_RADIUS: f64 = 0.125;
 
 pub const PEARL_HEIGHT: f64 = 0.25;
@@ -12,8 +17,19 @@ pub const TNT_HEIGHT: f64 = 0.98;
 
 pub const TNT_EXPLOSION_RADIUS: f64 = 8.0;
 
+pub const TNT_GRAVITY_ACCELERATION: f64 = 0.04;
+
+pub const TNT_DRAG_MULTIPLIER: f64 = 0.98;
+
+// Applied to motion after drag while the TNT rests on a block.
+pub const TNT_GROUND_FRICTION: (f64, f64, f64) = (0.7, -0.5, 0.7);
+
 pub const TNT_ENTITY_Y_OFFSET: f64 = 0.06125; // 0.98 * 0.0625
 
 pub const PEARL_EXPLOSION_Y_FACTOR: f64 = 0.85;
 
 pub const FLOAT_PRECISION_EPSILON: f64 = 1e-10;
+
+pub const WORLD_BORDER_RADIUS: f64 = 29_999_984.0;
+
+pub const VOID_DEPTH: f64 = 64.0;
@@ -1,4 +1,7 @@
 pub mod entities;
+pub mod explosion;
 pub mod movement;
 pub mod pearl_entities;
+pub mod profile;
+pub mod projectile;
 pub mod tnt_entities;
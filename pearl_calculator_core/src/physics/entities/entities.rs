@@ -1,4 +1,5 @@
 use crate::physics::aabb::aabb_box::AABBBox;
+use crate::physics::world::collision_world::CollisionWorld;
 use crate::physics::world::space::Space3D;
 
 pub trait EntityTrait {
@@ -14,6 +15,7 @@ pub struct EntityData {
     pub is_collided_horizontally: bool,
     pub is_collided_vertically: bool,
     pub is_gravity: bool,
+    pub is_in_water: bool,
 }
 
 impl EntityData {
@@ -26,32 +28,42 @@ impl EntityData {
             is_collided_horizontally: false,
             is_collided_vertically: false,
             is_gravity: false,
+            is_in_water: false,
         }
     }
 
+    pub fn set_position(&mut self, position: Space3D) {
+        let half_width = (self.bounding_box.max_x - self.bounding_box.min_x) / 2.0;
+        let height = self.bounding_box.max_y - self.bounding_box.min_y;
+        self.position = position;
+        self.bounding_box = AABBBox::from_feet(position, half_width, height);
+    }
+
     pub fn move_entity(
         &mut self,
         mut xa: f64,
         mut ya: f64,
         mut za: f64,
-        world_collisions: &[AABBBox],
+        world_collisions: &CollisionWorld,
     ) {
         let original_xa = xa;
         let original_ya = ya;
         let original_za = za;
 
         let mut bb = self.bounding_box;
-        for aabb in world_collisions {
+        let nearby = world_collisions.query(&bb.expand_towards(xa, ya, za));
+
+        for aabb in nearby.iter() {
             ya = aabb.y_offset(&bb, ya);
         }
         bb = bb.offset(0.0, ya, 0.0);
 
-        for aabb in world_collisions {
+        for aabb in nearby.iter() {
             xa = aabb.x_offset(&bb, xa);
         }
         bb = bb.offset(xa, 0.0, 0.0);
 
-        for aabb in world_collisions {
+        for aabb in nearby.iter() {
             za = aabb.z_offset(&bb, za);
         }
 
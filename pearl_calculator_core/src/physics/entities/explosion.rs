@@ -0,0 +1,54 @@
+use crate::physics::constants::constants::TNT_ENTITY_Y_OFFSET;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ExplosionKind {
+    #[default]
+    Tnt,
+    /// A stationary TNT minecart; moving carts gain up to 7.5 extra power from their speed.
+    TntMinecart,
+    EndCrystal,
+    Bed,
+    RespawnAnchor,
+    Creeper,
+    ChargedCreeper,
+    WindCharge,
+}
+
+impl ExplosionKind {
+    /// Vanilla stores explosion power as a float, so keep it in single precision.
+    pub fn power(&self) -> f32 {
+        match self {
+            ExplosionKind::Tnt | ExplosionKind::TntMinecart => 4.0,
+            ExplosionKind::EndCrystal | ExplosionKind::ChargedCreeper => 6.0,
+            ExplosionKind::Bed | ExplosionKind::RespawnAnchor => 5.0,
+            ExplosionKind::Creeper => 3.0,
+            ExplosionKind::WindCharge => 1.2,
+        }
+    }
+
+    /// Entities beyond this distance from the centre are not pushed.
+    pub fn radius(&self) -> f64 {
+        (self.power() * 2.0) as f64
+    }
+
+    pub fn knockback_multiplier(&self) -> f64 {
+        match self {
+            ExplosionKind::WindCharge => 1.22f32 as f64,
+            _ => 1.0,
+        }
+    }
+
+    /// Height of the explosion centre above the source's position.
+    pub fn center_offset(&self) -> f64 {
+        match self {
+            ExplosionKind::Tnt => TNT_ENTITY_Y_OFFSET,
+            _ => 0.0,
+        }
+    }
+
+    /// Only primed TNT falls and drifts before it goes off; other sources explode where placed.
+    pub fn is_simulated(&self) -> bool {
+        matches!(self, ExplosionKind::Tnt)
+    }
+}
@@ -1,6 +1,9 @@
-use crate::physics::aabb::aabb_box::AABBBox;
-use crate::physics::constants::constants::{PEARL_DRAG_MULTIPLIER, PEARL_GRAVITY_ACCELERATION};
 use crate::physics::entities::pearl_entities::PearlEntity;
+use crate::physics::entities::profile::{PhysicsProfile, Precision, TickOrder};
+use crate::physics::world::collision_world::CollisionWorld;
+use crate::physics::world::direction::BlockFace;
+use crate::physics::world::fluid::FluidKind;
+use crate::physics::world::space::Space3D;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -19,107 +22,92 @@ pub enum PearlVersion {
 }
 
 impl PearlVersion {
-    pub fn apply_grav_drag_tick(&self, velocity: f64, gravity: f64, drag: f64) -> f64 {
-        match self {
-            PearlVersion::Legacy | PearlVersion::Post1205 => (velocity * drag) + gravity,
-            PearlVersion::Post1212 => (velocity + gravity) * drag,
-        }
-    }
-
-    pub fn get_projection_multiplier(&self, drag: f64) -> f64 {
-        match self {
-            PearlVersion::Legacy | PearlVersion::Post1205 => 1.0,
-            PearlVersion::Post1212 => drag,
-        }
+    pub fn profile(&self) -> PhysicsProfile {
+        PhysicsProfile::builtin(*self)
     }
+}
 
-    #[inline]
-    pub fn apply_motion_tick(&self, velocity: f64, drag: f64) -> (f64, f64) {
-        match self {
-            PearlVersion::Legacy | PearlVersion::Post1205 => {
-                let displacement = velocity;
-                let new_velocity = velocity * drag;
-                (new_velocity, displacement)
+/// Advances the pearl by one tick and returns where its path first hit a block, if it did.
+pub fn run_tick_sequence(
+    pearl: &mut PearlEntity,
+    world_collisions: &CollisionWorld,
+) -> Option<(Space3D, BlockFace)> {
+    match pearl.tick_order {
+        TickOrder::MoveFirst => {
+            pearl.data.is_in_water =
+                world_collisions.is_in_fluid(&pearl.data.bounding_box, FluidKind::Water);
+
+            // Older versions handle the hit before moving, so the pearl stays where it was.
+            let from = pearl.data.position;
+            if let Some(hit) = world_collisions.clip(from, from + pearl.data.motion) {
+                return Some(hit);
             }
-            PearlVersion::Post1212 => {
-                let new_velocity = velocity * drag;
-                let displacement = new_velocity;
-                (new_velocity, displacement)
+
+            move_through_air(pearl);
+            apply_drag(pearl);
+            apply_gravity(pearl);
+            None
+        }
+        TickOrder::GravityFirst => {
+            apply_gravity(pearl);
+            // The water state still comes from the end of the previous tick.
+            apply_drag(pearl);
+
+            let from = pearl.data.position;
+            if let Some((position, face)) = world_collisions.clip(from, from + pearl.data.motion) {
+                pearl.data.set_position(position);
+                return Some((position, face));
             }
+
+            move_through_air(pearl);
+            pearl.data.is_in_water =
+                world_collisions.is_in_fluid(&pearl.data.bounding_box, FluidKind::Water);
+            None
         }
     }
 }
 
-pub trait PearlMovement {
-    fn run_tick_sequence(pearl: &mut PearlEntity<Self>, world_collisions: &[AABBBox])
-    where
-        Self: Sized;
+// Projectiles clip against blocks themselves, so the move never collides.
+fn move_through_air(pearl: &mut PearlEntity) {
+    pearl.data.move_entity(
+        pearl.data.motion.x,
+        pearl.data.motion.y,
+        pearl.data.motion.z,
+        &CollisionWorld::default(),
+    );
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct MovementLegacy;
-
-impl PearlMovement for MovementLegacy {
-    fn run_tick_sequence(pearl: &mut PearlEntity<Self>, world_collisions: &[AABBBox]) {
-        pearl.data.move_entity(
-            pearl.data.motion.x,
-            pearl.data.motion.y,
-            pearl.data.motion.z,
-            world_collisions,
-        );
-
-        let mut mx = pearl.data.motion.x as f32;
-        let mut my = pearl.data.motion.y as f32;
-        let mut mz = pearl.data.motion.z as f32;
-
-        mx *= PEARL_DRAG_MULTIPLIER as f32;
-        my *= PEARL_DRAG_MULTIPLIER as f32;
-        mz *= PEARL_DRAG_MULTIPLIER as f32;
-
-        if pearl.data.is_gravity {
-            my -= PEARL_GRAVITY_ACCELERATION as f32;
+fn apply_drag(pearl: &mut PearlEntity) {
+    let drag = pearl_drag(pearl);
+    match pearl.precision {
+        Precision::Float => {
+            let drag = drag as f32;
+            pearl.data.motion.x = (pearl.data.motion.x as f32 * drag) as f64;
+            pearl.data.motion.y = (pearl.data.motion.y as f32 * drag) as f64;
+            pearl.data.motion.z = (pearl.data.motion.z as f32 * drag) as f64;
         }
-
-        pearl.data.motion.x = mx as f64;
-        pearl.data.motion.y = my as f64;
-        pearl.data.motion.z = mz as f64;
+        Precision::Double => pearl.data.motion *= drag,
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct MovementPost1205;
-
-impl PearlMovement for MovementPost1205 {
-    fn run_tick_sequence(pearl: &mut PearlEntity<Self>, world_collisions: &[AABBBox]) {
-        pearl.data.move_entity(
-            pearl.data.motion.x,
-            pearl.data.motion.y,
-            pearl.data.motion.z,
-            world_collisions,
-        );
-
-        pearl.data.motion *= PEARL_DRAG_MULTIPLIER;
-        if pearl.data.is_gravity {
-            pearl.data.motion.y -= PEARL_GRAVITY_ACCELERATION;
+fn apply_gravity(pearl: &mut PearlEntity) {
+    if !pearl.data.is_gravity {
+        return;
+    }
+    match pearl.precision {
+        Precision::Float => {
+            pearl.data.motion.y =
+                (pearl.data.motion.y as f32 - pearl.physics.gravity as f32) as f64;
         }
+        Precision::Double => pearl.data.motion.y -= pearl.physics.gravity,
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct MovementPost1212;
-
-impl PearlMovement for MovementPost1212 {
-    fn run_tick_sequence(pearl: &mut PearlEntity<Self>, world_collisions: &[AABBBox]) {
-        if pearl.data.is_gravity {
-            pearl.data.motion.y -= PEARL_GRAVITY_ACCELERATION;
-        }
-        pearl.data.motion *= PEARL_DRAG_MULTIPLIER;
-
-        pearl.data.move_entity(
-            pearl.data.motion.x,
-            pearl.data.motion.y,
-            pearl.data.motion.z,
-            world_collisions,
-        );
+// Lava slows other entities but projectiles only check for water.
+fn pearl_drag(pearl: &PearlEntity) -> f64 {
+    if pearl.data.is_in_water {
+        pearl.physics.water_drag
+    } else {
+        pearl.physics.drag
     }
 }
@@ -1,33 +1,38 @@
-use std::marker::PhantomData;
-
 use crate::physics::aabb::aabb_box::AABBBox;
-use crate::physics::constants::constants::{PEARL_HEIGHT, PEARL_RADIUS};
 use crate::physics::entities::entities::EntityData;
-use crate::physics::entities::movement::PearlMovement;
+use crate::physics::entities::profile::{PhysicsProfile, Precision, TickOrder};
+use crate::physics::entities::projectile::{ProjectileKind, ProjectilePhysics};
 use crate::physics::world::space::Space3D;
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct PearlEntity<M: PearlMovement> {
+pub struct PearlEntity {
     pub data: EntityData,
-    _movement: PhantomData<M>,
+    pub physics: ProjectilePhysics,
+    pub precision: Precision,
+    pub tick_order: TickOrder,
 }
 
-impl<M: PearlMovement> PearlEntity<M> {
-    pub fn new(position: Space3D, motion: Space3D) -> Self {
-        let bounding_box = AABBBox::new(
-            position.x - PEARL_RADIUS,
-            position.y,
-            position.z - PEARL_RADIUS,
-            position.x + PEARL_RADIUS,
-            position.y + PEARL_HEIGHT,
-            position.z + PEARL_RADIUS,
-        );
+impl PearlEntity {
+    pub fn new(position: Space3D, motion: Space3D, profile: &PhysicsProfile) -> Self {
+        Self::with_kind(position, motion, ProjectileKind::EnderPearl, profile)
+    }
+
+    pub fn with_kind(
+        position: Space3D,
+        motion: Space3D,
+        kind: ProjectileKind,
+        profile: &PhysicsProfile,
+    ) -> Self {
+        let physics = profile.projectile_physics(kind);
+        let bounding_box = AABBBox::from_feet(position, physics.half_width, physics.height);
         let mut data = EntityData::new(position, motion, bounding_box);
         data.is_gravity = true;
 
         Self {
             data,
-            _movement: PhantomData,
+            physics,
+            precision: profile.precision,
+            tick_order: profile.tick_order_for(kind),
         }
     }
 }
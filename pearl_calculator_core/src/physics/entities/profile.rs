@@ -0,0 +1,166 @@
+use crate::physics::constants::constants::TNT_ENTITY_Y_OFFSET;
+use crate::physics::entities::explosion::ExplosionKind;
+use crate::physics::entities::movement::PearlVersion;
+use crate::physics::entities::projectile::{ProjectileKind, ProjectilePhysics};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Precision {
+    /// Motion is rounded to single precision after drag and gravity, as before 1.20.5.
+    Float,
+    Double,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TickOrder {
+    /// Move => Drag => Gravity
+    MoveFirst,
+    /// Gravity => Drag => Move
+    GravityFirst,
+}
+
+impl TickOrder {
+    pub fn apply_grav_drag_tick(&self, velocity: f64, gravity: f64, drag: f64) -> f64 {
+        match self {
+            TickOrder::MoveFirst => (velocity * drag) + gravity,
+            TickOrder::GravityFirst => (velocity + gravity) * drag,
+        }
+    }
+
+    pub fn get_projection_multiplier(&self, drag: f64) -> f64 {
+        match self {
+            TickOrder::MoveFirst => 1.0,
+            TickOrder::GravityFirst => drag,
+        }
+    }
+
+    #[inline]
+    pub fn apply_motion_tick(&self, velocity: f64, drag: f64) -> (f64, f64) {
+        match self {
+            TickOrder::MoveFirst => {
+                let displacement = velocity;
+                let new_velocity = velocity * drag;
+                (new_velocity, displacement)
+            }
+            TickOrder::GravityFirst => {
+                let new_velocity = velocity * drag;
+                let displacement = new_velocity;
+                (new_velocity, displacement)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExplosionConstants {
+    /// Scales every explosion push, for servers that weaken or disable TNT knockback.
+    pub knockback_scale: f64,
+    /// Height of a primed TNT explosion above the entity's feet.
+    pub tnt_center_offset: f64,
+}
+
+impl Default for ExplosionConstants {
+    fn default() -> Self {
+        Self {
+            knockback_scale: 1.0,
+            tnt_center_offset: TNT_ENTITY_Y_OFFSET,
+        }
+    }
+}
+
+/// The rules a simulation follows. The vanilla versions ship as built-in profiles and
+/// custom ones can be loaded from JSON for snapshots or modded servers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhysicsProfile {
+    pub name: String,
+    pub precision: Precision,
+    pub tick_order: TickOrder,
+    /// Overrides the simulated projectile's own value when set.
+    #[serde(default)]
+    pub gravity: Option<f64>,
+    #[serde(default)]
+    pub drag: Option<f64>,
+    #[serde(default)]
+    pub water_drag: Option<f64>,
+    #[serde(default)]
+    pub explosion: ExplosionConstants,
+    /// Whether ender pearls keep the chunks they fly through loaded. When unset this follows the
+    /// tick order, since both changed in 1.21.2.
+    #[serde(default)]
+    pub pearls_load_chunks: Option<bool>,
+}
+
+impl PhysicsProfile {
+    pub fn builtin(version: PearlVersion) -> Self {
+        let (name, precision, tick_order) = match version {
+            PearlVersion::Legacy => ("Legacy", Precision::Float, TickOrder::MoveFirst),
+            PearlVersion::Post1205 => ("Post1205", Precision::Double, TickOrder::MoveFirst),
+            PearlVersion::Post1212 => ("Post1212", Precision::Double, TickOrder::GravityFirst),
+        };
+        Self {
+            name: name.to_string(),
+            precision,
+            tick_order,
+            gravity: None,
+            drag: None,
+            water_drag: None,
+            explosion: ExplosionConstants::default(),
+            pearls_load_chunks: None,
+        }
+    }
+
+    pub fn builtins() -> Vec<Self> {
+        [
+            PearlVersion::Legacy,
+            PearlVersion::Post1205,
+            PearlVersion::Post1212,
+        ]
+        .into_iter()
+        .map(Self::builtin)
+        .collect()
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn projectile_physics(&self, kind: ProjectileKind) -> ProjectilePhysics {
+        let mut physics = kind.physics();
+        physics.gravity = self.gravity.unwrap_or(physics.gravity);
+        physics.drag = self.drag.unwrap_or(physics.drag);
+        physics.water_drag = self.water_drag.unwrap_or(physics.water_drag);
+        physics
+    }
+
+    /// Only thrown items moved gravity and drag ahead of the move in 1.21.2;
+    /// arrows, tridents and wind charges still move first.
+    pub fn tick_order_for(&self, kind: ProjectileKind) -> TickOrder {
+        if kind.is_thrown_item() {
+            self.tick_order
+        } else {
+            TickOrder::MoveFirst
+        }
+    }
+
+    pub fn pearls_load_chunks(&self) -> bool {
+        self.pearls_load_chunks
+            .unwrap_or(self.tick_order == TickOrder::GravityFirst)
+    }
+
+    pub fn explosion_center_offset(&self, kind: ExplosionKind) -> f64 {
+        match kind {
+            ExplosionKind::Tnt => self.explosion.tnt_center_offset,
+            _ => kind.center_offset(),
+        }
+    }
+}
+
+impl From<PearlVersion> for PhysicsProfile {
+    fn from(version: PearlVersion) -> Self {
+        Self::builtin(version)
+    }
+}
@@ -0,0 +1,76 @@
+use crate::physics::constants::constants::{
+    PEARL_DRAG_MULTIPLIER, PEARL_EXPLOSION_Y_FACTOR, PEARL_GRAVITY_ACCELERATION, PEARL_HEIGHT,
+    PEARL_RADIUS, PEARL_WATER_DRAG_MULTIPLIER,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ProjectileKind {
+    #[default]
+    EnderPearl,
+    Snowball,
+    Egg,
+    Arrow,
+    Trident,
+    WindCharge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProjectilePhysics {
+    pub gravity: f64,
+    pub drag: f64,
+    pub water_drag: f64,
+    pub half_width: f64,
+    pub height: f64,
+    pub eye_height: f64,
+}
+
+impl ProjectileKind {
+    pub fn physics(&self) -> ProjectilePhysics {
+        match self {
+            ProjectileKind::EnderPearl | ProjectileKind::Snowball | ProjectileKind::Egg => {
+                ProjectilePhysics {
+                    gravity: PEARL_GRAVITY_ACCELERATION,
+                    drag: PEARL_DRAG_MULTIPLIER,
+                    water_drag: PEARL_WATER_DRAG_MULTIPLIER,
+                    half_width: PEARL_RADIUS,
+                    height: PEARL_HEIGHT,
+                    eye_height: PEARL_EXPLOSION_Y_FACTOR * PEARL_HEIGHT,
+                }
+            }
+            ProjectileKind::Arrow => ProjectilePhysics {
+                gravity: 0.05,
+                drag: 0.99,
+                water_drag: 0.6,
+                half_width: 0.25,
+                height: 0.5,
+                eye_height: 0.13,
+            },
+            ProjectileKind::Trident => ProjectilePhysics {
+                gravity: 0.05,
+                drag: 0.99,
+                water_drag: 0.99,
+                half_width: 0.25,
+                height: 0.5,
+                eye_height: 0.13,
+            },
+            // Wind charges fly in a straight line at a constant speed.
+            ProjectileKind::WindCharge => ProjectilePhysics {
+                gravity: 0.0,
+                drag: 1.0,
+                water_drag: 1.0,
+                half_width: 0.15625,
+                height: 0.3125,
+                eye_height: 0.0,
+            },
+        }
+    }
+
+    /// Snowballs, eggs and pearls; they follow the profile's tick order.
+    pub fn is_thrown_item(&self) -> bool {
+        matches!(
+            self,
+            ProjectileKind::EnderPearl | ProjectileKind::Snowball | ProjectileKind::Egg
+        )
+    }
+}
@@ -1,12 +1,19 @@
 use crate::physics::aabb::aabb_box::AABBBox;
-use crate::physics::constants::constants::{TNT_HEIGHT, TNT_RADIUS};
+use crate::physics::constants::constants::{
+    TNT_DRAG_MULTIPLIER, TNT_GRAVITY_ACCELERATION, TNT_GROUND_FRICTION, TNT_HEIGHT, TNT_RADIUS,
+};
 use crate::physics::entities::entities::{EntityData, EntityTrait};
+use crate::physics::entities::explosion::ExplosionKind;
+use crate::physics::world::collision_world::CollisionWorld;
 use crate::physics::world::space::Space3D;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct TNTEntity {
     pub data: EntityData,
     pub fuse: u32,
+    pub source: ExplosionKind,
+    pub amount: u32,
+    pub anchored: bool,
 }
 
 impl TNTEntity {
@@ -19,9 +26,36 @@ impl TNTEntity {
             position.y + TNT_HEIGHT,
             position.z + TNT_RADIUS,
         );
-        let data = EntityData::new(position, Space3D::default(), bounding_box);
+        let mut data = EntityData::new(position, Space3D::default(), bounding_box);
+        data.is_gravity = true;
 
-        Self { data, fuse }
+        Self {
+            data,
+            fuse,
+            source: ExplosionKind::Tnt,
+            amount: 1,
+            anchored: false,
+        }
+    }
+
+    pub fn tick_motion(&mut self, world_collisions: &CollisionWorld) {
+        if self.data.is_gravity {
+            self.data.motion.y -= TNT_GRAVITY_ACCELERATION;
+        }
+
+        self.data.move_entity(
+            self.data.motion.x,
+            self.data.motion.y,
+            self.data.motion.z,
+            world_collisions,
+        );
+
+        self.data.motion *= TNT_DRAG_MULTIPLIER;
+        if self.data.on_ground {
+            self.data.motion.x *= TNT_GROUND_FRICTION.0;
+            self.data.motion.y *= TNT_GROUND_FRICTION.1;
+            self.data.motion.z *= TNT_GROUND_FRICTION.2;
+        }
     }
 }
 
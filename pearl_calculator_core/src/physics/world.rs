@@ -1,3 +1,7 @@
+pub mod chunks;
+pub mod collision_world;
+pub mod dimension;
 pub mod direction;
+pub mod fluid;
 pub mod layout_direction;
 pub mod space;
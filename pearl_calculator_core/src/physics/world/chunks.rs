@@ -0,0 +1,68 @@
+use crate::physics::world::space::Space3D;
+use serde::{Deserialize, Serialize};
+
+pub const CHUNK_SIZE: f64 = 16.0;
+
+/// Chunks per side of a region file.
+pub const REGION_SIZE: i64 = 32;
+
+/// Chunks at or below this load level tick the entities in them.
+pub const ENTITY_TICKING_LEVEL: u32 = 31;
+
+/// A chunk ticket, such as a player or a nether portal, loading the chunk containing `position`.
+/// Every chunk further out is one level weaker.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LoadTicket {
+    /// Absolute block position.
+    pub position: Space3D,
+    pub level: u32,
+}
+
+/// Which chunks tick entities, from the tickets loading them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChunkLoading {
+    tickets: Vec<LoadTicket>,
+}
+
+impl ChunkLoading {
+    pub fn new(tickets: Vec<LoadTicket>) -> Self {
+        Self { tickets }
+    }
+
+    pub fn tickets(&self) -> &[LoadTicket] {
+        &self.tickets
+    }
+
+    /// The strongest level any ticket gives the chunk, or `None` if none reach it.
+    pub fn chunk_level(&self, chunk: (i64, i64)) -> Option<u32> {
+        self.tickets
+            .iter()
+            .map(|ticket| {
+                let (x, z) = chunk_of(ticket.position);
+                let distance = (chunk.0 - x).abs().max((chunk.1 - z).abs());
+                ticket
+                    .level
+                    .saturating_add(distance.min(u32::MAX as i64) as u32)
+            })
+            .min()
+    }
+
+    pub fn is_entity_ticking(&self, position: Space3D) -> bool {
+        self.chunk_level(chunk_of(position))
+            .is_some_and(|level| level <= ENTITY_TICKING_LEVEL)
+    }
+}
+
+pub fn chunk_of(position: Space3D) -> (i64, i64) {
+    (
+        (position.x / CHUNK_SIZE).floor() as i64,
+        (position.z / CHUNK_SIZE).floor() as i64,
+    )
+}
+
+pub fn region_of(chunk: (i64, i64)) -> (i64, i64) {
+    (
+        chunk.0.div_euclid(REGION_SIZE),
+        chunk.1.div_euclid(REGION_SIZE),
+    )
+}
@@ -0,0 +1,346 @@
+use crate::physics::aabb::aabb_box::AABBBox;
+use crate::physics::constants::constants::{FLUID_CONTACT_DEFLATION, WORLD_BORDER_RADIUS};
+use crate::physics::world::chunks::ChunkLoading;
+use crate::physics::world::dimension::Dimension;
+use crate::physics::world::direction::BlockFace;
+use crate::physics::world::fluid::{FluidKind, FluidVolume};
+use crate::physics::world::space::Space3D;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+const CELL_SIZE: f64 = 4.0;
+
+const MAX_CELLS_PER_BOX: i64 = 512;
+
+// Below this many boxes a linear scan beats hashing grid cells.
+const LINEAR_SCAN_LIMIT: usize = 32;
+
+// Segments shorter than this never hit anything, matching vanilla's shape clipping.
+const MIN_CLIP_LENGTH_SQ: f64 = 1.0e-7;
+
+type CellKey = (i64, i64, i64);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlockHit {
+    pub position: Space3D,
+    pub face: BlockFace,
+    pub tick: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CollisionWorld {
+    boxes: Vec<AABBBox>,
+    cells: HashMap<CellKey, Vec<u32>>,
+    oversized: Vec<u32>,
+    fluids: Vec<FluidVolume>,
+    void_floor: Option<f64>,
+    border_radius: Option<f64>,
+    chunk_loading: Option<ChunkLoading>,
+    origin: Space3D,
+}
+
+impl CollisionWorld {
+    pub fn new(boxes: Vec<AABBBox>) -> Self {
+        let mut world = Self {
+            boxes,
+            cells: HashMap::new(),
+            oversized: Vec::new(),
+            fluids: Vec::new(),
+            void_floor: None,
+            border_radius: None,
+            chunk_loading: None,
+            origin: Space3D::default(),
+        };
+        if world.boxes.len() > LINEAR_SCAN_LIMIT {
+            world.build_grid();
+        }
+        world
+    }
+
+    /// Adds the void, bedrock layers and world border of `dimension`.
+    /// `origin` is the absolute position that the simulation's coordinates are relative to.
+    pub fn with_dimension(mut self, dimension: Dimension, origin: Space3D) -> Self {
+        self.void_floor = Some(dimension.void_floor() - origin.y);
+        self.border_radius = Some(WORLD_BORDER_RADIUS);
+        self.origin = origin;
+
+        for (min_y, max_y) in dimension.bedrock_layers() {
+            self.push(AABBBox::new(
+                -WORLD_BORDER_RADIUS - origin.x,
+                min_y - origin.y,
+                -WORLD_BORDER_RADIUS - origin.z,
+                WORLD_BORDER_RADIUS - origin.x,
+                max_y - origin.y,
+                WORLD_BORDER_RADIUS - origin.z,
+            ));
+        }
+        self
+    }
+
+    pub fn with_void_floor(mut self, y: f64) -> Self {
+        self.void_floor = Some(y);
+        self
+    }
+
+    pub fn void_floor(&self) -> Option<f64> {
+        self.void_floor
+    }
+
+    pub fn is_below_void(&self, y: f64) -> bool {
+        self.void_floor.is_some_and(|floor| y < floor)
+    }
+
+    pub fn with_fluids(mut self, fluids: impl IntoIterator<Item = FluidVolume>) -> Self {
+        self.fluids.extend(fluids);
+        self
+    }
+
+    pub fn fluids(&self) -> &[FluidVolume] {
+        &self.fluids
+    }
+
+    pub fn add_fluid(&mut self, fluid: FluidVolume) {
+        self.fluids.push(fluid);
+    }
+
+    pub fn is_in_fluid(&self, bounding_box: &AABBBox, kind: FluidKind) -> bool {
+        let contact = bounding_box.deflate(FLUID_CONTACT_DEFLATION);
+        self.fluids
+            .iter()
+            .any(|fluid| fluid.kind == kind && fluid.touches(&contact))
+    }
+
+    pub fn border_radius(&self) -> Option<f64> {
+        self.border_radius
+    }
+
+    /// The absolute position that the simulation's coordinates are relative to.
+    pub fn origin(&self) -> Space3D {
+        self.origin
+    }
+
+    pub fn is_outside_border(&self, position: Space3D) -> bool {
+        self.border_radius.is_some_and(|radius| {
+            (position.x + self.origin.x).abs() > radius
+                || (position.z + self.origin.z).abs() > radius
+        })
+    }
+
+    /// Limits entity ticking to the chunks `loading` keeps loaded; every chunk ticks without it.
+    /// `origin` is the absolute position that the simulation's coordinates are relative to.
+    pub fn with_chunk_loading(mut self, loading: ChunkLoading, origin: Space3D) -> Self {
+        self.chunk_loading = Some(loading);
+        self.origin = origin;
+        self
+    }
+
+    pub fn chunk_loading(&self) -> Option<&ChunkLoading> {
+        self.chunk_loading.as_ref()
+    }
+
+    pub fn is_entity_ticking(&self, position: Space3D) -> bool {
+        self.chunk_loading
+            .as_ref()
+            .is_none_or(|loading| loading.is_entity_ticking(position + self.origin))
+    }
+
+    pub fn boxes(&self) -> &[AABBBox] {
+        &self.boxes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    pub fn push(&mut self, aabb: AABBBox) {
+        self.boxes.push(aabb);
+        let index = self.boxes.len() - 1;
+        if self.boxes.len() == LINEAR_SCAN_LIMIT + 1 {
+            self.build_grid();
+        } else if self.boxes.len() > LINEAR_SCAN_LIMIT {
+            self.insert_into_grid(index);
+        }
+    }
+
+    pub fn extend(&mut self, boxes: impl IntoIterator<Item = AABBBox>) {
+        for aabb in boxes {
+            self.push(aabb);
+        }
+    }
+
+    pub fn query(&self, region: &AABBBox) -> Cow<'_, [AABBBox]> {
+        if self.boxes.len() <= LINEAR_SCAN_LIMIT {
+            return Cow::Borrowed(&self.boxes);
+        }
+
+        let mut indices: Vec<u32> = self
+            .oversized
+            .iter()
+            .copied()
+            .filter(|index| touches(&self.boxes[*index as usize], region))
+            .collect();
+
+        let min = cell_of(region.min_x, region.min_y, region.min_z);
+        let max = cell_of(region.max_x, region.max_y, region.max_z);
+
+        if cell_span(min, max) > self.cells.len() as i64 {
+            indices.extend(self.cells.iter().flat_map(|(key, entries)| {
+                let inside = (min.0..=max.0).contains(&key.0)
+                    && (min.1..=max.1).contains(&key.1)
+                    && (min.2..=max.2).contains(&key.2);
+                entries.iter().copied().filter(move |_| inside)
+            }));
+        } else {
+            for x in min.0..=max.0 {
+                for y in min.1..=max.1 {
+                    for z in min.2..=max.2 {
+                        if let Some(entries) = self.cells.get(&(x, y, z)) {
+                            indices.extend(entries);
+                        }
+                    }
+                }
+            }
+        }
+
+        indices.sort_unstable();
+        indices.dedup();
+
+        Cow::Owned(
+            indices
+                .into_iter()
+                .map(|index| self.boxes[index as usize])
+                .filter(|aabb| touches(aabb, region))
+                .collect(),
+        )
+    }
+
+    pub fn clip(&self, from: Space3D, to: Space3D) -> Option<(Space3D, BlockFace)> {
+        let delta = to - from;
+        if delta.length_sq() < MIN_CLIP_LENGTH_SQ {
+            return None;
+        }
+
+        let region = AABBBox::new(from.x, from.y, from.z, from.x, from.y, from.z)
+            .expand_towards(delta.x, delta.y, delta.z);
+        let nearby = self.query(&region);
+
+        // A ray that starts inside a box hits it immediately, facing back along its motion.
+        let probe = from + delta * 0.001;
+        if nearby.iter().any(|aabb| aabb.contains(probe)) {
+            return Some((probe, BlockFace::nearest(delta).opposite()));
+        }
+
+        nearby
+            .iter()
+            .filter_map(|aabb| aabb.clip(from, to))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(t, face)| (from + delta * t, face))
+    }
+
+    /// Fraction of the sample points spread over `target` that have a clear line to `source`,
+    /// as vanilla uses to scale explosion knockback.
+    pub fn exposure(&self, source: Space3D, target: &AABBBox) -> f64 {
+        if self.boxes.is_empty() {
+            return 1.0;
+        }
+
+        let step_x = 1.0 / ((target.max_x - target.min_x) * 2.0 + 1.0);
+        let step_y = 1.0 / ((target.max_y - target.min_y) * 2.0 + 1.0);
+        let step_z = 1.0 / ((target.max_z - target.min_z) * 2.0 + 1.0);
+        if step_x < 0.0 || step_y < 0.0 || step_z < 0.0 {
+            return 0.0;
+        }
+        let offset_x = (1.0 - (1.0 / step_x).floor() * step_x) / 2.0;
+        let offset_z = (1.0 - (1.0 / step_z).floor() * step_z) / 2.0;
+
+        let lerp = |delta: f64, start: f64, end: f64| start + delta * (end - start);
+        let mut clear = 0u32;
+        let mut total = 0u32;
+
+        let mut fx = 0.0;
+        while fx <= 1.0 {
+            let mut fy = 0.0;
+            while fy <= 1.0 {
+                let mut fz = 0.0;
+                while fz <= 1.0 {
+                    let point = Space3D::new(
+                        lerp(fx, target.min_x, target.max_x) + offset_x,
+                        lerp(fy, target.min_y, target.max_y),
+                        lerp(fz, target.min_z, target.max_z) + offset_z,
+                    );
+                    if self.clip(point, source).is_none() {
+                        clear += 1;
+                    }
+                    total += 1;
+                    fz += step_z;
+                }
+                fy += step_y;
+            }
+            fx += step_x;
+        }
+
+        // Vanilla computes the ratio in single precision.
+        (clear as f32 / total as f32) as f64
+    }
+
+    fn build_grid(&mut self) {
+        self.cells.clear();
+        self.oversized.clear();
+        for index in 0..self.boxes.len() {
+            self.insert_into_grid(index);
+        }
+    }
+
+    fn insert_into_grid(&mut self, index: usize) {
+        let aabb = self.boxes[index];
+        let min = cell_of(aabb.min_x, aabb.min_y, aabb.min_z);
+        let max = cell_of(aabb.max_x, aabb.max_y, aabb.max_z);
+
+        if cell_span(min, max) > MAX_CELLS_PER_BOX {
+            self.oversized.push(index as u32);
+            return;
+        }
+
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                for z in min.2..=max.2 {
+                    self.cells.entry((x, y, z)).or_default().push(index as u32);
+                }
+            }
+        }
+    }
+}
+
+impl From<Vec<AABBBox>> for CollisionWorld {
+    fn from(boxes: Vec<AABBBox>) -> Self {
+        Self::new(boxes)
+    }
+}
+
+impl From<&[AABBBox]> for CollisionWorld {
+    fn from(boxes: &[AABBBox]) -> Self {
+        Self::new(boxes.to_vec())
+    }
+}
+
+fn cell_of(x: f64, y: f64, z: f64) -> CellKey {
+    let cell = |value: f64| (value / CELL_SIZE).floor() as i64;
+    (cell(x), cell(y), cell(z))
+}
+
+fn cell_span(min: CellKey, max: CellKey) -> i64 {
+    let axis = |min: i64, max: i64| max.saturating_sub(min).saturating_add(1);
+    axis(min.0, max.0)
+        .saturating_mul(axis(min.1, max.1))
+        .saturating_mul(axis(min.2, max.2))
+}
+
+// Inclusive on every face so boxes that merely touch the region are still returned.
+fn touches(aabb: &AABBBox, region: &AABBBox) -> bool {
+    aabb.min_x <= region.max_x
+        && aabb.max_x >= region.min_x
+        && aabb.min_y <= region.max_y
+        && aabb.max_y >= region.min_y
+        && aabb.min_z <= region.max_z
+        && aabb.max_z >= region.min_z
+}
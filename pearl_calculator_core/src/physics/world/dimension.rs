@@ -0,0 +1,39 @@
+use crate::physics::constants::constants::VOID_DEPTH;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Dimension {
+    Overworld,
+    Nether,
+    End,
+}
+
+impl Dimension {
+    pub fn min_y(&self) -> f64 {
+        match self {
+            Dimension::Overworld => -64.0,
+            Dimension::Nether | Dimension::End => 0.0,
+        }
+    }
+
+    pub fn max_y(&self) -> f64 {
+        match self {
+            Dimension::Overworld => 320.0,
+            Dimension::Nether | Dimension::End => 256.0,
+        }
+    }
+
+    /// Entities below this height are discarded.
+    pub fn void_floor(&self) -> f64 {
+        self.min_y() - VOID_DEPTH
+    }
+
+    /// Vertical ranges of the solid bedrock layers that span the whole dimension.
+    pub fn bedrock_layers(&self) -> Vec<(f64, f64)> {
+        match self {
+            Dimension::Overworld => vec![(-64.0, -63.0)],
+            Dimension::Nether => vec![(0.0, 1.0), (127.0, 128.0)],
+            Dimension::End => Vec::new(),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::physics::world::space::Space3D;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -70,3 +71,47 @@ impl std::ops::Not for Direction {
         !(self as u8)
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BlockFace {
+    Down,
+    Up,
+    North,
+    South,
+    West,
+    East,
+}
+
+impl BlockFace {
+    pub fn opposite(&self) -> BlockFace {
+        match self {
+            BlockFace::Down => BlockFace::Up,
+            BlockFace::Up => BlockFace::Down,
+            BlockFace::North => BlockFace::South,
+            BlockFace::South => BlockFace::North,
+            BlockFace::West => BlockFace::East,
+            BlockFace::East => BlockFace::West,
+        }
+    }
+
+    pub fn nearest(vector: Space3D) -> BlockFace {
+        let (ax, ay, az) = (vector.x.abs(), vector.y.abs(), vector.z.abs());
+        if ax >= ay && ax >= az {
+            if vector.x < 0.0 {
+                BlockFace::West
+            } else {
+                BlockFace::East
+            }
+        } else if ay >= az {
+            if vector.y < 0.0 {
+                BlockFace::Down
+            } else {
+                BlockFace::Up
+            }
+        } else if vector.z < 0.0 {
+            BlockFace::North
+        } else {
+            BlockFace::South
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use crate::physics::aabb::aabb_box::AABBBox;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FluidKind {
+    Water,
+    Lava,
+}
+
+/// A region of fluid; `bounds.max_y` is the fluid surface rather than the top of the block.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FluidVolume {
+    pub bounds: AABBBox,
+    pub kind: FluidKind,
+}
+
+impl FluidVolume {
+    pub fn new(bounds: AABBBox, kind: FluidKind) -> Self {
+        Self { bounds, kind }
+    }
+
+    pub fn touches(&self, aabb: &AABBBox) -> bool {
+        self.bounds.min_x < aabb.max_x
+            && self.bounds.max_x > aabb.min_x
+            && self.bounds.min_z < aabb.max_z
+            && self.bounds.max_z > aabb.min_z
+            && self.bounds.min_y < aabb.max_y
+            && self.bounds.max_y >= aabb.min_y
+    }
+}
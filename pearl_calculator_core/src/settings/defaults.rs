@@ -1,5 +1,6 @@
 use super::types::*;
 use crate::calculation::inputs::GeneralData;
+use crate::physics::entities::projectile::ProjectileKind;
 use crate::physics::world::space::Space3D;
 
 impl Default for AppSettings {
@@ -20,6 +21,10 @@ impl Default for CannonSettings {
             mode: CannonMode::Standard,
             default_red_direction: None,
             default_blue_direction: None,
+            projectile: ProjectileKind::EnderPearl,
+            stages: vec![],
+            aim_fuse: 0,
+            groups: vec![],
             north_west_tnt: Space3D::default(),
             north_east_tnt: Space3D::default(),
             south_west_tnt: Space3D::default(),
@@ -39,6 +44,7 @@ impl Default for GeneralData {
             pearl_position: Space3D::new(0.5, 4.0625, 0.5),
             pearl_motion: Space3D::new(0.0, 0.0, 0.0),
             tnt_charges: vec![],
+            projectile: ProjectileKind::EnderPearl,
         }
     }
 }
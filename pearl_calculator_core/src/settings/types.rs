@@ -1,3 +1,5 @@
+use crate::calculation::inputs::{CannonStage, TNTGroup};
+use crate::physics::entities::projectile::ProjectileKind;
 use crate::physics::world::layout_direction::LayoutDirection;
 use crate::physics::world::space::Space3D;
 use serde::{Deserialize, Serialize};
@@ -37,13 +39,23 @@ pub struct CannonSettings {
     pub default_red_direction: Option<LayoutDirection>,
     #[serde(default)]
     pub default_blue_direction: Option<LayoutDirection>,
-    #[serde(rename = "NorthWestTNT")]
+    #[serde(default)]
+    pub projectile: ProjectileKind,
+    #[serde(default)]
+    pub stages: Vec<CannonStage>,
+    #[serde(default)]
+    pub aim_fuse: u32,
+    /// Charge groups for cannons that do not fit the four-corner layout, which then leave the
+    /// corners unset.
+    #[serde(default)]
+    pub groups: Vec<TNTGroup>,
+    #[serde(default, rename = "NorthWestTNT")]
     pub north_west_tnt: Space3D,
-    #[serde(rename = "NorthEastTNT")]
+    #[serde(default, rename = "NorthEastTNT")]
     pub north_east_tnt: Space3D,
-    #[serde(rename = "SouthWestTNT")]
+    #[serde(default, rename = "SouthWestTNT")]
     pub south_west_tnt: Space3D,
-    #[serde(rename = "SouthEastTNT")]
+    #[serde(default, rename = "SouthEastTNT")]
     pub south_east_tnt: Space3D,
     pub offset: Surface2D,
     pub pearl: PearlInfo,
@@ -0,0 +1,115 @@
+#![cfg(feature = "world-import")]
+
+mod common;
+
+use common::{Tag, encode_root};
+use pearl_calculator_core::import::anvil::AnvilWorld;
+use pearl_calculator_core::physics::world::space::Space3D;
+use std::fs;
+use std::path::Path;
+
+fn write_region_with_wall(dir: &Path) {
+    // One section (y 64..79) of chunk (0, 0) with a stone wall on local z = 8.
+    let mut packed = vec![0i64; 256];
+    for index in 0..4096usize {
+        if (index / 16) % 16 == 8 {
+            packed[index / 16] |= 1i64 << ((index % 16) * 4);
+        }
+    }
+
+    let chunk = encode_root(Tag::Compound(vec![
+        ("DataVersion", Tag::Int(3953)),
+        (
+            "sections",
+            Tag::List(vec![Tag::Compound(vec![
+                ("Y", Tag::Byte(4)),
+                (
+                    "block_states",
+                    Tag::Compound(vec![
+                        (
+                            "palette",
+                            Tag::List(vec![
+                                Tag::Compound(vec![("Name", Tag::String("minecraft:air"))]),
+                                Tag::Compound(vec![("Name", Tag::String("minecraft:stone"))]),
+                            ]),
+                        ),
+                        ("data", Tag::LongArray(packed)),
+                    ]),
+                ),
+            ])]),
+        ),
+    ]));
+
+    write_region(dir, &chunk);
+}
+
+fn write_region(dir: &Path, chunk: &[u8]) {
+    let mut region = vec![0u8; 8192];
+    region[0..4].copy_from_slice(&[0, 0, 2, 1]);
+    region.extend(((chunk.len() + 1) as u32).to_be_bytes());
+    region.push(3);
+    region.extend(chunk);
+    region.resize(region.len().div_ceil(4096) * 4096, 0);
+
+    fs::create_dir_all(dir.join("region")).unwrap();
+    fs::write(dir.join("region").join("r.0.0.mca"), region).unwrap();
+}
+
+#[test]
+fn collisions_along_trace_load_only_crossed_terrain() {
+    let dir = std::env::temp_dir().join(format!("pearl_anvil_test_{}", std::process::id()));
+    write_region_with_wall(&dir);
+
+    let mut world = AnvilWorld::open(&dir).expect("world should open");
+    let origin = Space3D::new(2.0, 0.0, 0.0);
+    let trace = [Space3D::new(2.5, 70.0, 2.0), Space3D::new(2.5, 70.0, 14.0)];
+
+    let boxes = world
+        .collisions_along(&trace, origin, 1.0)
+        .expect("collisions should load");
+    assert_eq!(
+        boxes.len(),
+        9,
+        "a 3 x 3 patch of the wall surrounds the path"
+    );
+    assert!(boxes.iter().all(|b| b.min_z == 8.0 && b.max_z == 9.0));
+    assert!(boxes.iter().all(|b| (1.0..=3.0).contains(&b.min_x)));
+
+    let far_away = [
+        Space3D::new(5000.0, 70.0, 0.0),
+        Space3D::new(5010.0, 70.0, 0.0),
+    ];
+    let empty = world
+        .collisions_along(&far_away, origin, 1.0)
+        .expect("missing regions are empty, not errors");
+    assert!(empty.is_empty());
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn pre_flattening_chunks_are_rejected() {
+    let dir = std::env::temp_dir().join(format!("pearl_anvil_legacy_{}", std::process::id()));
+    let chunk = encode_root(Tag::Compound(vec![(
+        "Level",
+        Tag::Compound(vec![(
+            "Sections",
+            Tag::List(vec![Tag::Compound(vec![
+                ("Y", Tag::Byte(4)),
+                ("Blocks", Tag::ByteArray(vec![1; 4096])),
+            ])]),
+        )]),
+    )]));
+    write_region(&dir, &chunk);
+
+    let mut world = AnvilWorld::open(&dir).expect("world should open");
+    let trace = [Space3D::new(2.5, 70.0, 2.0), Space3D::new(2.5, 70.0, 14.0)];
+    assert!(
+        world
+            .collisions_along(&trace, Space3D::default(), 1.0)
+            .is_err(),
+        "solid pre-1.13 terrain must not read as air"
+    );
+
+    fs::remove_dir_all(&dir).ok();
+}
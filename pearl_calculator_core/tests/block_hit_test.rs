@@ -0,0 +1,59 @@
+use pearl_calculator_core::calculation::calculation::calculate_raw_trace;
+use pearl_calculator_core::calculation::results::PearlOutcome;
+use pearl_calculator_core::physics::aabb::aabb_box::AABBBox;
+use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::direction::BlockFace;
+use pearl_calculator_core::physics::world::space::Space3D;
+
+#[test]
+fn pearl_stops_at_first_block_hit() {
+    let world = CollisionWorld::new(vec![AABBBox::new(-5.0, 90.0, 10.0, 5.0, 110.0, 11.0)]);
+    let start = Space3D::new(0.0, 100.0, 0.0);
+    let motion = Space3D::new(0.0, 0.0, 2.0);
+
+    let modern = calculate_raw_trace(
+        start,
+        motion,
+        vec![],
+        100,
+        &world,
+        &PearlVersion::Post1212.profile(),
+    )
+    .expect("trace");
+    let PearlOutcome::Landed(hit) = modern.outcome else {
+        panic!("expected a block hit, got {:?}", modern.outcome);
+    };
+    assert_eq!(hit.face, BlockFace::North);
+    assert_eq!(hit.position.z, 10.0);
+    assert_eq!(modern.tick, hit.tick);
+    assert_eq!(modern.landing_position, hit.position);
+
+    let legacy = calculate_raw_trace(
+        start,
+        motion,
+        vec![],
+        100,
+        &world,
+        &PearlVersion::Legacy.profile(),
+    )
+    .expect("trace");
+    let PearlOutcome::Landed(hit) = legacy.outcome else {
+        panic!("expected a block hit, got {:?}", legacy.outcome);
+    };
+    assert_eq!(hit.position.z, 10.0);
+    assert!(legacy.landing_position.z < 10.0);
+
+    let void = CollisionWorld::default().with_void_floor(0.0);
+    let fall = calculate_raw_trace(
+        start,
+        Space3D::default(),
+        vec![],
+        1000,
+        &void,
+        &PearlVersion::Post1212.profile(),
+    )
+    .expect("trace");
+    assert_eq!(fall.outcome, PearlOutcome::Void);
+    assert!(fall.tick < 1000);
+}
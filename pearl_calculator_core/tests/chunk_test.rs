@@ -0,0 +1,101 @@
+mod common;
+
+use common::test_cannon;
+use pearl_calculator_core::calculation::calculation::{
+    calculate_chunk_report, calculate_pearl_trace, calculate_tnt_amount,
+};
+use pearl_calculator_core::calculation::inputs::GeneralData;
+use pearl_calculator_core::calculation::results::PearlOutcome;
+use pearl_calculator_core::calculation::simulation;
+use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::entities::projectile::ProjectileKind;
+use pearl_calculator_core::physics::world::chunks::{ChunkLoading, LoadTicket};
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::direction::Direction;
+use pearl_calculator_core::physics::world::space::Space3D;
+
+#[test]
+fn pearls_stall_in_chunks_that_do_not_tick_entities() {
+    // Level 29 makes the two rings of chunks around the ticket entity-ticking.
+    let world = CollisionWorld::default().with_chunk_loading(
+        ChunkLoading::new(vec![LoadTicket {
+            position: Space3D::new(0.0, 0.0, 0.0),
+            level: 29,
+        }]),
+        Space3D::default(),
+    );
+    let flight = |projectile: ProjectileKind, version: PearlVersion| {
+        let data = GeneralData {
+            pearl_position: Space3D::new(8.0, 100.0, 8.0),
+            pearl_motion: Space3D::new(1.5, 0.0, 0.0),
+            tnt_charges: vec![],
+            projectile,
+        };
+        simulation::run(&data, None, 40, &world, &version.profile()).expect("run")
+    };
+
+    let legacy = flight(ProjectileKind::EnderPearl, PearlVersion::Post1205);
+    assert_eq!(legacy.outcome, PearlOutcome::Stalled);
+    assert!((48.0..49.5).contains(&legacy.landing_position.x));
+    assert_eq!(legacy.records.len() as u32, legacy.tick + 1);
+
+    let modern = flight(ProjectileKind::EnderPearl, PearlVersion::Post1212);
+    assert_eq!(modern.outcome, PearlOutcome::TimedOut);
+    let snowball = flight(ProjectileKind::Snowball, PearlVersion::Post1212);
+    assert_eq!(snowball.outcome, PearlOutcome::Stalled);
+
+    let cannon = test_cannon();
+    let destination = cannon.pearl.position + Space3D::new(40.0, 0.0, 90.0);
+    let solve = |version: PearlVersion| {
+        calculate_tnt_amount(
+            &cannon,
+            destination,
+            30,
+            None,
+            300,
+            3.0,
+            &world,
+            &version.profile(),
+            false,
+        )
+    };
+    assert!(solve(PearlVersion::Post1205).is_empty());
+    assert!(!solve(PearlVersion::Post1212).is_empty());
+}
+
+#[test]
+fn chunk_reports_follow_the_trace_in_absolute_chunks() {
+    let cannon = test_cannon();
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let origin = Space3D::new(-1000.0, 0.0, 5.0);
+
+    let trace = calculate_pearl_trace(
+        &cannon,
+        10,
+        10,
+        0,
+        &[],
+        Direction::South,
+        100,
+        &world,
+        &profile,
+    )
+    .expect("trace");
+    let report = calculate_chunk_report(&trace, origin);
+
+    assert_eq!(report.ticks.len(), trace.records.len());
+    assert_eq!(report.ticks[0].chunk, (-63, 0));
+    assert_eq!(report.ticks[0].region, (-2, 0));
+    assert!(!report.crossings.is_empty());
+    for crossing in &report.crossings {
+        let tick = crossing.tick as usize;
+        assert_eq!(report.ticks[tick - 1].chunk, crossing.from);
+        assert_eq!(report.ticks[tick].chunk, crossing.to);
+    }
+
+    let last = report.ticks.last().expect("ticks").chunk;
+    assert_eq!(report.first_tick_within((-63, 0), 0), Some(0));
+    assert_eq!(report.chunks().last(), Some(&last));
+    assert!(report.first_tick_within(last, 0).is_some());
+}
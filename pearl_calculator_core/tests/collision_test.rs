@@ -0,0 +1,67 @@
+mod common;
+
+use common::test_cannon;
+use pearl_calculator_core::calculation::calculation::calculate_tnt_amount;
+use pearl_calculator_core::physics::aabb::aabb_box::AABBBox;
+use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::space::Space3D;
+
+#[test]
+fn blocked_candidates_are_scored_at_their_obstructed_landing() {
+    let cannon = test_cannon();
+    let destination = Space3D::new(3.0, 0.0, 160.0);
+    let wall = AABBBox::new(-1000.0, 0.0, 80.0, 1000.0, 1000.0, 81.0);
+    let world = CollisionWorld::new(vec![wall]);
+
+    let open = calculate_tnt_amount(
+        &cannon,
+        destination,
+        200,
+        None,
+        400,
+        5.0,
+        &CollisionWorld::default(),
+        &PearlVersion::Post1212.profile(),
+        false,
+    );
+    assert!(!open.is_empty(), "open field should have solutions");
+
+    let blocked = calculate_tnt_amount(
+        &cannon,
+        destination,
+        200,
+        None,
+        400,
+        5.0,
+        &world,
+        &PearlVersion::Post1212.profile(),
+        false,
+    );
+    assert!(blocked.is_empty(), "wall should reject every solution");
+
+    let at_wall = calculate_tnt_amount(
+        &cannon,
+        Space3D::new(3.0, 0.0, 79.875),
+        200,
+        None,
+        400,
+        200.0,
+        &world,
+        &PearlVersion::Post1212.profile(),
+        false,
+    );
+    assert!(!at_wall.is_empty(), "the wall itself should be reachable");
+    assert!(
+        at_wall
+            .iter()
+            .all(|result| result.pearl_end_pos.z <= wall.min_z),
+        "no result may land beyond the wall"
+    );
+    assert!(
+        at_wall
+            .iter()
+            .any(|result| result.pearl_end_pos.z == wall.min_z),
+        "blocked candidates should be scored at their contact point"
+    );
+}
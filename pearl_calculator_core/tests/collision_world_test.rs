@@ -0,0 +1,102 @@
+use pearl_calculator_core::physics::aabb::aabb_box::AABBBox;
+use pearl_calculator_core::physics::entities::entities::EntityData;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::space::Space3D;
+
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> f64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn range(&mut self, min: f64, max: f64) -> f64 {
+        min + (max - min) * self.next()
+    }
+}
+
+fn linear_move(bb: AABBBox, xa: f64, ya: f64, za: f64, boxes: &[AABBBox]) -> AABBBox {
+    let (mut xa, mut ya, mut za) = (xa, ya, za);
+    let mut bb = bb;
+    for aabb in boxes {
+        ya = aabb.y_offset(&bb, ya);
+    }
+    bb = bb.offset(0.0, ya, 0.0);
+    for aabb in boxes {
+        xa = aabb.x_offset(&bb, xa);
+    }
+    bb = bb.offset(xa, 0.0, 0.0);
+    for aabb in boxes {
+        za = aabb.z_offset(&bb, za);
+    }
+    bb.offset(0.0, 0.0, za)
+}
+
+#[test]
+fn grid_queries_match_linear_scan() {
+    let mut rng = Lcg(0x5eed);
+    let mut boxes = Vec::new();
+    for _ in 0..600 {
+        let (x, y, z) = (
+            rng.range(-40.0, 40.0).floor(),
+            rng.range(-10.0, 30.0).floor(),
+            rng.range(-40.0, 40.0).floor(),
+        );
+        boxes.push(AABBBox::new(
+            x,
+            y,
+            z,
+            x + 1.0,
+            y + rng.range(0.1, 1.0),
+            z + 1.0,
+        ));
+    }
+    boxes.push(AABBBox::new(-500.0, -12.0, -500.0, 500.0, -11.0, 500.0));
+    let world = CollisionWorld::new(boxes.clone());
+
+    for _ in 0..2000 {
+        let position = Space3D::new(
+            rng.range(-35.0, 35.0),
+            rng.range(-8.0, 28.0),
+            rng.range(-35.0, 35.0),
+        );
+        let (xa, ya, za) = (
+            rng.range(-3.0, 3.0),
+            rng.range(-3.0, 3.0),
+            rng.range(-3.0, 3.0),
+        );
+        let bb = AABBBox::new(
+            position.x - 0.125,
+            position.y,
+            position.z - 0.125,
+            position.x + 0.125,
+            position.y + 0.25,
+            position.z + 0.125,
+        );
+
+        let mut entity = EntityData::new(position, Space3D::new(xa, ya, za), bb);
+        entity.move_entity(xa, ya, za, &world);
+
+        assert_eq!(entity.bounding_box, linear_move(bb, xa, ya, za, &boxes));
+    }
+}
+
+#[test]
+fn exposure_counts_unblocked_sample_rays() {
+    let pearl = AABBBox::from_feet(Space3D::new(0.0, 100.0, 0.0), 0.125, 0.25);
+    let source = Space3D::new(0.0, 100.1, 3.0);
+
+    let open = CollisionWorld::new(vec![AABBBox::new(10.0, 0.0, 10.0, 11.0, 1.0, 11.0)]);
+    assert_eq!(open.exposure(source, &pearl), 1.0);
+
+    let wall = CollisionWorld::new(vec![AABBBox::new(-2.0, 98.0, 1.0, 2.0, 102.0, 2.0)]);
+    assert_eq!(wall.exposure(source, &pearl), 0.0);
+
+    // Vanilla samples a 2x2x2 grid shifted east; shielding only the western column blocks half.
+    let half = CollisionWorld::new(vec![AABBBox::new(-2.0, 98.0, 0.2, 0.125, 102.0, 0.3)]);
+    assert_eq!(half.exposure(source, &pearl), 0.5);
+}
@@ -0,0 +1,98 @@
+#![allow(dead_code)]
+
+use pearl_calculator_core::calculation::inputs::{Cannon, Pearl};
+use pearl_calculator_core::physics::entities::projectile::ProjectileKind;
+use pearl_calculator_core::physics::world::layout_direction::LayoutDirection;
+use pearl_calculator_core::physics::world::space::Space3D;
+use pearl_calculator_core::settings::CannonMode;
+
+/// A four-corner cannon with the pearl resting on the charges and no stages.
+pub fn test_cannon() -> Cannon {
+    Cannon {
+        pearl: Pearl {
+            position: Space3D::new(0.0, 100.0, 0.0),
+            motion: Space3D::new(0.0, 0.5, 0.0),
+        },
+        red_tnt_override: None,
+        blue_tnt_override: None,
+        vertical_tnt: None,
+        mode: CannonMode::Standard,
+        north_west_tnt: Space3D::new(-0.625, 99.5, -0.625),
+        north_east_tnt: Space3D::new(0.625, 99.5, -0.625),
+        south_west_tnt: Space3D::new(-0.625, 99.5, 0.625),
+        south_east_tnt: Space3D::new(0.625, 99.5, 0.625),
+        default_red_duper: Some(LayoutDirection::NorthWest),
+        default_blue_duper: Some(LayoutDirection::NorthEast),
+        projectile: ProjectileKind::EnderPearl,
+        stages: vec![],
+        aim_fuse: 0,
+    }
+}
+
+pub enum Tag<'a> {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    String(&'a str),
+    ByteArray(Vec<u8>),
+    LongArray(Vec<i64>),
+    List(Vec<Tag<'a>>),
+    Compound(Vec<(&'a str, Tag<'a>)>),
+}
+
+impl Tag<'_> {
+    fn id(&self) -> u8 {
+        match self {
+            Tag::Byte(_) => 1,
+            Tag::Short(_) => 2,
+            Tag::Int(_) => 3,
+            Tag::ByteArray(_) => 7,
+            Tag::String(_) => 8,
+            Tag::List(_) => 9,
+            Tag::Compound(_) => 10,
+            Tag::LongArray(_) => 12,
+        }
+    }
+
+    fn write_payload(&self, out: &mut Vec<u8>) {
+        match self {
+            Tag::Byte(value) => out.extend(value.to_be_bytes()),
+            Tag::Short(value) => out.extend(value.to_be_bytes()),
+            Tag::Int(value) => out.extend(value.to_be_bytes()),
+            Tag::String(value) => write_string(out, value),
+            Tag::ByteArray(values) => {
+                out.extend((values.len() as i32).to_be_bytes());
+                out.extend(values);
+            }
+            Tag::LongArray(values) => {
+                out.extend((values.len() as i32).to_be_bytes());
+                values.iter().for_each(|v| out.extend(v.to_be_bytes()));
+            }
+            Tag::List(items) => {
+                out.push(items.first().map_or(0, Tag::id));
+                out.extend((items.len() as i32).to_be_bytes());
+                items.iter().for_each(|item| item.write_payload(out));
+            }
+            Tag::Compound(entries) => {
+                for (name, tag) in entries {
+                    out.push(tag.id());
+                    write_string(out, name);
+                    tag.write_payload(out);
+                }
+                out.push(0);
+            }
+        }
+    }
+}
+
+fn write_string(out: &mut Vec<u8>, value: &str) {
+    out.extend((value.len() as u16).to_be_bytes());
+    out.extend(value.as_bytes());
+}
+
+pub fn encode_root(root: Tag) -> Vec<u8> {
+    let mut out = vec![10];
+    write_string(&mut out, "");
+    root.write_payload(&mut out);
+    out
+}
@@ -0,0 +1,68 @@
+use pearl_calculator_core::calculation::calculation::calculate_raw_trace;
+use pearl_calculator_core::calculation::results::PearlOutcome;
+use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::dimension::Dimension;
+use pearl_calculator_core::physics::world::direction::BlockFace;
+use pearl_calculator_core::physics::world::space::Space3D;
+
+#[test]
+fn dimensions_bound_the_flight() {
+    let origin = Space3D::new(1000.0, 0.0, -2000.0);
+    let nether = CollisionWorld::default().with_dimension(Dimension::Nether, origin);
+    let up = calculate_raw_trace(
+        Space3D::new(0.0, 120.0, 0.0),
+        Space3D::new(0.0, 3.0, 0.0),
+        vec![],
+        100,
+        &nether,
+        &PearlVersion::Post1212.profile(),
+    )
+    .expect("trace");
+    let PearlOutcome::Landed(roof) = up.outcome else {
+        panic!("expected the nether roof, got {:?}", up.outcome);
+    };
+    assert_eq!(roof.face, BlockFace::Down);
+    assert_eq!(roof.position.y, 127.0);
+
+    let overworld = CollisionWorld::default().with_dimension(Dimension::Overworld, origin);
+    let fall = calculate_raw_trace(
+        Space3D::new(0.0, 70.0, 0.0),
+        Space3D::default(),
+        vec![],
+        1000,
+        &overworld,
+        &PearlVersion::Post1212.profile(),
+    )
+    .expect("trace");
+    let PearlOutcome::Landed(floor) = fall.outcome else {
+        panic!("expected the bedrock floor, got {:?}", fall.outcome);
+    };
+    assert_eq!(floor.position.y, -63.0);
+
+    let end = CollisionWorld::default().with_dimension(Dimension::End, origin);
+    let fall = calculate_raw_trace(
+        Space3D::new(0.0, 70.0, 0.0),
+        Space3D::default(),
+        vec![],
+        1000,
+        &end,
+        &PearlVersion::Post1212.profile(),
+    )
+    .expect("trace");
+    assert_eq!(fall.outcome, PearlOutcome::Void);
+    assert!(fall.landing_position.y < Dimension::End.void_floor());
+
+    let edge = CollisionWorld::default()
+        .with_dimension(Dimension::End, Space3D::new(29_999_980.0, 0.0, 0.0));
+    let out = calculate_raw_trace(
+        Space3D::new(0.0, 70.0, 0.0),
+        Space3D::new(2.0, 0.0, 0.0),
+        vec![],
+        100,
+        &edge,
+        &PearlVersion::Post1212.profile(),
+    )
+    .expect("trace");
+    assert_eq!(out.outcome, PearlOutcome::OutsideBorder);
+}
@@ -0,0 +1,40 @@
+use pearl_calculator_core::calculation::calculation::calculate_raw_trace;
+use pearl_calculator_core::physics::aabb::aabb_box::AABBBox;
+use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::fluid::{FluidKind, FluidVolume};
+use pearl_calculator_core::physics::world::space::Space3D;
+
+#[test]
+fn water_switches_drag_in_version_order() {
+    let pool = CollisionWorld::default().with_fluids([FluidVolume::new(
+        AABBBox::new(-50.0, 0.0, -50.0, 50.0, 200.0, 50.0),
+        FluidKind::Water,
+    )]);
+    let start = Space3D::new(0.0, 100.0, 0.0);
+    let motion = Space3D::new(0.0, 0.0, 1.0);
+
+    let legacy = calculate_raw_trace(
+        start,
+        motion,
+        vec![],
+        2,
+        &pool,
+        &PearlVersion::Post1205.profile(),
+    )
+    .expect("trace");
+    assert_eq!(legacy.pearl_motion_trace()[1].z, 0.8);
+
+    // Post-1.21.2 pearls apply drag before they refresh their water state.
+    let modern = calculate_raw_trace(
+        start,
+        motion,
+        vec![],
+        2,
+        &pool,
+        &PearlVersion::Post1212.profile(),
+    )
+    .expect("trace");
+    assert_eq!(modern.pearl_motion_trace()[1].z, 0.99);
+    assert_eq!(modern.pearl_motion_trace()[2].z, 0.99 * 0.8);
+}
@@ -0,0 +1,211 @@
+mod common;
+
+use common::test_cannon;
+use pearl_calculator_core::calculation::calculation::{
+    SolveOptions, calculate_group_tnt_amount, calculate_raw_trace, calculate_tnt_amount,
+    search_group_tnt_amount, search_group_tnt_amount_with_progress,
+};
+use pearl_calculator_core::calculation::inputs::{GroupCannon, Pearl, TNT, TNTGroup};
+use pearl_calculator_core::calculation::progress::{CancellationToken, SolveProgress};
+use pearl_calculator_core::physics::entities::explosion::ExplosionKind;
+use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::entities::profile::PhysicsProfile;
+use pearl_calculator_core::physics::entities::projectile::ProjectileKind;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::direction::Direction;
+use pearl_calculator_core::physics::world::space::Space3D;
+
+fn six_group_cannon() -> GroupCannon {
+    let group = |name: &str, x: f64, z: f64, directions: Vec<Direction>| TNTGroup {
+        name: name.to_string(),
+        position: Space3D::new(x, 99.5, z),
+        min_tnt: 0,
+        max_tnt: 40,
+        directions,
+    };
+    GroupCannon {
+        pearl: Pearl {
+            position: Space3D::new(0.0, 100.0, 0.0),
+            motion: Space3D::new(0.0, 0.5, 0.0),
+        },
+        groups: vec![
+            group("North", 0.0, -0.8, vec![Direction::South]),
+            group("NorthWest", -0.6, -0.6, vec![]),
+            group("NorthEast", 0.6, -0.6, vec![]),
+            group("West", -0.8, 0.0, vec![Direction::East, Direction::South]),
+            group("East", 0.8, 0.0, vec![Direction::West]),
+            group("South", 0.0, 0.8, vec![Direction::North]),
+        ],
+        projectile: ProjectileKind::EnderPearl,
+    }
+}
+
+fn group_options<'a>(world: &'a CollisionWorld, profile: &'a PhysicsProfile) -> SolveOptions<'a> {
+    SolveOptions {
+        max_tnt: 0,
+        max_vertical_tnt: None,
+        max_ticks: 300,
+        max_distance: 2.0,
+        world_collisions: world,
+        profile,
+        plane_intercept_y: false,
+    }
+}
+
+#[test]
+fn corner_groups_solve_like_the_test_cannon() {
+    let cannon = test_cannon();
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let destination = cannon.pearl.position + Space3D::new(40.0, 0.0, 90.0);
+
+    let classic = calculate_tnt_amount(
+        &cannon,
+        destination,
+        60,
+        None,
+        300,
+        3.0,
+        &world,
+        &profile,
+        false,
+    );
+    let groups = GroupCannon::from_cannon(&cannon, 60, None).expect("no stages");
+    let general = calculate_group_tnt_amount(
+        &groups,
+        destination,
+        &SolveOptions {
+            max_tnt: 60,
+            max_vertical_tnt: None,
+            max_ticks: 300,
+            max_distance: 3.0,
+            world_collisions: &world,
+            profile: &profile,
+            plane_intercept_y: false,
+        },
+        false,
+    );
+
+    assert!(!classic.is_empty());
+    assert!(general[0].distance <= classic[0].distance);
+    assert!(general.iter().any(|result| {
+        result.tick == classic[0].tick
+            && result.total == classic[0].total
+            && result.direction == classic[0].direction
+    }));
+}
+
+#[test]
+fn six_group_solutions_replay_as_raw_tnt() {
+    let cannon = six_group_cannon();
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let destination = cannon.pearl.position + Space3D::new(30.0, 0.0, 120.0);
+
+    let results = calculate_group_tnt_amount(
+        &cannon,
+        destination,
+        &group_options(&world, &profile),
+        false,
+    );
+    let best = results.first().expect("a solution");
+    assert!(best.distance <= 2.0);
+    assert_eq!(best.counts.len(), 6);
+    assert_eq!(best.counts[4], 0, "East only fires for westward flights");
+    assert_eq!(best.counts[5], 0, "South only fires for northward flights");
+
+    let charges = cannon
+        .groups
+        .iter()
+        .zip(&best.counts)
+        .map(|(group, count)| TNT {
+            position: group.position,
+            fuse: 0,
+            motion: Space3D::default(),
+            source: ExplosionKind::Tnt,
+            amount: *count,
+            anchored: true,
+        })
+        .collect();
+    let trace = calculate_raw_trace(
+        cannon.pearl.position,
+        cannon.pearl.motion,
+        charges,
+        best.tick,
+        &world,
+        &profile,
+    )
+    .expect("trace");
+    let replayed = trace.records[best.tick as usize].position;
+    assert!(replayed.distance_sq(&best.pearl_end_pos) < 1e-9);
+}
+
+#[test]
+fn group_search_reports_progress_and_cancels() {
+    let cannon = six_group_cannon();
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let destination = cannon.pearl.position + Space3D::new(30.0, 0.0, 120.0);
+    let options = group_options(&world, &profile);
+
+    let full = search_group_tnt_amount(&cannon, destination, &options, false);
+    assert!(!full.results.is_empty());
+    assert!(full.explored > 0);
+
+    let mut reports: Vec<SolveProgress> = Vec::new();
+    let observed = search_group_tnt_amount_with_progress(
+        &cannon,
+        destination,
+        &options,
+        false,
+        &CancellationToken::new(),
+        &mut |progress| reports.push(progress.clone()),
+    );
+    assert!(!observed.cancelled);
+    assert_eq!(observed.results, full.results);
+    let last = reports.last().expect("reports");
+    assert_eq!(last.validated, last.total);
+    assert_eq!(last.best.first(), full.results.first());
+
+    let cancel = CancellationToken::new();
+    let stopped = search_group_tnt_amount_with_progress(
+        &cannon,
+        destination,
+        &options,
+        false,
+        &cancel,
+        &mut |_| cancel.cancel(),
+    );
+    assert!(stopped.cancelled);
+    assert!(stopped.results.is_empty());
+}
+
+#[test]
+fn only_redundant_groups_cut_the_search_short() {
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let mut options = group_options(&world, &profile);
+    options.max_distance = 1.0;
+
+    // Four groups push a pearl through two horizontal dimensions in too many ways to search.
+    let mut redundant = six_group_cannon();
+    for group in &mut redundant.groups {
+        group.max_tnt = 200;
+    }
+    let destination = redundant.pearl.position + Space3D::new(30.0, 0.0, 120.0);
+    let search = search_group_tnt_amount(&redundant, destination, &options, false);
+    assert!(search.truncated);
+
+    // Two groups leave a single best count per tick, however many charges they may fire.
+    let mut independent = six_group_cannon();
+    independent
+        .groups
+        .retain(|group| group.name == "North" || group.name == "West");
+    for group in &mut independent.groups {
+        group.max_tnt = 5000;
+        group.directions.clear();
+    }
+    let search = search_group_tnt_amount(&independent, destination, &options, false);
+    assert!(!search.truncated);
+    assert!(search.results[0].distance <= 1.0);
+}
@@ -0,0 +1,140 @@
+mod common;
+
+use common::test_cannon;
+use pearl_calculator_core::calculation::calculation::{SolveOptions, search_tnt_amount};
+use pearl_calculator_core::calculation::lookup::LookupTable;
+use pearl_calculator_core::calculation::ranking::{Ranking, rank_results};
+use pearl_calculator_core::physics::aabb::aabb_box::AABBBox;
+use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::dimension::Dimension;
+use pearl_calculator_core::physics::world::space::Space3D;
+
+#[test]
+fn lookup_tables_round_trip_and_replay_live_answers() {
+    let cannon = test_cannon();
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let options = SolveOptions {
+        max_tnt: 200,
+        max_vertical_tnt: None,
+        max_ticks: 300,
+        max_distance: 1.0,
+        world_collisions: &world,
+        profile: &profile,
+        plane_intercept_y: false,
+    };
+
+    let table = LookupTable::build(&cannon, 60, 30, &options).expect("table");
+    let loaded = LookupTable::from_bytes(&table.to_bytes()).expect("load");
+    assert_eq!(loaded, table);
+    assert!(loaded.matches(&cannon, &options));
+    assert!(!loaded.matches(
+        &cannon,
+        &SolveOptions {
+            max_tnt: 100,
+            ..options
+        }
+    ));
+    assert!(!loaded.matches(
+        &cannon,
+        &SolveOptions {
+            plane_intercept_y: true,
+            ..options
+        }
+    ));
+
+    let destination = cannon.pearl.position + Space3D::new(30.0, 0.0, 60.0);
+    let live = rank_results(
+        search_tnt_amount(&cannon, destination, &options).results,
+        &Ranking::default(),
+    );
+    let stored = loaded
+        .resolve(&cannon, destination, &world, &profile)
+        .expect("sampled destination");
+    assert_eq!((stored.red, stored.blue), (live[0].red, live[0].blue));
+    assert_eq!(stored.distance, live[0].distance);
+
+    let between = destination + Space3D::new(1.0, 0.0, 0.0);
+    assert!(loaded.get(&cannon, between).is_none());
+}
+
+#[test]
+fn fingerprints_cover_each_field_and_stay_stable() {
+    let cannon = test_cannon();
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let options = SolveOptions {
+        max_tnt: 20,
+        max_vertical_tnt: None,
+        max_ticks: 50,
+        max_distance: 1.0,
+        world_collisions: &world,
+        profile: &profile,
+        plane_intercept_y: false,
+    };
+    let table = LookupTable::build(&cannon, 0, 1, &options).expect("table");
+
+    // A saved table must keep matching until the format version changes.
+    assert_eq!(table.fingerprint, 10815242592548119178);
+
+    let mut moved = cannon.clone();
+    moved.south_east_tnt.x += 0.0625;
+    assert!(!table.matches(&moved, &options));
+
+    let mut relabelled = profile.clone();
+    relabelled.name = "Renamed".to_string();
+    assert!(table.matches(
+        &cannon,
+        &SolveOptions {
+            profile: &relabelled,
+            ..options
+        }
+    ));
+
+    let mut weaker = profile.clone();
+    weaker.explosion.knockback_scale = 0.5;
+    assert!(!table.matches(
+        &cannon,
+        &SolveOptions {
+            profile: &weaker,
+            ..options
+        }
+    ));
+}
+
+#[test]
+fn tables_built_in_an_empty_world_do_not_match_a_walled_one() {
+    let cannon = test_cannon();
+    let empty = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let options = SolveOptions {
+        max_tnt: 20,
+        max_vertical_tnt: None,
+        max_ticks: 50,
+        max_distance: 1.0,
+        world_collisions: &empty,
+        profile: &profile,
+        plane_intercept_y: false,
+    };
+    let table = LookupTable::build(&cannon, 0, 1, &options).expect("table");
+
+    let walled = CollisionWorld::new(vec![AABBBox::new(10.0, -64.0, -64.0, 11.0, 320.0, 64.0)]);
+    assert!(!table.matches(
+        &cannon,
+        &SolveOptions {
+            world_collisions: &walled,
+            ..options
+        }
+    ));
+
+    let overworld =
+        CollisionWorld::default().with_dimension(Dimension::Overworld, Space3D::default());
+    assert!(!table.matches(
+        &cannon,
+        &SolveOptions {
+            world_collisions: &overworld,
+            ..options
+        }
+    ));
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use common::test_cannon;
+use pearl_calculator_core::calculation::calculation::{
+    SolveOptions, calculate_pearl_trace, search_tnt_amount,
+};
+use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::direction::Direction;
+
+#[test]
+fn lattice_search_recovers_large_charge_counts_exactly() {
+    let cannon = test_cannon();
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+
+    let flight = calculate_pearl_trace(
+        &cannon,
+        173,
+        141,
+        0,
+        &[],
+        Direction::South,
+        120,
+        &world,
+        &profile,
+    )
+    .expect("trace");
+    let destination = flight.landing_position + cannon.pearl.position;
+
+    let options = SolveOptions {
+        max_tnt: 200,
+        max_vertical_tnt: None,
+        max_ticks: 120,
+        max_distance: 0.01,
+        world_collisions: &world,
+        profile: &profile,
+        plane_intercept_y: false,
+    };
+    let search = search_tnt_amount(&cannon, destination, &options);
+    assert!(search.explored > 0);
+    assert!(
+        search
+            .results
+            .iter()
+            .any(|result| result.red == 173 && result.blue == 141 && result.distance < 1e-6),
+        "{:?}",
+        search.results
+    );
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use common::test_cannon;
+use pearl_calculator_core::calculation::calculation::calculate_pearl_trace;
+use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::entities::profile::PhysicsProfile;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::direction::Direction;
+
+#[test]
+fn custom_profiles_load_from_json() {
+    let profile: PhysicsProfile = serde_json::from_str(
+        r#"{ "name": "Post1212", "precision": "Double", "tick_order": "GravityFirst" }"#,
+    )
+    .expect("profile");
+    assert_eq!(profile, PearlVersion::Post1212.profile());
+
+    let cannon = test_cannon();
+    let world = CollisionWorld::default();
+    let trace = |profile: &PhysicsProfile| {
+        calculate_pearl_trace(
+            &cannon,
+            4,
+            4,
+            0,
+            &[],
+            Direction::South,
+            200,
+            &world,
+            profile,
+        )
+        .expect("trace")
+    };
+
+    let mut no_knockback = profile.clone();
+    no_knockback.explosion.knockback_scale = 0.0;
+    let mut heavy = profile.clone();
+    heavy.gravity = Some(0.05);
+
+    let vanilla = trace(&profile);
+    assert!(
+        trace(&no_knockback)
+            .records
+            .iter()
+            .all(|record| record.position.x == 0.0 && record.position.z == 0.0)
+    );
+    assert!(trace(&heavy).landing_position.y < vanilla.landing_position.y);
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use common::test_cannon;
+use pearl_calculator_core::calculation::calculation::{
+    SolveOptions, search_tnt_amount, search_tnt_amount_with_progress,
+};
+use pearl_calculator_core::calculation::inputs::CannonStage;
+use pearl_calculator_core::calculation::progress::{CancellationToken, SolveProgress};
+use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::space::Space3D;
+
+#[test]
+fn progress_reports_cover_the_search_and_cancel_it() {
+    let cannon = test_cannon();
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let destination = cannon.pearl.position + Space3D::new(40.0, 0.0, 90.0);
+
+    let options = SolveOptions {
+        max_tnt: 200,
+        max_vertical_tnt: None,
+        max_ticks: 300,
+        max_distance: 5.0,
+        world_collisions: &world,
+        profile: &profile,
+        plane_intercept_y: false,
+    };
+    let full = search_tnt_amount(&cannon, destination, &options);
+    assert!(!full.results.is_empty());
+
+    let mut reports: Vec<SolveProgress> = Vec::new();
+    let observed = search_tnt_amount_with_progress(
+        &cannon,
+        destination,
+        &options,
+        &CancellationToken::new(),
+        &mut |progress| reports.push(progress.clone()),
+    );
+    assert!(!observed.cancelled);
+    assert_eq!(observed.results, full.results);
+    assert!(
+        reports
+            .iter()
+            .all(|report| report.validated <= report.total)
+    );
+    let last = reports.last().expect("reports");
+    assert_eq!(last.validated, last.total);
+    assert_eq!(
+        last.best.first(),
+        full.results
+            .iter()
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    );
+
+    let cancel = CancellationToken::new();
+    let stopped =
+        search_tnt_amount_with_progress(&cannon, destination, &options, &cancel, &mut |_| {
+            cancel.cancel()
+        });
+    assert!(stopped.cancelled);
+    assert!(stopped.results.is_empty());
+}
+
+#[test]
+fn staged_progress_counts_over_every_stage_combination() {
+    let mut cannon = test_cannon();
+    cannon.stages = vec![CannonStage {
+        position: Space3D::new(0.0, 99.0, 0.0),
+        fuse: 0,
+        max_tnt: 3,
+    }];
+    cannon.aim_fuse = 4;
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let options = SolveOptions {
+        max_tnt: 40,
+        max_vertical_tnt: None,
+        max_ticks: 300,
+        max_distance: 2.0,
+        world_collisions: &world,
+        profile: &profile,
+        plane_intercept_y: false,
+    };
+
+    let mut reports: Vec<SolveProgress> = Vec::new();
+    let full = search_tnt_amount_with_progress(
+        &cannon,
+        Space3D::new(3.0, 0.0, 160.0),
+        &options,
+        &CancellationToken::new(),
+        &mut |progress| reports.push(progress.clone()),
+    );
+    assert!(
+        reports.windows(2).all(|pair| {
+            pair[0].validated <= pair[1].validated && pair[0].total == pair[1].total
+        })
+    );
+    let last = reports.last().expect("reports");
+    assert_eq!(last.validated, last.total);
+
+    let cancel = CancellationToken::new();
+    let partial = search_tnt_amount_with_progress(
+        &cannon,
+        Space3D::new(3.0, 0.0, 160.0),
+        &options,
+        &cancel,
+        &mut |progress| {
+            if !progress.best.is_empty() {
+                cancel.cancel();
+            }
+        },
+    );
+    assert!(partial.cancelled);
+    assert!(!partial.results.is_empty(), "results so far are kept");
+    assert!(partial.results.len() < full.results.len());
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use common::test_cannon;
+use pearl_calculator_core::calculation::calculation::{
+    calculate_pearl_trace, calculate_tnt_amount,
+};
+use pearl_calculator_core::calculation::inputs::GeneralData;
+use pearl_calculator_core::calculation::simulation;
+use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::entities::projectile::ProjectileKind;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::space::Space3D;
+
+#[test]
+fn projectiles_fly_with_their_own_physics() {
+    let world = CollisionWorld::default();
+    let flight = |projectile: ProjectileKind, version: PearlVersion| {
+        let data = GeneralData {
+            pearl_position: Space3D::new(0.0, 100.0, 0.0),
+            pearl_motion: Space3D::new(0.0, 0.0, 1.5),
+            tnt_charges: vec![],
+            projectile,
+        };
+        simulation::run(&data, None, 20, &world, &version.profile()).expect("run")
+    };
+
+    let pearl = flight(ProjectileKind::EnderPearl, PearlVersion::Post1212);
+    let snowball = flight(ProjectileKind::Snowball, PearlVersion::Post1212);
+    assert_eq!(pearl.pearl_trace(), snowball.pearl_trace());
+
+    let arrow = flight(ProjectileKind::Arrow, PearlVersion::Post1212);
+    assert!(arrow.landing_position.y < pearl.landing_position.y);
+    // Arrows kept moving before gravity and drag when thrown items switched order.
+    assert_eq!(
+        arrow.pearl_trace(),
+        flight(ProjectileKind::Arrow, PearlVersion::Post1205).pearl_trace()
+    );
+
+    let wind = flight(ProjectileKind::WindCharge, PearlVersion::Post1212);
+    assert_eq!(wind.landing_position, Space3D::new(0.0, 100.0, 30.0));
+}
+
+#[test]
+fn wind_charges_solve_without_drag() {
+    let mut cannon = test_cannon();
+    cannon.projectile = ProjectileKind::WindCharge;
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let destination = cannon.pearl.position + Space3D::new(40.0, 0.0, 90.0);
+
+    let results = calculate_tnt_amount(
+        &cannon,
+        destination,
+        200,
+        None,
+        200,
+        3.0,
+        &world,
+        &profile,
+        false,
+    );
+    let best = results.first().expect("a solution");
+    assert!(best.distance <= 3.0);
+
+    let replayed = calculate_pearl_trace(
+        &cannon,
+        best.red,
+        best.blue,
+        best.vertical,
+        &[],
+        best.direction,
+        best.tick,
+        &world,
+        &profile,
+    )
+    .expect("trace");
+    assert!(replayed.landing_position.distance_sq(&best.pearl_end_pos) < 1e-9);
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use common::test_cannon;
+use pearl_calculator_core::calculation::calculation::{
+    calculate_pearl_trace, calculate_tnt_amount,
+};
+use pearl_calculator_core::calculation::ranking::{
+    Objective, Ranking, WeightedObjective, rank_results,
+};
+use pearl_calculator_core::calculation::results::TNTResult;
+use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::direction::Direction;
+use pearl_calculator_core::physics::world::space::Space3D;
+
+fn result(distance: f64, total: u32, tick: u32) -> TNTResult {
+    TNTResult {
+        distance,
+        tick,
+        blue: total / 2,
+        red: total - total / 2,
+        vertical: 0,
+        stages: Vec::new(),
+        counts: Vec::new(),
+        yaw: 0.0,
+        pitch: 0.0,
+        total,
+        pearl_end_pos: Space3D::default(),
+        pearl_end_motion: Space3D::default(),
+        direction: Direction::South,
+        peak_y: 100.0 + tick as f64,
+        sensitivity: 1.0 + tick as f64 / 100.0,
+    }
+}
+
+#[test]
+fn rankings_trade_distance_against_charges() {
+    let results = vec![
+        result(0.1, 300, 40),
+        result(0.4, 120, 30),
+        result(0.5, 200, 35),
+        result(0.9, 60, 20),
+    ];
+
+    let closest = rank_results(results.clone(), &Ranking::default());
+    assert_eq!(closest[0].distance, 0.1);
+
+    let cheapest = rank_results(
+        results.clone(),
+        &Ranking::Weighted(vec![
+            WeightedObjective {
+                objective: Objective::Distance,
+                weight: 1.0,
+            },
+            WeightedObjective {
+                objective: Objective::Total,
+                weight: 3.0,
+            },
+        ]),
+    );
+    assert_eq!(cheapest[0].total, 60);
+
+    let front = rank_results(
+        results,
+        &Ranking::Pareto(vec![Objective::Distance, Objective::Total]),
+    );
+    let totals: Vec<u32> = front.iter().map(|result| result.total).collect();
+    assert_eq!(totals, vec![300, 120, 60]);
+}
+
+#[test]
+fn sensitivity_matches_the_drift_of_a_misplaced_pearl() {
+    let cannon = test_cannon();
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let results = calculate_tnt_amount(
+        &cannon,
+        Space3D::new(40.0, 0.0, 300.0),
+        400,
+        None,
+        200,
+        2.0,
+        &world,
+        &profile,
+        false,
+    );
+    let best = results.first().expect("a solution");
+
+    let step = 1.0 / 64.0;
+    let landing = |offset: Space3D| {
+        let mut moved = cannon.clone();
+        moved.pearl.position += offset;
+        calculate_pearl_trace(
+            &moved,
+            best.red,
+            best.blue,
+            best.vertical,
+            &[],
+            best.direction,
+            best.tick,
+            &world,
+            &profile,
+        )
+        .expect("trace")
+        .landing_position
+    };
+    let drift = [
+        Space3D::new(step, 0.0, 0.0),
+        Space3D::new(0.0, step, 0.0),
+        Space3D::new(0.0, 0.0, step),
+    ]
+    .into_iter()
+    .map(|offset| {
+        let shift = landing(offset) - best.pearl_end_pos;
+        Space3D::new(shift.x, 0.0, shift.z).length() / step
+    })
+    .fold(0.0, f64::max);
+
+    assert!(best.sensitivity > 1.0);
+    assert!(
+        (drift - best.sensitivity).abs() < 0.05 * best.sensitivity,
+        "{drift} vs {}",
+        best.sensitivity
+    );
+    assert!(
+        results
+            .iter()
+            .any(|result| (result.sensitivity - best.sensitivity).abs() > 1e-6)
+    );
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::test_cannon;
+use pearl_calculator_core::calculation::inputs::{Cannon, Pearl};
+use pearl_calculator_core::calculation::reachability::calculate_reachability;
+use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::entities::projectile::ProjectileKind;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::layout_direction::LayoutDirection;
+use pearl_calculator_core::physics::world::space::Space3D;
+use pearl_calculator_core::settings::CannonMode;
+
+#[test]
+fn reachability_map_covers_every_direction() {
+    let cannon = Cannon {
+        pearl: Pearl {
+            position: Space3D::new(0.0, 100.0, 0.0),
+            motion: Space3D::new(0.0, 0.5, 0.0),
+        },
+        red_tnt_override: None,
+        blue_tnt_override: None,
+        vertical_tnt: None,
+        mode: CannonMode::Standard,
+        north_west_tnt: Space3D::new(-0.625, 99.5, -0.625),
+        north_east_tnt: Space3D::new(0.625, 99.5, -0.625),
+        south_west_tnt: Space3D::new(-0.625, 99.5, 0.625),
+        south_east_tnt: Space3D::new(0.625, 99.5, 0.625),
+        default_red_duper: Some(LayoutDirection::NorthWest),
+        default_blue_duper: Some(LayoutDirection::NorthEast),
+        projectile: ProjectileKind::EnderPearl,
+        stages: vec![],
+        aim_fuse: 0,
+    };
+
+    let map = calculate_reachability(
+        &cannon,
+        8,
+        0,
+        2000,
+        Some(60.0),
+        &CollisionWorld::default(),
+        &PearlVersion::Post1212.profile(),
+    )
+    .expect("map");
+    assert_eq!(map.points().len(), 4 * 9 * 9);
+
+    let far = map
+        .points()
+        .iter()
+        .max_by(|a, b| {
+            a.position
+                .distance_2d(&Space3D::default())
+                .total_cmp(&b.position.distance_2d(&Space3D::default()))
+        })
+        .expect("points");
+    for quadrant in [(1.0, 1.0), (1.0, -1.0), (-1.0, 1.0), (-1.0, -1.0)] {
+        let target = Space3D::new(quadrant.0 * 20.0, 60.0, quadrant.1 * 20.0);
+        let (_, distance) = map.nearest(target).expect("nearest");
+        let brute = map
+            .points()
+            .iter()
+            .map(|point| point.position.distance_2d(&target))
+            .fold(f64::INFINITY, f64::min);
+        assert_eq!(distance, brute);
+    }
+
+    let report = map.coverage(Space3D::new(0.0, 60.0, 0.0), 8.0, 1.0, 2.0);
+    assert!(report.samples > 0);
+    assert!(report.worst_error.is_finite());
+    assert!(map.within(far.position, 0.0).contains(&far));
+
+    assert_eq!(map.to_csv().lines().count(), map.points().len() + 1);
+    assert_eq!(
+        map.to_geojson()["features"].as_array().map(Vec::len),
+        Some(map.points().len())
+    );
+}
+
+#[test]
+fn reachability_rejects_cannons_with_an_aim_fuse() {
+    let mut cannon = test_cannon();
+    cannon.aim_fuse = 2;
+
+    let map = calculate_reachability(
+        &cannon,
+        4,
+        0,
+        200,
+        Some(60.0),
+        &CollisionWorld::default(),
+        &PearlVersion::Post1212.profile(),
+    );
+    assert!(map.is_err());
+}
@@ -1,6 +1,7 @@
 use pearl_calculator_core::calculation::calculation::calculate_pearl_trace;
 use pearl_calculator_core::calculation::inputs::Cannon;
 use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
 use pearl_calculator_core::physics::world::direction::Direction;
 use pearl_calculator_core::physics::world::space::Space3D;
 use pearl_calculator_core::settings::{CannonSettings, Surface2D};
@@ -75,10 +76,11 @@ fn regression_cases_match_game_trace() {
             case.red_tnt,
             case.blue_tnt,
             case.vertical_tnt,
+            &[],
             case.direction,
             case.max_ticks,
-            &[],
-            case.version,
+            &CollisionWorld::default(),
+            &case.version.profile(),
         )
         .unwrap_or_else(|| panic!("{} trace should be calculable", case.name));
 
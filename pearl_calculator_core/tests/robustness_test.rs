@@ -0,0 +1,93 @@
+use pearl_calculator_core::calculation::calculation::calculate_tnt_amount;
+use pearl_calculator_core::calculation::inputs::{Cannon, Pearl};
+use pearl_calculator_core::calculation::robustness::{
+    JitterModel, analyse_robustness, rank_by_robustness,
+};
+use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::entities::projectile::ProjectileKind;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::layout_direction::LayoutDirection;
+use pearl_calculator_core::physics::world::space::Space3D;
+use pearl_calculator_core::settings::CannonMode;
+
+#[test]
+fn jitter_spreads_landings_around_the_nominal_solution() {
+    let cannon = Cannon {
+        pearl: Pearl {
+            position: Space3D::new(0.0, 100.0, 0.0),
+            motion: Space3D::new(0.0, 0.5, 0.0),
+        },
+        red_tnt_override: None,
+        blue_tnt_override: None,
+        vertical_tnt: None,
+        mode: CannonMode::Standard,
+        north_west_tnt: Space3D::new(-0.625, 99.5, -0.625),
+        north_east_tnt: Space3D::new(0.625, 99.5, -0.625),
+        south_west_tnt: Space3D::new(-0.625, 99.5, 0.625),
+        south_east_tnt: Space3D::new(0.625, 99.5, 0.625),
+        default_red_duper: Some(LayoutDirection::NorthWest),
+        default_blue_duper: Some(LayoutDirection::NorthEast),
+        projectile: ProjectileKind::EnderPearl,
+        stages: vec![],
+        aim_fuse: 0,
+    };
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let destination = cannon.pearl.position + Space3D::new(40.0, 0.0, 90.0);
+
+    let results = calculate_tnt_amount(
+        &cannon,
+        destination,
+        60,
+        None,
+        300,
+        3.0,
+        &world,
+        &profile,
+        false,
+    );
+    let best = &results[0];
+
+    let still = JitterModel {
+        horizontal_speed: 0.0,
+        jump_speed: 0.0,
+        trials: 4,
+        ..JitterModel::default()
+    };
+    let nominal = analyse_robustness(&cannon, best, destination, &still, &world, &profile);
+    let expected = best.pearl_end_pos.distance_2d(&destination);
+    assert!(
+        nominal
+            .errors
+            .iter()
+            .all(|error| (error - expected).abs() < 1e-9)
+    );
+
+    let jitter = JitterModel {
+        trials: 64,
+        seed: 7,
+        ..JitterModel::default()
+    };
+    let report = analyse_robustness(&cannon, best, destination, &jitter, &world, &profile);
+    assert_eq!(report.errors.len(), 64);
+    assert!(report.max_error > report.errors[0]);
+    assert!(report.errors.is_sorted());
+    assert_eq!(
+        report,
+        analyse_robustness(&cannon, best, destination, &jitter, &world, &profile)
+    );
+
+    let ranked = rank_by_robustness(
+        &cannon,
+        results.into_iter().take(5).collect(),
+        destination,
+        &jitter,
+        &world,
+        &profile,
+    );
+    assert!(
+        ranked
+            .windows(2)
+            .all(|pair| pair[0].1.success_probability >= pair[1].1.success_probability)
+    );
+}
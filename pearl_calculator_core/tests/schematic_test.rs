@@ -0,0 +1,148 @@
+#![cfg(feature = "world-import")]
+
+mod common;
+
+use common::{Tag, encode_root};
+use pearl_calculator_core::import::schematic::Schematic;
+use pearl_calculator_core::physics::aabb::aabb_box::AABBBox;
+use pearl_calculator_core::physics::world::space::Space3D;
+
+fn assert_box(actual: &AABBBox, expected: [f64; 6]) {
+    let actual = [
+        actual.min_x,
+        actual.min_y,
+        actual.min_z,
+        actual.max_x,
+        actual.max_y,
+        actual.max_z,
+    ];
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn sponge_schematic_produces_block_shapes_at_origin() {
+    // 2 x 1 x 1: stone, then a top slab.
+    let bytes = encode_root(Tag::Compound(vec![
+        ("Version", Tag::Int(2)),
+        ("Width", Tag::Short(2)),
+        ("Height", Tag::Short(1)),
+        ("Length", Tag::Short(1)),
+        (
+            "Palette",
+            Tag::Compound(vec![
+                ("minecraft:stone", Tag::Int(0)),
+                (
+                    "minecraft:oak_slab[type=top,waterlogged=false]",
+                    Tag::Int(1),
+                ),
+            ]),
+        ),
+        ("BlockData", Tag::ByteArray(vec![0, 1])),
+    ]));
+
+    let schematic = Schematic::from_sponge_bytes(&bytes).expect("schematic should parse");
+    let boxes = schematic.collision_boxes(Space3D::new(10.0, 64.0, -3.0));
+
+    assert_eq!(boxes.len(), 2);
+    assert_box(&boxes[0], [10.0, 64.0, -3.0, 11.0, 65.0, -2.0]);
+    assert_box(&boxes[1], [11.0, 64.5, -3.0, 12.0, 65.0, -2.0]);
+}
+
+#[test]
+fn litematic_regions_unpack_spanning_block_states() {
+    // Five palette entries need three bits, so entries straddle the long boundary.
+    let palette = ["air", "stone", "cobblestone_wall", "oak_fence", "glass"];
+    let size_x = 30;
+    let states: Vec<u64> = (0..size_x).map(|x| if x == 21 { 3 } else { 0 }).collect();
+    let mut packed = vec![0i64; (size_x * 3usize).div_ceil(64)];
+    for (index, state) in states.iter().enumerate() {
+        let bit = index * 3;
+        packed[bit / 64] |= (state << (bit % 64)) as i64;
+        if bit % 64 > 61 {
+            packed[bit / 64 + 1] |= (state >> (64 - bit % 64)) as i64;
+        }
+    }
+
+    let vec3 = |x, y, z| {
+        Tag::Compound(vec![
+            ("x", Tag::Int(x)),
+            ("y", Tag::Int(y)),
+            ("z", Tag::Int(z)),
+        ])
+    };
+    let bytes = encode_root(Tag::Compound(vec![(
+        "Regions",
+        Tag::Compound(vec![(
+            "Cannon",
+            Tag::Compound(vec![
+                ("Position", vec3(0, 0, 0)),
+                ("Size", vec3(-(size_x as i32), 1, 1)),
+                (
+                    "BlockStatePalette",
+                    Tag::List(
+                        palette
+                            .iter()
+                            .map(|name| Tag::Compound(vec![("Name", Tag::String(name))]))
+                            .collect(),
+                    ),
+                ),
+                ("BlockStates", Tag::LongArray(packed)),
+            ]),
+        )]),
+    )]));
+
+    let schematic = Schematic::from_litematic_bytes(&bytes).expect("litematic should parse");
+    let boxes = schematic.collision_boxes(Space3D::default());
+
+    // An unconnected fence is only its post, one and a half blocks tall.
+    assert_eq!(boxes.len(), 1);
+    let block_x = -29.0 + 21.0;
+    assert_box(
+        &boxes[0],
+        [block_x + 0.375, 0.0, 0.375, block_x + 0.625, 1.5, 0.625],
+    );
+}
+
+#[test]
+fn stairs_and_walls_follow_their_block_states() {
+    // 3 x 1 x 1: top stairs facing east, a wall post with two arms, then a bare wall arm.
+    let bytes = encode_root(Tag::Compound(vec![
+        ("Version", Tag::Int(2)),
+        ("Width", Tag::Short(3)),
+        ("Height", Tag::Short(1)),
+        ("Length", Tag::Short(1)),
+        (
+            "Palette",
+            Tag::Compound(vec![
+                (
+                    "minecraft:oak_stairs[facing=east,half=top,shape=straight]",
+                    Tag::Int(0),
+                ),
+                (
+                    "minecraft:cobblestone_wall[east=tall,north=low,south=none,up=true,west=none]",
+                    Tag::Int(1),
+                ),
+                (
+                    "minecraft:cobblestone_wall[east=none,north=none,south=tall,up=false,west=none]",
+                    Tag::Int(2),
+                ),
+            ]),
+        ),
+        ("BlockData", Tag::ByteArray(vec![0, 1, 2])),
+    ]));
+
+    let schematic = Schematic::from_sponge_bytes(&bytes).expect("schematic should parse");
+    let boxes = schematic.collision_boxes(Space3D::default());
+
+    assert_eq!(boxes.len(), 7);
+    // Upside-down stairs: the slab on top, the step below on the east half.
+    assert_box(&boxes[0], [0.0, 0.5, 0.0, 1.0, 1.0, 1.0]);
+    assert_box(&boxes[1], [0.5, 0.0, 0.0, 1.0, 0.5, 0.5]);
+    assert_box(&boxes[2], [0.5, 0.0, 0.5, 1.0, 0.5, 1.0]);
+    // Wall post, then its north and east arms, all a block and a half tall.
+    assert_box(&boxes[3], [1.25, 0.0, 0.25, 1.75, 1.5, 0.75]);
+    assert_box(&boxes[4], [1.3125, 0.0, 0.0, 1.6875, 1.5, 0.6875]);
+    assert_box(&boxes[5], [1.3125, 0.0, 0.3125, 2.0, 1.5, 0.6875]);
+    // Without `up` the wall is only its south arm.
+    assert_box(&boxes[6], [2.3125, 0.0, 0.3125, 2.6875, 1.5, 1.0]);
+}
@@ -0,0 +1,113 @@
+use pearl_calculator_core::calculation::calculation::calculate_raw_trace;
+use pearl_calculator_core::calculation::inputs::{GeneralData, TNT};
+use pearl_calculator_core::calculation::results::PearlOutcome;
+use pearl_calculator_core::calculation::simulator::Simulator;
+use pearl_calculator_core::physics::entities::explosion::ExplosionKind;
+use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::entities::projectile::ProjectileKind;
+use pearl_calculator_core::physics::world::chunks::{ChunkLoading, LoadTicket};
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::space::Space3D;
+
+fn group(x: f64, fuse: u32) -> TNT {
+    TNT {
+        position: Space3D::new(x, 100.0, 0.0),
+        fuse,
+        motion: Space3D::default(),
+        source: ExplosionKind::Tnt,
+        amount: 1,
+        anchored: true,
+    }
+}
+
+fn simulator(tnt_charges: Vec<TNT>) -> Simulator {
+    let data = GeneralData {
+        pearl_position: Space3D::new(0.0, 100.0, 0.0),
+        pearl_motion: Space3D::default(),
+        tnt_charges,
+        projectile: ProjectileKind::EnderPearl,
+    };
+    Simulator::new(
+        &data,
+        CollisionWorld::default(),
+        PearlVersion::Post1212.profile(),
+    )
+}
+
+#[test]
+fn stepping_matches_a_full_run_and_snapshots_rewind_it() {
+    let charges = vec![group(-6.0, 0), group(3.0, 5)];
+    let expected = calculate_raw_trace(
+        Space3D::new(0.0, 100.0, 0.0),
+        Space3D::default(),
+        charges.clone(),
+        40,
+        &CollisionWorld::default(),
+        &PearlVersion::Post1212.profile(),
+    )
+    .expect("trace");
+
+    let mut sim = simulator(charges);
+    assert_eq!(sim.step(3).len(), 3);
+    let saved = sim.snapshot();
+    sim.step(37);
+    assert_eq!(sim.tick(), 40);
+    assert_eq!(sim.records(), expected.records.as_slice());
+
+    sim.restore(saved.clone());
+    assert_eq!(sim.tick(), 3);
+    assert_eq!(sim.snapshot(), saved);
+    sim.step(37);
+    assert_eq!(sim.records(), expected.records.as_slice());
+}
+
+#[test]
+fn injected_explosions_push_like_a_charge_on_that_tick() {
+    let mut charged = simulator(vec![group(-6.0, 0), group(3.0, 5)]);
+    charged.step(20);
+
+    let mut injected = simulator(vec![group(-6.0, 0)]);
+    injected.step(5);
+    injected.inject_explosion(Space3D::new(3.0, 100.0, 0.0), ExplosionKind::Tnt, 1);
+    injected.step(15);
+
+    assert_eq!(
+        injected.pearl().data.position,
+        charged.pearl().data.position
+    );
+    assert_eq!(injected.pearl().data.motion, charged.pearl().data.motion);
+}
+
+#[test]
+fn stepping_again_after_a_stall_does_not_detonate_charges_twice() {
+    // Only the chunks within two rings of the ticket tick entities, so a snowball far out stalls.
+    let world = CollisionWorld::default().with_chunk_loading(
+        ChunkLoading::new(vec![LoadTicket {
+            position: Space3D::new(0.0, 0.0, 0.0),
+            level: 29,
+        }]),
+        Space3D::default(),
+    );
+    let data = GeneralData {
+        pearl_position: Space3D::new(200.0, 100.0, 8.0),
+        pearl_motion: Space3D::default(),
+        tnt_charges: vec![TNT {
+            position: Space3D::new(197.0, 100.0, 8.0),
+            ..group(0.0, 0)
+        }],
+        projectile: ProjectileKind::Snowball,
+    };
+    let mut sim = Simulator::new(&data, world, PearlVersion::Post1212.profile());
+
+    assert!(sim.step(1).is_empty());
+    assert_eq!(sim.outcome(), Some(PearlOutcome::Stalled));
+    let stalled = sim.snapshot();
+
+    sim.clear_outcome();
+    assert!(sim.step(1).is_empty());
+    assert_eq!(sim.outcome(), Some(PearlOutcome::Stalled));
+    assert_eq!(sim.tick(), 0);
+    assert_eq!(sim.pearl(), &stalled.pearl);
+    assert_eq!(sim.tnt_entities(), stalled.tnt_entities.as_slice());
+    assert_eq!(sim.pearl().data.motion, Space3D::default());
+}
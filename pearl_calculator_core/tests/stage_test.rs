@@ -0,0 +1,122 @@
+mod common;
+
+use common::test_cannon;
+use pearl_calculator_core::calculation::calculation::{
+    calculate_pearl_trace, calculate_tnt_amount,
+};
+use pearl_calculator_core::calculation::inputs::CannonStage;
+use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::direction::Direction;
+use pearl_calculator_core::physics::world::space::Space3D;
+
+#[test]
+fn staged_solutions_replay_through_the_trace() {
+    let mut cannon = test_cannon();
+    cannon.stages = vec![CannonStage {
+        position: Space3D::new(0.0, 99.0, 0.0),
+        fuse: 0,
+        max_tnt: 3,
+    }];
+    cannon.aim_fuse = 4;
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+
+    let results = calculate_tnt_amount(
+        &cannon,
+        Space3D::new(3.0, 0.0, 160.0),
+        40,
+        None,
+        300,
+        2.0,
+        &world,
+        &profile,
+        false,
+    );
+    let best = results
+        .iter()
+        .filter(|result| result.stages[0] > 0)
+        .min_by(|a, b| a.distance.total_cmp(&b.distance))
+        .expect("a boosted solution");
+    assert!(best.tick > cannon.aim_fuse);
+
+    let trace = calculate_pearl_trace(
+        &cannon,
+        best.red,
+        best.blue,
+        best.vertical,
+        &best.stages,
+        best.direction,
+        best.tick,
+        &world,
+        &profile,
+    )
+    .expect("trace");
+    assert!((trace.landing_position - best.pearl_end_pos).length() < 1e-9);
+}
+
+#[test]
+fn large_stage_counts_are_solved_rather_than_enumerated() {
+    let mut cannon = test_cannon();
+    cannon.stages = vec![
+        CannonStage {
+            position: Space3D::new(0.0, 100.0, -1.5),
+            fuse: 0,
+            max_tnt: 1000,
+        },
+        CannonStage {
+            position: Space3D::new(-1.5, 100.0, 0.0),
+            fuse: 0,
+            max_tnt: 1000,
+        },
+    ];
+    cannon.aim_fuse = 1;
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+
+    let aimed = calculate_pearl_trace(
+        &cannon,
+        2,
+        1,
+        0,
+        &[6, 4],
+        Direction::South,
+        150,
+        &world,
+        &profile,
+    )
+    .expect("trace");
+    let destination = aimed.landing_position;
+
+    let results = calculate_tnt_amount(
+        &cannon,
+        destination,
+        3,
+        None,
+        300,
+        2.0,
+        &world,
+        &profile,
+        false,
+    );
+    let best = results
+        .iter()
+        .min_by(|a, b| a.distance.total_cmp(&b.distance))
+        .expect("a staged solution");
+    assert!(best.distance <= 2.0);
+    assert!(best.stages.iter().sum::<u32>() > 0);
+
+    let trace = calculate_pearl_trace(
+        &cannon,
+        best.red,
+        best.blue,
+        best.vertical,
+        &best.stages,
+        best.direction,
+        best.tick,
+        &world,
+        &profile,
+    )
+    .expect("trace");
+    assert!((trace.landing_position - best.pearl_end_pos).length() < 1e-9);
+}
@@ -0,0 +1,310 @@
+mod common;
+
+use common::test_cannon;
+use pearl_calculator_core::calculation::calculation::{calculate_pearl_trace, calculate_raw_trace};
+use pearl_calculator_core::calculation::inputs::{GeneralData, TNT};
+use pearl_calculator_core::calculation::results::TickRecord;
+use pearl_calculator_core::calculation::simulation::{
+    self, calculate_explosion_motion, calculate_tnt_motion,
+};
+use pearl_calculator_core::physics::aabb::aabb_box::AABBBox;
+use pearl_calculator_core::physics::entities::explosion::ExplosionKind;
+use pearl_calculator_core::physics::entities::movement::PearlVersion;
+use pearl_calculator_core::physics::entities::projectile::ProjectileKind;
+use pearl_calculator_core::physics::world::collision_world::CollisionWorld;
+use pearl_calculator_core::physics::world::direction::Direction;
+use pearl_calculator_core::physics::world::space::Space3D;
+
+fn explosion_push(tnt_charges: Vec<TNT>, fuse: u32, world: &CollisionWorld) -> (Space3D, Space3D) {
+    let data = GeneralData {
+        pearl_position: Space3D::new(0.0, 100.0, 0.0),
+        pearl_motion: Space3D::default(),
+        tnt_charges,
+        projectile: ProjectileKind::EnderPearl,
+    };
+    let before =
+        simulation::run(&data, None, fuse, world, &PearlVersion::Post1205.profile()).expect("run");
+    let after = simulation::run(
+        &data,
+        None,
+        fuse + 1,
+        world,
+        &PearlVersion::Post1205.profile(),
+    )
+    .expect("run");
+    // Post-1.20.5 pearls move before drag, so the change in motion over the fuse tick is the push
+    // followed by one round of drag and gravity.
+    let pushed = after.final_motion;
+    let unpushed = before.final_motion * 0.99 - Space3D::new(0.0, 0.03, 0.0);
+    (before.landing_position, (pushed - unpushed) / 0.99)
+}
+
+#[test]
+fn primed_tnt_falls_and_rests_before_exploding() {
+    let spawn = Space3D::new(1.5, 100.0, 0.0);
+    let charge = || TNT {
+        position: spawn,
+        fuse: 10,
+        motion: Space3D::default(),
+        source: ExplosionKind::Tnt,
+        amount: 1,
+        anchored: false,
+    };
+
+    let mut fallen = spawn;
+    let mut velocity = 0.0;
+    for _ in 0..10 {
+        velocity -= 0.04;
+        fallen.y += velocity;
+        velocity *= 0.98;
+    }
+
+    let (pearl_at_fuse, push) = explosion_push(vec![charge()], 10, &CollisionWorld::default());
+    let expected = calculate_tnt_motion(
+        pearl_at_fuse,
+        fallen,
+        &CollisionWorld::default(),
+        &PearlVersion::Post1205.profile(),
+    );
+    assert!(
+        (push - expected).length() < 1e-9,
+        "{push:?} vs {expected:?}"
+    );
+
+    let floor = CollisionWorld::new(vec![AABBBox::new(1.0, 99.0, -1.0, 2.0, 100.0, 1.0)]);
+    let (pearl_at_fuse, resting) = explosion_push(vec![charge()], 10, &floor);
+    let expected = calculate_tnt_motion(
+        pearl_at_fuse,
+        spawn,
+        &floor,
+        &PearlVersion::Post1205.profile(),
+    );
+    assert!(
+        (resting - expected).length() < 1e-9,
+        "{resting:?} vs {expected:?}"
+    );
+}
+
+#[test]
+fn explosion_sources_scale_by_power() {
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let pearl = Space3D::new(0.0, 100.0, 0.0);
+    let source = Space3D::new(0.0, 100.0, -10.0);
+
+    assert_eq!(
+        calculate_explosion_motion(
+            pearl,
+            source,
+            ExplosionKind::Tnt,
+            ProjectileKind::EnderPearl,
+            &world,
+            &profile
+        ),
+        Space3D::default()
+    );
+    let crystal = calculate_explosion_motion(
+        pearl,
+        source,
+        ExplosionKind::EndCrystal,
+        ProjectileKind::EnderPearl,
+        &world,
+        &profile,
+    );
+    assert!(crystal.z > 0.0);
+
+    let close = Space3D::new(0.0, 100.0, -1.0);
+    let wind = calculate_explosion_motion(
+        pearl,
+        close,
+        ExplosionKind::WindCharge,
+        ProjectileKind::EnderPearl,
+        &world,
+        &profile,
+    );
+    let expected = (1.0 - 1.0 / (2.4f32 as f64)) * (1.22f32 as f64);
+    assert!((wind.length() - expected).abs() < 1e-12);
+}
+
+#[test]
+fn raw_trace_groups_explode_on_their_own_fuse() {
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let group = |x: f64, fuse: u32| TNT {
+        position: Space3D::new(x, 100.0, 0.0),
+        fuse,
+        motion: Space3D::default(),
+        source: ExplosionKind::Tnt,
+        amount: 1,
+        anchored: true,
+    };
+    let trace = |charges: Vec<TNT>| {
+        calculate_raw_trace(
+            Space3D::new(0.0, 100.0, 0.0),
+            Space3D::default(),
+            charges,
+            40,
+            &world,
+            &profile,
+        )
+        .expect("trace")
+        .pearl_motion_trace()
+    };
+
+    let early = trace(vec![group(-6.0, 0)]);
+    let both = trace(vec![group(-6.0, 0), group(3.0, 5)]);
+    let diverged = early
+        .iter()
+        .zip(&both)
+        .position(|(a, b)| a != b)
+        .expect("the delayed group pushes the pearl");
+    assert_eq!(diverged, 6);
+    assert!(both[diverged].x < early[diverged].x);
+}
+
+#[test]
+fn trace_records_mark_the_tick_each_group_explodes() {
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let group = |x: f64, fuse: u32| TNT {
+        position: Space3D::new(x, 100.0, 0.0),
+        fuse,
+        motion: Space3D::default(),
+        source: ExplosionKind::Tnt,
+        amount: 1,
+        anchored: true,
+    };
+
+    let result = calculate_raw_trace(
+        Space3D::new(0.0, 100.0, 0.0),
+        Space3D::default(),
+        vec![group(-6.0, 0), group(3.0, 5)],
+        40,
+        &world,
+        &profile,
+    )
+    .expect("trace");
+
+    assert!(
+        result
+            .records
+            .iter()
+            .enumerate()
+            .all(|(index, record)| record.tick == index as u32)
+    );
+    assert_eq!(result.records[1].exploded, vec![0]);
+    assert_eq!(result.records[6].exploded, vec![1]);
+    assert_eq!(
+        result
+            .records
+            .iter()
+            .filter(|record| !record.exploded.is_empty())
+            .count(),
+        2
+    );
+    assert_eq!(
+        result.pearl_trace().len(),
+        result.pearl_motion_trace().len()
+    );
+}
+
+#[test]
+fn empty_groups_keep_their_index_in_trace_records() {
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let group = |x: f64, fuse: u32, amount: u32| TNT {
+        position: Space3D::new(x, 100.0, 0.0),
+        fuse,
+        motion: Space3D::default(),
+        source: ExplosionKind::Tnt,
+        amount,
+        anchored: true,
+    };
+    let trace = |groups: Vec<TNT>| {
+        calculate_raw_trace(
+            Space3D::new(0.0, 100.0, 0.0),
+            Space3D::default(),
+            groups,
+            40,
+            &world,
+            &profile,
+        )
+        .expect("trace")
+    };
+
+    let padded = trace(vec![group(-3.0, 0, 0), group(-6.0, 0, 1), group(3.0, 5, 1)]);
+    assert_eq!(padded.records[1].exploded, vec![1]);
+    assert_eq!(padded.records[6].exploded, vec![2]);
+    assert_eq!(
+        padded.pearl_trace(),
+        trace(vec![group(-6.0, 0, 1), group(3.0, 5, 1)]).pearl_trace()
+    );
+}
+
+#[test]
+fn pearl_traces_record_the_aimers_exploding() {
+    let world = CollisionWorld::default();
+    let profile = PearlVersion::Post1212.profile();
+    let trace = calculate_pearl_trace(
+        &test_cannon(),
+        4,
+        2,
+        0,
+        &[],
+        Direction::South,
+        40,
+        &world,
+        &profile,
+    )
+    .expect("trace");
+
+    // Red and blue both go off before the pearl's first move.
+    assert_eq!(trace.records[1].exploded, vec![0, 1]);
+    assert!(
+        trace.records[2..]
+            .iter()
+            .all(|record| record.exploded.is_empty())
+    );
+}
+
+#[test]
+fn trace_records_flag_the_block_the_pearl_lands_on() {
+    let profile = PearlVersion::Post1212.profile();
+    let trace = |motion: Space3D, block: AABBBox| {
+        calculate_raw_trace(
+            Space3D::new(0.0, 100.0, 0.0),
+            motion,
+            Vec::new(),
+            40,
+            &CollisionWorld::new(vec![block]),
+            &profile,
+        )
+        .expect("trace")
+        .records
+    };
+    let in_flight = |records: &[TickRecord]| {
+        records.iter().all(|record| {
+            !record.on_ground && !record.is_collided_horizontally && !record.is_collided_vertically
+        })
+    };
+
+    let floor = trace(
+        Space3D::default(),
+        AABBBox::new(-2.0, 90.0, -2.0, 2.0, 95.0, 2.0),
+    );
+    let (landed, before) = floor.split_last().expect("records");
+    assert!(in_flight(before));
+    assert!(landed.on_ground && landed.is_collided_vertically);
+    assert!(!landed.is_collided_horizontally);
+    assert_eq!(landed.position.y, 95.0);
+
+    let wall = trace(
+        Space3D::new(1.5, 0.0, 0.0),
+        AABBBox::new(5.0, 90.0, -2.0, 6.0, 110.0, 2.0),
+    );
+    let (landed, before) = wall.split_last().expect("records");
+    assert!(in_flight(before));
+    assert!(landed.is_collided_horizontally);
+    assert!(!landed.on_ground && !landed.is_collided_vertically);
+    assert_eq!(landed.position.x, 5.0);
+}
@@ -1,13 +1,110 @@
 use pearl_calculator_bridge::api;
-use pearl_calculator_bridge::inputs::{CalculationInput, PearlTraceInput, RawTraceInput};
-use pearl_calculator_bridge::outputs::PearlTraceOutput;
+use pearl_calculator_bridge::inputs::{
+    CalculationInput, EntityEditInput, ExplosionInput, GroupCalculationInput, LookupBuildInput,
+    PearlTraceInput, RawTraceInput, RobustnessInput, TntGroupInput,
+};
+use pearl_calculator_bridge::outputs::{
+    ChunkReportOutput, PearlTraceOutput, RobustResultOutput, SimulatorOutput, TNTSearchOutput,
+};
+use pearl_calculator_bridge::session::SimulatorSession;
+use pearl_calculator_core::calculation::lookup::LookupTable;
+use pearl_calculator_core::calculation::progress::CancellationToken;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, State};
+
+pub type LookupTableStore = Mutex<Option<LookupTable>>;
+pub type CancellationStore = Mutex<CancellationToken>;
+pub type SimulatorStore = Mutex<Option<SimulatorSession>>;
 
 #[tauri::command]
-pub fn calculate_tnt_amount_command(input: CalculationInput) -> Result<serde_json::Value, String> {
-    let results = api::calculate_tnt_amount(input)?;
+pub fn calculate_tnt_amount_command(
+    input: CalculationInput,
+    lookup_table: State<'_, LookupTableStore>,
+) -> Result<serde_json::Value, String> {
+    let lookup_table = lookup_table.lock().map_err(|error| error.to_string())?;
+    let results = api::calculate_tnt_amount_with_table(input, lookup_table.as_ref())?;
     serde_json::to_value(&results).map_err(|e| format!("Serialization error: {}", e))
 }
 
+#[tauri::command(async)]
+pub fn calculate_group_tnt_amount_command(
+    input: GroupCalculationInput,
+) -> Result<TNTSearchOutput, String> {
+    api::calculate_group_tnt_amount(input)
+}
+
+/// Emits a `calculation-progress` event for every progress report while solving.
+#[tauri::command(async)]
+pub fn calculate_tnt_amount_with_progress_command(
+    app: AppHandle,
+    input: CalculationInput,
+    cancellation: State<'_, CancellationStore>,
+) -> Result<serde_json::Value, String> {
+    let cancel = CancellationToken::new();
+    *cancellation.lock().map_err(|error| error.to_string())? = cancel.clone();
+
+    let search = api::calculate_tnt_amount_with_progress(input, &cancel, &mut |progress| {
+        let _ = app.emit("calculation-progress", progress);
+    })?;
+    serde_json::to_value(&search).map_err(|e| format!("Serialization error: {}", e))
+}
+
+/// Same as [`calculate_tnt_amount_with_progress_command`] for a cannon with named charge groups.
+#[tauri::command(async)]
+pub fn calculate_group_tnt_amount_with_progress_command(
+    app: AppHandle,
+    input: GroupCalculationInput,
+    cancellation: State<'_, CancellationStore>,
+) -> Result<TNTSearchOutput, String> {
+    let cancel = CancellationToken::new();
+    *cancellation.lock().map_err(|error| error.to_string())? = cancel.clone();
+
+    api::calculate_group_tnt_amount_with_progress(input, &cancel, &mut |progress| {
+        let _ = app.emit("calculation-progress", progress);
+    })
+}
+
+#[tauri::command]
+pub fn cancel_calculation_command(
+    cancellation: State<'_, CancellationStore>,
+) -> Result<(), String> {
+    cancellation
+        .lock()
+        .map_err(|error| error.to_string())?
+        .cancel();
+    Ok(())
+}
+
+#[tauri::command(async)]
+pub fn analyse_robustness_command(
+    input: RobustnessInput,
+) -> Result<Vec<RobustResultOutput>, String> {
+    api::analyse_robustness(input)
+}
+
+#[tauri::command(async)]
+pub fn build_lookup_table_command(input: LookupBuildInput) -> Result<Vec<u8>, String> {
+    api::build_lookup_table(input)
+}
+
+#[tauri::command]
+pub fn load_lookup_table_command(
+    bytes: Vec<u8>,
+    lookup_table: State<'_, LookupTableStore>,
+) -> Result<(), String> {
+    let table = api::load_lookup_table(&bytes)?;
+    *lookup_table.lock().map_err(|error| error.to_string())? = Some(table);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn unload_lookup_table_command(
+    lookup_table: State<'_, LookupTableStore>,
+) -> Result<(), String> {
+    *lookup_table.lock().map_err(|error| error.to_string())? = None;
+    Ok(())
+}
+
 #[tauri::command]
 pub fn calculate_pearl_trace_command(input: PearlTraceInput) -> Result<PearlTraceOutput, String> {
     api::calculate_pearl_trace(input)
@@ -17,3 +114,75 @@ pub fn calculate_pearl_trace_command(input: PearlTraceInput) -> Result<PearlTrac
 pub fn calculate_raw_trace_command(input: RawTraceInput) -> Result<PearlTraceOutput, String> {
     api::calculate_raw_trace(input)
 }
+
+#[tauri::command]
+pub fn calculate_chunk_report_command(input: PearlTraceInput) -> Result<ChunkReportOutput, String> {
+    api::calculate_chunk_report(input)
+}
+
+fn with_simulator<T>(
+    simulator: &SimulatorStore,
+    action: impl FnOnce(&mut SimulatorSession) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut simulator = simulator.lock().map_err(|error| error.to_string())?;
+    let session = simulator
+        .as_mut()
+        .ok_or_else(|| "No simulator has been created".to_string())?;
+    action(session)
+}
+
+#[tauri::command]
+pub fn create_simulator_command(
+    input: RawTraceInput,
+    simulator: State<'_, SimulatorStore>,
+) -> Result<SimulatorOutput, String> {
+    let session = SimulatorSession::new(input)?;
+    let state = session.state();
+    *simulator.lock().map_err(|error| error.to_string())? = Some(session);
+    Ok(state)
+}
+
+#[tauri::command]
+pub fn step_simulator_command(
+    ticks: u32,
+    simulator: State<'_, SimulatorStore>,
+) -> Result<SimulatorOutput, String> {
+    with_simulator(&simulator, |session| Ok(session.step(ticks)))
+}
+
+#[tauri::command]
+pub fn edit_simulator_command(
+    input: EntityEditInput,
+    simulator: State<'_, SimulatorStore>,
+) -> Result<SimulatorOutput, String> {
+    with_simulator(&simulator, |session| session.edit(input))
+}
+
+#[tauri::command]
+pub fn inject_simulator_explosion_command(
+    input: ExplosionInput,
+    simulator: State<'_, SimulatorStore>,
+) -> Result<SimulatorOutput, String> {
+    with_simulator(&simulator, |session| Ok(session.inject_explosion(input)))
+}
+
+#[tauri::command]
+pub fn add_simulator_tnt_command(
+    group: TntGroupInput,
+    simulator: State<'_, SimulatorStore>,
+) -> Result<SimulatorOutput, String> {
+    with_simulator(&simulator, |session| Ok(session.add_tnt(group)))
+}
+
+#[tauri::command]
+pub fn snapshot_simulator_command(simulator: State<'_, SimulatorStore>) -> Result<usize, String> {
+    with_simulator(&simulator, |session| Ok(session.snapshot()))
+}
+
+#[tauri::command]
+pub fn restore_simulator_command(
+    id: usize,
+    simulator: State<'_, SimulatorStore>,
+) -> Result<SimulatorOutput, String> {
+    with_simulator(&simulator, |session| session.restore(id))
+}
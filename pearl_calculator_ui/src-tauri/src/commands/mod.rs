@@ -3,7 +3,14 @@ pub mod config;
 pub mod state;
 
 pub use calculation::{
+    CancellationStore, LookupTableStore, SimulatorStore, add_simulator_tnt_command,
+    analyse_robustness_command, build_lookup_table_command, calculate_chunk_report_command,
+    calculate_group_tnt_amount_command, calculate_group_tnt_amount_with_progress_command,
     calculate_pearl_trace_command, calculate_raw_trace_command, calculate_tnt_amount_command,
+    calculate_tnt_amount_with_progress_command, cancel_calculation_command,
+    create_simulator_command, edit_simulator_command, inject_simulator_explosion_command,
+    load_lookup_table_command, restore_simulator_command, snapshot_simulator_command,
+    step_simulator_command, unload_lookup_table_command,
 };
 pub use config::{load_config, load_config_from_content, verify_config};
 pub use state::{dispatch_app_state_action, get_app_state};
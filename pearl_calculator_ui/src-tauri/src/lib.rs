@@ -3,14 +3,24 @@ mod commands;
 
 use commands::state::AppStateStore;
 use commands::{
+    CancellationStore, LookupTableStore, SimulatorStore, add_simulator_tnt_command,
+    analyse_robustness_command, build_lookup_table_command, calculate_chunk_report_command,
+    calculate_group_tnt_amount_command, calculate_group_tnt_amount_with_progress_command,
     calculate_pearl_trace_command, calculate_raw_trace_command, calculate_tnt_amount_command,
-    dispatch_app_state_action, get_app_state, load_config, load_config_from_content, verify_config,
+    calculate_tnt_amount_with_progress_command, cancel_calculation_command,
+    create_simulator_command, dispatch_app_state_action, edit_simulator_command, get_app_state,
+    inject_simulator_explosion_command, load_config, load_config_from_content,
+    load_lookup_table_command, restore_simulator_command, snapshot_simulator_command,
+    step_simulator_command, unload_lookup_table_command, verify_config,
 };
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .manage(AppStateStore::new(app_state::AppStateSnapshot::default()))
+        .manage(LookupTableStore::default())
+        .manage(CancellationStore::default())
+        .manage(SimulatorStore::default())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
@@ -22,8 +32,24 @@ pub fn run() {
             load_config,
             load_config_from_content,
             calculate_tnt_amount_command,
+            calculate_group_tnt_amount_command,
+            calculate_group_tnt_amount_with_progress_command,
+            calculate_tnt_amount_with_progress_command,
+            cancel_calculation_command,
+            analyse_robustness_command,
             calculate_pearl_trace_command,
-            calculate_raw_trace_command
+            calculate_raw_trace_command,
+            calculate_chunk_report_command,
+            build_lookup_table_command,
+            load_lookup_table_command,
+            unload_lookup_table_command,
+            create_simulator_command,
+            step_simulator_command,
+            edit_simulator_command,
+            inject_simulator_explosion_command,
+            add_simulator_tnt_command,
+            snapshot_simulator_command,
+            restore_simulator_command
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -98,24 +98,41 @@ function Vector3Block({
 	);
 }
 
+type TNTGroup = SimulatorConfig["groups"][number];
+
+const emptyTNTGroup: TNTGroup = {
+	pos: { x: 0, y: 0, z: 0 },
+	amount: 0,
+	fuse: 0,
+};
+
 function TNTGroupBlock({
 	title,
 	data,
 	onUpdate,
+	onRemove,
 }: {
 	title: string;
-	data: { pos: { x: number; y: number; z: number }; amount: number };
-	onUpdate: (data: {
-		pos: { x: number; y: number; z: number };
-		amount: number;
-	}) => void;
+	data: TNTGroup;
+	onUpdate: (data: TNTGroup) => void;
+	onRemove: () => void;
 }) {
 	const { t } = useTranslation();
 	const parse = (v: string) => CoercedNumberSchema.parse(v);
 
 	return (
 		<div className="space-y-1.5">
-			<div className="text-xs font-bold text-foreground/80">{title}</div>
+			<div className="flex items-center justify-between">
+				<div className="text-xs font-bold text-foreground/80">{title}</div>
+				<Button
+					variant="ghost"
+					size="sm"
+					className="h-5 px-1.5 text-xs"
+					onClick={onRemove}
+				>
+					{t("simulator.remove_tnt_group")}
+				</Button>
+			</div>
 			<div className="grid gap-1.5">
 				<CompactInput
 					label={t("simulator.label_x")}
@@ -147,6 +164,12 @@ function TNTGroupBlock({
 					value={data.amount}
 					onChange={(v) => onUpdate({ ...data, amount: parse(v) })}
 				/>
+				<CompactInput
+					label={t("simulator.label_fuse")}
+					labelClassName="w-10 text-left pr-0"
+					value={data.fuse}
+					onChange={(v) => onUpdate({ ...data, fuse: parse(v) })}
+				/>
 			</div>
 		</div>
 	);
@@ -212,22 +235,39 @@ export default function SimulatorInputForm({
 									})
 								}
 							/>
-							{(
-								[
-									{ key: "tntA", i18nKey: "simulator.tnt_group_a" },
-									{ key: "tntB", i18nKey: "simulator.tnt_group_b" },
-									{ key: "tntC", i18nKey: "simulator.tnt_group_c" },
-									{ key: "tntD", i18nKey: "simulator.tnt_group_d" },
-								] as const
-							).map(({ key, i18nKey }) => (
+							{config.groups.map((group, index) => (
 								<TNTGroupBlock
-									key={key}
-									title={t(i18nKey)}
-									data={config[key]}
-									onUpdate={(d) => onConfigChange({ ...config, [key]: d })}
+									key={index}
+									title={t("simulator.tnt_group", { index: index + 1 })}
+									data={group}
+									onUpdate={(d) =>
+										onConfigChange({
+											...config,
+											groups: config.groups.map((g, i) => (i === index ? d : g)),
+										})
+									}
+									onRemove={() =>
+										onConfigChange({
+											...config,
+											groups: config.groups.filter((_, i) => i !== index),
+										})
+									}
 								/>
 							))}
 						</div>
+						<Button
+							variant="outline"
+							size="sm"
+							className="w-full h-7 text-xs"
+							onClick={() =>
+								onConfigChange({
+									...config,
+									groups: [...config.groups, emptyTNTGroup],
+								})
+							}
+						>
+							{t("simulator.add_tnt_group")}
+						</Button>
 					</FieldSet>
 				</div>
 			</ScrollArea>
@@ -112,8 +112,9 @@ export const DataTable = React.forwardRef<
 		if (defaultSortColumn && columnIds.includes(defaultSortColumn)) {
 			return [{ id: defaultSortColumn, desc: false }];
 		}
+		// Calculation results arrive already ranked by the backend.
 		if (columnIds.includes("distance")) {
-			return [{ id: "distance", desc: false }];
+			return [];
 		}
 		if (columnIds.length > 0) {
 			return [{ id: columnIds[0], desc: false }];
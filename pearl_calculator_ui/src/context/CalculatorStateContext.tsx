@@ -82,22 +82,7 @@ export const emptySimulatorConfig: SimulatorConfig = {
 		pos: { x: 0, y: 0, z: 0 },
 		momentum: { x: 0, y: 0, z: 0 },
 	},
-	tntA: {
-		pos: { x: 0, y: 0, z: 0 },
-		amount: 0,
-	},
-	tntB: {
-		pos: { x: 0, y: 0, z: 0 },
-		amount: 0,
-	},
-	tntC: {
-		pos: { x: 0, y: 0, z: 0 },
-		amount: 0,
-	},
-	tntD: {
-		pos: { x: 0, y: 0, z: 0 },
-		amount: 0,
-	},
+	groups: [],
 };
 
 const CalculatorStateContext = createContext<
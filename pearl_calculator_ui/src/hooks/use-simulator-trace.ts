@@ -20,11 +20,12 @@ export function useSimulatorTrace() {
 				pearlMotionY: config.pearl.momentum.y,
 				pearlMotionZ: config.pearl.momentum.z,
 
-				tntGroups: (["tntA", "tntB", "tntC", "tntD"] as const).map((key) => ({
-					x: config[key].pos.x,
-					y: config[key].pos.y,
-					z: config[key].pos.z,
-					amount: config[key].amount,
+				tntGroups: config.groups.map((group) => ({
+					x: group.pos.x,
+					y: group.pos.y,
+					z: group.pos.z,
+					amount: group.amount,
+					fuse: group.fuse,
 				})),
 
 				version: version,
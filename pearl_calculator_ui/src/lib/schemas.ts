@@ -49,9 +49,39 @@ export const TNTResultSchema = z
 		direction: z.string(),
 		vertical: z.number().optional(),
 		charges: z.number().optional(),
+		stages: z.array(z.number()).optional(),
+		counts: z.array(z.number()).optional(),
+		peak_y: z.number().optional(),
+		sensitivity: z.number().optional(),
 	})
 	.passthrough();
 
+export const TNTSearchSchema = z.object({
+	results: z.array(TNTResultSchema),
+	cancelled: z.boolean(),
+	truncated: z.boolean().optional(),
+});
+
+export const RobustResultSchema = TNTResultSchema.extend({
+	trials: z.number(),
+	success_probability: z.number(),
+	mean_error: z.number(),
+	median_error: z.number(),
+	p95_error: z.number(),
+	max_error: z.number(),
+	errors: z.array(z.number()),
+});
+
+export const TickRecordSchema = z.object({
+	tick: z.number(),
+	position: z.object({ X: z.number(), Y: z.number(), Z: z.number() }),
+	motion: z.object({ X: z.number(), Y: z.number(), Z: z.number() }),
+	on_ground: z.boolean(),
+	is_collided_horizontally: z.boolean(),
+	is_collided_vertically: z.boolean(),
+	exploded: z.array(z.number()),
+});
+
 export const PearlTraceResultSchema = z.object({
 	landing_position: z.object({ X: z.number(), Y: z.number(), Z: z.number() }),
 	pearl_trace: z.array(
@@ -71,6 +101,48 @@ export const PearlTraceResultSchema = z.object({
 			distance: z.number(),
 		})
 		.nullish(),
+	stalled_at: z
+		.object({ X: z.number(), Y: z.number(), Z: z.number() })
+		.nullish(),
+	records: z.array(TickRecordSchema).optional(),
+});
+
+export const SimulatorStateSchema = z.object({
+	tick: z.number(),
+	pearl: TickRecordSchema,
+	tnt: z.array(
+		z.object({
+			position: z.object({ X: z.number(), Y: z.number(), Z: z.number() }),
+			motion: z.object({ X: z.number(), Y: z.number(), Z: z.number() }),
+			fuse: z.number(),
+			amount: z.number(),
+			source: z.string(),
+			exploded: z.boolean(),
+		}),
+	),
+	records: z.array(TickRecordSchema),
+	outcome: z.string().nullish(),
+});
+
+export const ChunkReportSchema = z.object({
+	ticks: z.array(
+		z.object({
+			tick: z.number(),
+			chunk_x: z.number(),
+			chunk_z: z.number(),
+			region_x: z.number(),
+			region_z: z.number(),
+		}),
+	),
+	crossings: z.array(
+		z.object({
+			tick: z.number(),
+			from_x: z.number(),
+			from_z: z.number(),
+			to_x: z.number(),
+			to_z: z.number(),
+		}),
+	),
 });
 
 export const TraceTNTSchema = z.object({
@@ -102,6 +174,7 @@ export const GeneralConfigSchema = z.object({
 const TntGroupSchema = z.object({
 	pos: Vector3Schema,
 	amount: z.number(),
+	fuse: z.number().default(0),
 });
 
 export const SimulatorConfigSchema = z.object({
@@ -109,10 +182,7 @@ export const SimulatorConfigSchema = z.object({
 		pos: Vector3Schema,
 		momentum: Vector3Schema,
 	}),
-	tntA: TntGroupSchema,
-	tntB: TntGroupSchema,
-	tntC: TntGroupSchema,
-	tntD: TntGroupSchema,
+	groups: z.array(TntGroupSchema),
 });
 
 export const CalculatorInputsSchema = z.object({
@@ -1,4 +1,52 @@
-import type { PearlTraceResult, TNTResult } from "@/types/domain";
+import type {
+	ChunkReport,
+	PearlTraceResult,
+	RobustResult,
+	SimulatorState,
+	TNTResult,
+	TNTSearch,
+} from "@/types/domain";
+
+export type Dimension = "Overworld" | "Nether" | "End";
+
+export type Projectile =
+	| "EnderPearl"
+	| "Snowball"
+	| "Egg"
+	| "Arrow"
+	| "Trident"
+	| "WindCharge";
+
+export interface PhysicsProfile {
+	name: string;
+	precision: "Float" | "Double";
+	tick_order: "MoveFirst" | "GravityFirst";
+	gravity?: number;
+	drag?: number;
+	water_drag?: number;
+	explosion?: {
+		knockback_scale?: number;
+		tnt_center_offset?: number;
+	};
+	pearls_load_chunks?: boolean;
+}
+
+export interface LoadTicketInput {
+	x: number;
+	z: number;
+	level: number;
+}
+
+export type RankingObjective =
+	| "Distance"
+	| "Total"
+	| "Tick"
+	| "PeakY"
+	| "Sensitivity";
+
+export type Ranking =
+	| { Weighted: { objective: RankingObjective; weight: number }[] }
+	| { Pareto: RankingObjective[] };
 
 export interface Space3DInput {
 	x: number;
@@ -6,6 +54,14 @@ export interface Space3DInput {
 	z: number;
 }
 
+export interface StageInput {
+	x: number;
+	y: number;
+	z: number;
+	fuse: number;
+	maxTnt: number;
+}
+
 export interface CalculationInput {
 	pearlX: number;
 	pearlY: number;
@@ -27,14 +83,74 @@ export interface CalculationInput {
 	maxTicks: number;
 	maxDistance: number;
 	version: string;
+	profile?: PhysicsProfile;
 	verticalTnt?: Space3DInput;
 	mode?: string;
+	dimension?: Dimension;
+	projectile?: Projectile;
+	stages?: StageInput[];
+	aimFuse?: number;
+	ranking?: Ranking;
+	chunkTickets?: LoadTicketInput[];
+}
+
+export interface LookupBuildInput extends CalculationInput {
+	radius: number;
+	step: number;
+}
+
+export interface RobustnessInput extends CalculationInput {
+	candidates?: number;
+	trials?: number;
+	tolerance?: number;
+	positionError?: number;
+	horizontalSpeed?: number;
+	jumpSpeed?: number;
+	fuseTicks?: number;
+	seed?: number;
+}
+
+export type FlightDirection = "North" | "South" | "East" | "West";
+
+export interface GroupInput {
+	name: string;
+	x: number;
+	y: number;
+	z: number;
+	minTnt?: number;
+	maxTnt: number;
+	/** Flight directions the group fires for; every direction when unset. */
+	directions?: FlightDirection[];
+}
+
+export interface GroupCalculationInput {
+	pearlX: number;
+	pearlY: number;
+	pearlZ: number;
+	pearlMotionX: number;
+	pearlMotionY: number;
+	pearlMotionZ: number;
+	cannonY: number;
+	groups: GroupInput[];
+	destinationX: number;
+	destinationY?: number;
+	destinationZ: number;
+	maxTicks: number;
+	maxDistance: number;
+	version: string;
+	profile?: PhysicsProfile;
+	mode?: string;
+	dimension?: Dimension;
+	projectile?: Projectile;
+	ranking?: Ranking;
+	chunkTickets?: LoadTicketInput[];
 }
 
 export interface PearlTraceInput {
 	redTnt: number;
 	blueTnt: number;
 	verticalTntAmount?: number;
+	stageTnt?: number[];
 	pearlX: number;
 	pearlY: number;
 	pearlZ: number;
@@ -52,8 +168,14 @@ export interface PearlTraceInput {
 	destinationZ: number;
 	direction?: string;
 	version: string;
+	profile?: PhysicsProfile;
 	verticalTnt?: Space3DInput;
 	mode?: string;
+	dimension?: Dimension;
+	projectile?: Projectile;
+	stages?: StageInput[];
+	aimFuse?: number;
+	chunkTickets?: LoadTicketInput[];
 }
 
 export interface TntGroupInput {
@@ -63,6 +185,7 @@ export interface TntGroupInput {
 	charges?: number;
 	z: number;
 	amount: number;
+	fuse?: number;
 }
 
 export interface RawTraceInput {
@@ -74,12 +197,75 @@ export interface RawTraceInput {
 	pearlMotionZ: number;
 	tntGroups: TntGroupInput[];
 	version: string;
+	profile?: PhysicsProfile;
+	dimension?: Dimension;
+	chunkTickets?: LoadTicketInput[];
+}
+
+export interface EntityEditInput {
+	/** Index of the charge to edit; the pearl when unset. */
+	tntIndex?: number;
+	x?: number;
+	y?: number;
+	z?: number;
+	motionX?: number;
+	motionY?: number;
+	motionZ?: number;
+	fuse?: number;
+}
+
+export type ExplosionSource =
+	| "Tnt"
+	| "TntMinecart"
+	| "EndCrystal"
+	| "Bed"
+	| "RespawnAnchor"
+	| "Creeper"
+	| "ChargedCreeper"
+	| "WindCharge";
+
+export interface ExplosionInput {
+	x: number;
+	y: number;
+	z: number;
+	amount?: number;
+	source?: ExplosionSource;
+}
+
+export interface SolveProgress {
+	direction: string;
+	validated: number;
+	total: number;
+	best: TNTResult[];
 }
 
 export interface ICalculatorService {
 	calculateTNTAmount(input: CalculationInput): Promise<TNTResult[]>;
+	calculateTNTAmountWithProgress(
+		input: CalculationInput,
+		onProgress: (progress: SolveProgress) => void,
+	): Promise<TNTSearch>;
+	cancelCalculation(): Promise<void>;
+	/** Each result's `counts` has one entry per group, in the order of `input.groups`. */
+	calculateGroupTNTAmount(input: GroupCalculationInput): Promise<TNTSearch>;
+	calculateGroupTNTAmountWithProgress(
+		input: GroupCalculationInput,
+		onProgress: (progress: SolveProgress) => void,
+	): Promise<TNTSearch>;
 	calculatePearlTrace(input: PearlTraceInput): Promise<PearlTraceResult>;
 	calculateRawTrace(input: RawTraceInput): Promise<PearlTraceResult>;
+	calculateChunkReport(input: PearlTraceInput): Promise<ChunkReport>;
+	analyseRobustness(input: RobustnessInput): Promise<RobustResult[]>;
+	buildLookupTable(input: LookupBuildInput): Promise<Uint8Array>;
+	loadLookupTable(bytes: Uint8Array): Promise<void>;
+	unloadLookupTable(): Promise<void>;
+	createSimulator(input: RawTraceInput): Promise<SimulatorState>;
+	stepSimulator(ticks: number): Promise<SimulatorState>;
+	editSimulator(input: EntityEditInput): Promise<SimulatorState>;
+	injectSimulatorExplosion(input: ExplosionInput): Promise<SimulatorState>;
+	addSimulatorTnt(group: TntGroupInput): Promise<SimulatorState>;
+	snapshotSimulator(): Promise<number>;
+	restoreSimulator(id: number): Promise<SimulatorState>;
 	copyToClipboard(text: string): Promise<void>;
 	readFromClipboard(): Promise<string>;
 }
@@ -1,13 +1,35 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { readText, writeText } from "@tauri-apps/plugin-clipboard-manager";
 import { z } from "zod";
-import { PearlTraceResultSchema, TNTResultSchema } from "@/lib/schemas";
-import type { PearlTraceResult, TNTResult } from "@/types/domain";
+import {
+	ChunkReportSchema,
+	PearlTraceResultSchema,
+	RobustResultSchema,
+	SimulatorStateSchema,
+	TNTResultSchema,
+	TNTSearchSchema,
+} from "@/lib/schemas";
+import type {
+	ChunkReport,
+	PearlTraceResult,
+	RobustResult,
+	SimulatorState,
+	TNTResult,
+	TNTSearch,
+} from "@/types/domain";
 import type {
 	CalculationInput,
+	EntityEditInput,
+	ExplosionInput,
+	GroupCalculationInput,
 	ICalculatorService,
+	LookupBuildInput,
 	PearlTraceInput,
 	RawTraceInput,
+	RobustnessInput,
+	SolveProgress,
+	TntGroupInput,
 } from "./interface";
 
 export class TauriCalculatorService implements ICalculatorService {
@@ -16,6 +38,57 @@ export class TauriCalculatorService implements ICalculatorService {
 		return z.array(TNTResultSchema).parse(result);
 	}
 
+	async calculateTNTAmountWithProgress(
+		input: CalculationInput,
+		onProgress: (progress: SolveProgress) => void,
+	): Promise<TNTSearch> {
+		const unlisten = await listen<SolveProgress>(
+			"calculation-progress",
+			(event) => onProgress(event.payload),
+		);
+		try {
+			const result = await invoke(
+				"calculate_tnt_amount_with_progress_command",
+				{ input },
+			);
+			return TNTSearchSchema.parse(result);
+		} finally {
+			unlisten();
+		}
+	}
+
+	async cancelCalculation(): Promise<void> {
+		await invoke("cancel_calculation_command");
+	}
+
+	async calculateGroupTNTAmount(
+		input: GroupCalculationInput,
+	): Promise<TNTSearch> {
+		const result = await invoke("calculate_group_tnt_amount_command", {
+			input,
+		});
+		return TNTSearchSchema.parse(result);
+	}
+
+	async calculateGroupTNTAmountWithProgress(
+		input: GroupCalculationInput,
+		onProgress: (progress: SolveProgress) => void,
+	): Promise<TNTSearch> {
+		const unlisten = await listen<SolveProgress>(
+			"calculation-progress",
+			(event) => onProgress(event.payload),
+		);
+		try {
+			const result = await invoke(
+				"calculate_group_tnt_amount_with_progress_command",
+				{ input },
+			);
+			return TNTSearchSchema.parse(result);
+		} finally {
+			unlisten();
+		}
+	}
+
 	async calculatePearlTrace(input: PearlTraceInput): Promise<PearlTraceResult> {
 		const result = await invoke("calculate_pearl_trace_command", { input });
 		return PearlTraceResultSchema.parse(result);
@@ -26,6 +99,69 @@ export class TauriCalculatorService implements ICalculatorService {
 		return PearlTraceResultSchema.parse(result);
 	}
 
+	async calculateChunkReport(input: PearlTraceInput): Promise<ChunkReport> {
+		const result = await invoke("calculate_chunk_report_command", { input });
+		return ChunkReportSchema.parse(result);
+	}
+
+	async analyseRobustness(input: RobustnessInput): Promise<RobustResult[]> {
+		const result = await invoke("analyse_robustness_command", { input });
+		return z.array(RobustResultSchema).parse(result);
+	}
+
+	async buildLookupTable(input: LookupBuildInput): Promise<Uint8Array> {
+		const bytes = await invoke<number[]>("build_lookup_table_command", {
+			input,
+		});
+		return Uint8Array.from(bytes);
+	}
+
+	async loadLookupTable(bytes: Uint8Array): Promise<void> {
+		await invoke("load_lookup_table_command", { bytes: Array.from(bytes) });
+	}
+
+	async unloadLookupTable(): Promise<void> {
+		await invoke("unload_lookup_table_command");
+	}
+
+	async createSimulator(input: RawTraceInput): Promise<SimulatorState> {
+		const result = await invoke("create_simulator_command", { input });
+		return SimulatorStateSchema.parse(result);
+	}
+
+	async stepSimulator(ticks: number): Promise<SimulatorState> {
+		const result = await invoke("step_simulator_command", { ticks });
+		return SimulatorStateSchema.parse(result);
+	}
+
+	async editSimulator(input: EntityEditInput): Promise<SimulatorState> {
+		const result = await invoke("edit_simulator_command", { input });
+		return SimulatorStateSchema.parse(result);
+	}
+
+	async injectSimulatorExplosion(
+		input: ExplosionInput,
+	): Promise<SimulatorState> {
+		const result = await invoke("inject_simulator_explosion_command", {
+			input,
+		});
+		return SimulatorStateSchema.parse(result);
+	}
+
+	async addSimulatorTnt(group: TntGroupInput): Promise<SimulatorState> {
+		const result = await invoke("add_simulator_tnt_command", { group });
+		return SimulatorStateSchema.parse(result);
+	}
+
+	async snapshotSimulator(): Promise<number> {
+		return await invoke<number>("snapshot_simulator_command");
+	}
+
+	async restoreSimulator(id: number): Promise<SimulatorState> {
+		const result = await invoke("restore_simulator_command", { id });
+		return SimulatorStateSchema.parse(result);
+	}
+
 	async copyToClipboard(text: string): Promise<void> {
 		await writeText(text);
 	}
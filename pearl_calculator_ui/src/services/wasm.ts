@@ -1,25 +1,123 @@
-import type { PearlTraceResult, TNTResult } from "@/types/domain";
+import type {
+	ChunkReport,
+	PearlTraceResult,
+	RobustResult,
+	SimulatorState,
+	TNTResult,
+	TNTSearch,
+} from "@/types/domain";
 import type {
 	CalculationInput,
+	EntityEditInput,
+	ExplosionInput,
+	GroupCalculationInput,
 	ICalculatorService,
+	LookupBuildInput,
 	PearlTraceInput,
 	RawTraceInput,
+	RobustnessInput,
+	SolveProgress,
+	TntGroupInput,
 } from "./interface";
 
+interface WasmLookupTable {
+	calculate_tnt_amount(input: CalculationInput): TNTResult[];
+	free(): void;
+}
+
+interface WasmSimulator {
+	state(): SimulatorState;
+	step(ticks: number): SimulatorState;
+	edit(input: EntityEditInput): SimulatorState;
+	inject_explosion(input: ExplosionInput): SimulatorState;
+	add_tnt(group: TntGroupInput): SimulatorState;
+	snapshot(): number;
+	restore(id: number): SimulatorState;
+	free(): void;
+}
+
 interface PearlCalculatorWasm {
 	calculate_tnt_amount(input: CalculationInput): TNTResult[];
+	calculate_tnt_amount_with_progress(
+		input: CalculationInput,
+		onProgress: (progress: SolveProgress) => boolean,
+	): TNTSearch;
+	calculate_group_tnt_amount(input: GroupCalculationInput): TNTSearch;
+	calculate_group_tnt_amount_with_progress(
+		input: GroupCalculationInput,
+		onProgress: (progress: SolveProgress) => boolean,
+	): TNTSearch;
 	calculate_pearl_trace(input: PearlTraceInput): PearlTraceResult;
 	calculate_raw_trace(input: RawTraceInput): PearlTraceResult;
+	calculate_chunk_report(input: PearlTraceInput): ChunkReport;
+	analyse_robustness(input: RobustnessInput): RobustResult[];
+	build_lookup_table(input: LookupBuildInput): Uint8Array;
+	LookupTable: new (bytes: Uint8Array) => WasmLookupTable;
+	Simulator: new (input: RawTraceInput) => WasmSimulator;
 }
 
 export class WebCalculatorService implements ICalculatorService {
+	private lookupTable: WasmLookupTable | null = null;
+	private simulator: WasmSimulator | null = null;
+	private cancelled = false;
+
 	async calculateTNTAmount(input: CalculationInput): Promise<TNTResult[]> {
+		if (this.lookupTable) {
+			return this.lookupTable.calculate_tnt_amount(input);
+		}
 		const wasm = (await import(
 			"pearl_calculator_wasm"
 		)) as unknown as Promise<PearlCalculatorWasm>;
 		return (await wasm).calculate_tnt_amount(input);
 	}
 
+	async calculateTNTAmountWithProgress(
+		input: CalculationInput,
+		onProgress: (progress: SolveProgress) => void,
+	): Promise<TNTSearch> {
+		const wasm = (await import(
+			"pearl_calculator_wasm"
+		)) as unknown as Promise<PearlCalculatorWasm>;
+		this.cancelled = false;
+		return (await wasm).calculate_tnt_amount_with_progress(
+			input,
+			(progress) => {
+				onProgress(progress);
+				return !this.cancelled;
+			},
+		);
+	}
+
+	async cancelCalculation(): Promise<void> {
+		this.cancelled = true;
+	}
+
+	async calculateGroupTNTAmount(
+		input: GroupCalculationInput,
+	): Promise<TNTSearch> {
+		const wasm = (await import(
+			"pearl_calculator_wasm"
+		)) as unknown as Promise<PearlCalculatorWasm>;
+		return (await wasm).calculate_group_tnt_amount(input);
+	}
+
+	async calculateGroupTNTAmountWithProgress(
+		input: GroupCalculationInput,
+		onProgress: (progress: SolveProgress) => void,
+	): Promise<TNTSearch> {
+		const wasm = (await import(
+			"pearl_calculator_wasm"
+		)) as unknown as Promise<PearlCalculatorWasm>;
+		this.cancelled = false;
+		return (await wasm).calculate_group_tnt_amount_with_progress(
+			input,
+			(progress) => {
+				onProgress(progress);
+				return !this.cancelled;
+			},
+		);
+	}
+
 	async calculatePearlTrace(input: PearlTraceInput): Promise<PearlTraceResult> {
 		const wasm = (await import(
 			"pearl_calculator_wasm"
@@ -34,6 +132,84 @@ export class WebCalculatorService implements ICalculatorService {
 		return (await wasm).calculate_raw_trace(input);
 	}
 
+	async calculateChunkReport(input: PearlTraceInput): Promise<ChunkReport> {
+		const wasm = (await import(
+			"pearl_calculator_wasm"
+		)) as unknown as Promise<PearlCalculatorWasm>;
+		return (await wasm).calculate_chunk_report(input);
+	}
+
+	async analyseRobustness(input: RobustnessInput): Promise<RobustResult[]> {
+		const wasm = (await import(
+			"pearl_calculator_wasm"
+		)) as unknown as Promise<PearlCalculatorWasm>;
+		return (await wasm).analyse_robustness(input);
+	}
+
+	async buildLookupTable(input: LookupBuildInput): Promise<Uint8Array> {
+		const wasm = (await import(
+			"pearl_calculator_wasm"
+		)) as unknown as Promise<PearlCalculatorWasm>;
+		return (await wasm).build_lookup_table(input);
+	}
+
+	async loadLookupTable(bytes: Uint8Array): Promise<void> {
+		const wasm = (await import(
+			"pearl_calculator_wasm"
+		)) as unknown as Promise<PearlCalculatorWasm>;
+		const table = new (await wasm).LookupTable(bytes);
+		this.lookupTable?.free();
+		this.lookupTable = table;
+	}
+
+	async unloadLookupTable(): Promise<void> {
+		this.lookupTable?.free();
+		this.lookupTable = null;
+	}
+
+	private requireSimulator(): WasmSimulator {
+		if (!this.simulator) {
+			throw new Error("No simulator has been created");
+		}
+		return this.simulator;
+	}
+
+	async createSimulator(input: RawTraceInput): Promise<SimulatorState> {
+		const wasm = (await import(
+			"pearl_calculator_wasm"
+		)) as unknown as Promise<PearlCalculatorWasm>;
+		const simulator = new (await wasm).Simulator(input);
+		this.simulator?.free();
+		this.simulator = simulator;
+		return simulator.state();
+	}
+
+	async stepSimulator(ticks: number): Promise<SimulatorState> {
+		return this.requireSimulator().step(ticks);
+	}
+
+	async editSimulator(input: EntityEditInput): Promise<SimulatorState> {
+		return this.requireSimulator().edit(input);
+	}
+
+	async injectSimulatorExplosion(
+		input: ExplosionInput,
+	): Promise<SimulatorState> {
+		return this.requireSimulator().inject_explosion(input);
+	}
+
+	async addSimulatorTnt(group: TntGroupInput): Promise<SimulatorState> {
+		return this.requireSimulator().add_tnt(group);
+	}
+
+	async snapshotSimulator(): Promise<number> {
+		return this.requireSimulator().snapshot();
+	}
+
+	async restoreSimulator(id: number): Promise<SimulatorState> {
+		return this.requireSimulator().restore(id);
+	}
+
 	async copyToClipboard(text: string): Promise<void> {
 		await navigator.clipboard.writeText(text);
 	}
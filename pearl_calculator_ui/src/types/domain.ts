@@ -6,14 +6,19 @@ import type {
 	BitTemplateConfigSchema,
 	CalculatorInputsSchema,
 	CannonModeSchema,
+	ChunkReportSchema,
 	GeneralConfigSchema,
 	MaskGroupSchema,
 	MultiplierBitInputStateSchema,
 	MultiplierConfigSchema,
 	PearlTraceResultSchema,
 	PearlVersionSchema,
+	RobustResultSchema,
 	SimulatorConfigSchema,
+	SimulatorStateSchema,
+	TickRecordSchema,
 	TNTResultSchema,
+	TNTSearchSchema,
 	TraceTNTSchema,
 } from "@/lib/schemas";
 
@@ -23,8 +28,18 @@ export type CannonMode = z.infer<typeof CannonModeSchema>;
 
 export type TNTResult = z.infer<typeof TNTResultSchema>;
 
+export type TNTSearch = z.infer<typeof TNTSearchSchema>;
+
+export type RobustResult = z.infer<typeof RobustResultSchema>;
+
 export type PearlTraceResult = z.infer<typeof PearlTraceResultSchema>;
 
+export type ChunkReport = z.infer<typeof ChunkReportSchema>;
+
+export type TickRecord = z.infer<typeof TickRecordSchema>;
+
+export type SimulatorState = z.infer<typeof SimulatorStateSchema>;
+
 export type TraceTNT = z.infer<typeof TraceTNTSchema>;
 
 export type GeneralConfig = z.infer<typeof GeneralConfigSchema>;
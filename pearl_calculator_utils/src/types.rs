@@ -232,6 +232,8 @@ pub const fn default_distance_range() -> [u32; 2] {
 pub struct SimulatorTntGroup {
     pub pos: Vector3,
     pub amount: u32,
+    #[serde(default)]
+    pub fuse: u32,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
@@ -242,13 +244,38 @@ pub struct SimulatorPearlState {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", from = "SimulatorConfigRepr")]
 pub struct SimulatorConfig {
     pub pearl: SimulatorPearlState,
-    pub tnt_a: SimulatorTntGroup,
-    pub tnt_b: SimulatorTntGroup,
-    pub tnt_c: SimulatorTntGroup,
-    pub tnt_d: SimulatorTntGroup,
+    pub groups: Vec<SimulatorTntGroup>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimulatorConfigRepr {
+    pearl: SimulatorPearlState,
+    #[serde(default)]
+    groups: Vec<SimulatorTntGroup>,
+    // Saved before the simulator took any number of groups.
+    tnt_a: Option<SimulatorTntGroup>,
+    tnt_b: Option<SimulatorTntGroup>,
+    tnt_c: Option<SimulatorTntGroup>,
+    tnt_d: Option<SimulatorTntGroup>,
+}
+
+impl From<SimulatorConfigRepr> for SimulatorConfig {
+    fn from(repr: SimulatorConfigRepr) -> Self {
+        let mut groups = repr.groups;
+        groups.extend(
+            [repr.tnt_a, repr.tnt_b, repr.tnt_c, repr.tnt_d]
+                .into_iter()
+                .flatten(),
+        );
+        Self {
+            pearl: repr.pearl,
+            groups,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -1,5 +1,10 @@
 use pearl_calculator_bridge::api;
-use pearl_calculator_bridge::inputs::{CalculationInput, PearlTraceInput, RawTraceInput};
+use pearl_calculator_bridge::inputs::{
+    CalculationInput, EntityEditInput, ExplosionInput, GroupCalculationInput, LookupBuildInput,
+    PearlTraceInput, RawTraceInput, RobustnessInput, TntGroupInput,
+};
+use pearl_calculator_bridge::session::SimulatorSession;
+use pearl_calculator_core::calculation::progress::CancellationToken;
 use pearl_calculator_utils as utils;
 use wasm_bindgen::prelude::*;
 
@@ -10,6 +15,94 @@ pub fn calculate_tnt_amount(val: JsValue) -> Result<JsValue, JsError> {
     Ok(serde_wasm_bindgen::to_value(&results)?)
 }
 
+#[wasm_bindgen]
+pub fn calculate_group_tnt_amount(val: JsValue) -> Result<JsValue, JsError> {
+    let input: GroupCalculationInput = serde_wasm_bindgen::from_value(val)?;
+    let results = api::calculate_group_tnt_amount(input).map_err(|e| JsError::new(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&results)?)
+}
+
+/// Calls `on_progress` with each progress report; returning `false` from it cancels the solve.
+#[wasm_bindgen]
+pub fn calculate_tnt_amount_with_progress(
+    val: JsValue,
+    on_progress: &js_sys::Function,
+) -> Result<JsValue, JsError> {
+    let input: CalculationInput = serde_wasm_bindgen::from_value(val)?;
+    let cancel = CancellationToken::new();
+    let search = api::calculate_tnt_amount_with_progress(input, &cancel, &mut |progress| {
+        let Ok(progress) = serde_wasm_bindgen::to_value(progress) else {
+            return;
+        };
+        if on_progress
+            .call1(&JsValue::NULL, &progress)
+            .is_ok_and(|keep_going| keep_going == JsValue::FALSE)
+        {
+            cancel.cancel();
+        }
+    })
+    .map_err(|e| JsError::new(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&search)?)
+}
+
+/// Same as [`calculate_tnt_amount_with_progress`] for a cannon with named charge groups.
+#[wasm_bindgen]
+pub fn calculate_group_tnt_amount_with_progress(
+    val: JsValue,
+    on_progress: &js_sys::Function,
+) -> Result<JsValue, JsError> {
+    let input: GroupCalculationInput = serde_wasm_bindgen::from_value(val)?;
+    let cancel = CancellationToken::new();
+    let search = api::calculate_group_tnt_amount_with_progress(input, &cancel, &mut |progress| {
+        let Ok(progress) = serde_wasm_bindgen::to_value(progress) else {
+            return;
+        };
+        if on_progress
+            .call1(&JsValue::NULL, &progress)
+            .is_ok_and(|keep_going| keep_going == JsValue::FALSE)
+        {
+            cancel.cancel();
+        }
+    })
+    .map_err(|e| JsError::new(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&search)?)
+}
+
+#[wasm_bindgen]
+pub fn analyse_robustness(val: JsValue) -> Result<JsValue, JsError> {
+    let input: RobustnessInput = serde_wasm_bindgen::from_value(val)?;
+    let results = api::analyse_robustness(input).map_err(|e| JsError::new(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&results)?)
+}
+
+#[wasm_bindgen]
+pub fn build_lookup_table(val: JsValue) -> Result<Vec<u8>, JsError> {
+    let input: LookupBuildInput = serde_wasm_bindgen::from_value(val)?;
+    api::build_lookup_table(input).map_err(|e| JsError::new(&e))
+}
+
+/// A loaded lookup table that answers sampled destinations without solving.
+#[wasm_bindgen]
+pub struct LookupTable {
+    inner: pearl_calculator_core::calculation::lookup::LookupTable,
+}
+
+#[wasm_bindgen]
+impl LookupTable {
+    #[wasm_bindgen(constructor)]
+    pub fn new(bytes: &[u8]) -> Result<LookupTable, JsError> {
+        let inner = api::load_lookup_table(bytes).map_err(|e| JsError::new(&e))?;
+        Ok(Self { inner })
+    }
+
+    pub fn calculate_tnt_amount(&self, val: JsValue) -> Result<JsValue, JsError> {
+        let input: CalculationInput = serde_wasm_bindgen::from_value(val)?;
+        let results = api::calculate_tnt_amount_with_table(input, Some(&self.inner))
+            .map_err(|e| JsError::new(&e))?;
+        Ok(serde_wasm_bindgen::to_value(&results)?)
+    }
+}
+
 #[wasm_bindgen]
 pub fn calculate_pearl_trace(val: JsValue) -> Result<JsValue, JsError> {
     let input: PearlTraceInput = serde_wasm_bindgen::from_value(val)?;
@@ -17,6 +110,13 @@ pub fn calculate_pearl_trace(val: JsValue) -> Result<JsValue, JsError> {
     Ok(serde_wasm_bindgen::to_value(&result)?)
 }
 
+#[wasm_bindgen]
+pub fn calculate_chunk_report(val: JsValue) -> Result<JsValue, JsError> {
+    let input: PearlTraceInput = serde_wasm_bindgen::from_value(val)?;
+    let result = api::calculate_chunk_report(input).map_err(|e| JsError::new(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
 #[wasm_bindgen]
 pub fn calculate_raw_trace(val: JsValue) -> Result<JsValue, JsError> {
     let input: RawTraceInput = serde_wasm_bindgen::from_value(val)?;
@@ -24,6 +124,57 @@ pub fn calculate_raw_trace(val: JsValue) -> Result<JsValue, JsError> {
     Ok(serde_wasm_bindgen::to_value(&result)?)
 }
 
+/// A flight stepped tick by tick, for debugging a trace.
+#[wasm_bindgen]
+pub struct Simulator {
+    inner: SimulatorSession,
+}
+
+#[wasm_bindgen]
+impl Simulator {
+    #[wasm_bindgen(constructor)]
+    pub fn new(val: JsValue) -> Result<Simulator, JsError> {
+        let input: RawTraceInput = serde_wasm_bindgen::from_value(val)?;
+        let inner = SimulatorSession::new(input).map_err(|e| JsError::new(&e))?;
+        Ok(Self { inner })
+    }
+
+    pub fn state(&self) -> Result<JsValue, JsError> {
+        Ok(serde_wasm_bindgen::to_value(&self.inner.state())?)
+    }
+
+    pub fn step(&mut self, ticks: u32) -> Result<JsValue, JsError> {
+        Ok(serde_wasm_bindgen::to_value(&self.inner.step(ticks))?)
+    }
+
+    pub fn edit(&mut self, val: JsValue) -> Result<JsValue, JsError> {
+        let input: EntityEditInput = serde_wasm_bindgen::from_value(val)?;
+        let state = self.inner.edit(input).map_err(|e| JsError::new(&e))?;
+        Ok(serde_wasm_bindgen::to_value(&state)?)
+    }
+
+    pub fn inject_explosion(&mut self, val: JsValue) -> Result<JsValue, JsError> {
+        let input: ExplosionInput = serde_wasm_bindgen::from_value(val)?;
+        Ok(serde_wasm_bindgen::to_value(
+            &self.inner.inject_explosion(input),
+        )?)
+    }
+
+    pub fn add_tnt(&mut self, val: JsValue) -> Result<JsValue, JsError> {
+        let group: TntGroupInput = serde_wasm_bindgen::from_value(val)?;
+        Ok(serde_wasm_bindgen::to_value(&self.inner.add_tnt(group))?)
+    }
+
+    pub fn snapshot(&mut self) -> usize {
+        self.inner.snapshot()
+    }
+
+    pub fn restore(&mut self, id: usize) -> Result<JsValue, JsError> {
+        let state = self.inner.restore(id).map_err(|e| JsError::new(&e))?;
+        Ok(serde_wasm_bindgen::to_value(&state)?)
+    }
+}
+
 #[wasm_bindgen]
 pub fn parse_configuration_content(content: &str, path: &str) -> Result<JsValue, JsError> {
     let result =